@@ -0,0 +1,61 @@
+//! How long rendering takes per diagnostic, for thousands of random ones in
+//! each style and through the export of a whole report: `cargo bench`.
+
+use std::{
+    hint::black_box,
+    io,
+    time::{Duration, Instant},
+};
+
+use clap::{Parser, ValueEnum};
+use fastrand::Rng;
+use roost::{
+    ansi,
+    export::{self, ExportOptions},
+    random,
+    render::{self, Emitter, RenderOptions},
+};
+
+const COUNT: usize = 10_000;
+
+/// The options of `roost --color always` without any other flag.
+#[derive(Parser)]
+#[command(no_binary_name = true)]
+struct Options {
+    #[command(flatten)]
+    export: ExportOptions,
+    #[command(flatten)]
+    render: RenderOptions,
+}
+
+fn report(name: &str, elapsed: Duration) {
+    println!(
+        "{:<16} {:>8.2} µs/diagnostic",
+        name,
+        elapsed.as_secs_f64() * 1e6 / COUNT as f64
+    );
+}
+
+fn main() {
+    let mut rng = Rng::with_seed(0);
+    let errs: Vec<_> = (0..COUNT).map(|_| random::diagnostic(&mut rng)).collect();
+    ansi::set_enabled(true);
+
+    for &style in Emitter::value_variants() {
+        let options = RenderOptions {
+            style,
+            ..Default::default()
+        };
+        let start = Instant::now();
+        for err in &errs {
+            black_box(render::render(&options, err));
+        }
+        report(&format!("{:?}", style), start.elapsed());
+    }
+
+    let options = Options::parse_from(["--color", "always"]);
+    let start = Instant::now();
+    export::write(&options.export, &options.render, &errs, io::sink())
+        .expect("the sink takes everything");
+    report("export text", start.elapsed());
+}
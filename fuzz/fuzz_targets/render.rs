@@ -0,0 +1,57 @@
+//! Renders arbitrary diagnostics in every style, checking that none panics
+//! and that the carets of rustc's style sit under the span.
+
+#![no_main]
+
+use clap::ValueEnum;
+use libfuzzer_sys::fuzz_target;
+use roost::{
+    ansi,
+    render::{self, Emitter, Hyperlinks, RenderOptions},
+    Diagnostic,
+};
+use unicode_width::UnicodeWidthStr;
+
+/// Checks that the row under the quoted line of `err` marks its span with
+/// carets, from the column of the span and as wide as it.
+fn check_carets(err: &Diagnostic, rendered: &str) {
+    let gutter = format!(
+        "{:>width$} | ",
+        err.lineno(),
+        width = render::lineno_width(err)
+    );
+    let quoted = format!("{}{}", gutter, err.line());
+    let mut rows = rendered.lines();
+    rows.find(|row| row.trim_end() == quoted.trim_end())
+        .expect("the line is quoted");
+    let marks = rows.next().expect("the marks are under the line");
+
+    let marks = &marks[gutter.len().min(marks.len())..];
+    let indent = marks.len() - marks.trim_start_matches(' ').len();
+    let carets = marks[indent..].len() - marks[indent..].trim_start_matches('^').len();
+    assert_eq!(
+        (marks[..indent].width(), carets),
+        (err.span_indent(), err.span_width()),
+        "misaligned carets in\n{}",
+        rendered
+    );
+}
+
+fuzz_target!(|input: (Diagnostic<'static>, Option<u16>)| {
+    let (err, max_width) = input;
+    ansi::set_enabled(false);
+
+    for &style in Emitter::value_variants() {
+        let options = RenderOptions {
+            style,
+            hyperlinks: Hyperlinks::Never,
+            max_width: max_width.map(|width| width.max(16)),
+            ..Default::default()
+        };
+        let rendered = render::render(&options, &err);
+        // The labels may cover the span, and a narrow width cuts the line
+        if style == Emitter::Rustc && err.labels.is_empty() && max_width.is_none() {
+            check_carets(&err, &rendered);
+        }
+    }
+});
@@ -0,0 +1,57 @@
+//! The `--animate` output: the diagnostic typed out character by character.
+
+use std::{
+    io::{self, Write},
+    thread,
+    time::Duration,
+};
+
+/// Characters typed per second when `--animate` is given without a speed.
+pub const DEFAULT_CPS: &str = "40";
+
+/// The length of the escape sequence `text` starts with, a CSI sequence
+/// such as a color or an OSC one such as a hyperlink, or 0.
+fn escape_len(text: &str) -> usize {
+    let mut chars = text.char_indices();
+    if chars.next().map(|(_, c)| c) != Some('\x1b') {
+        return 0;
+    }
+    match chars.next() {
+        Some((_, '[')) => chars
+            .find(|(_, c)| ('\x40'..='\x7e').contains(c))
+            .map_or(text.len(), |(index, c)| index + c.len_utf8()),
+        // Ended by BEL or by ESC \
+        Some((_, ']')) => chars
+            .find(|&(index, c)| c == '\x07' || text[index..].starts_with("\x1b\\"))
+            .map_or(text.len(), |(index, c)| match c {
+                '\x07' => index + 1,
+                _ => index + 2,
+            }),
+        Some((index, c)) => index + c.len_utf8(),
+        None => text.len(),
+    }
+}
+
+/// Writes `text` to `output` a character at a time, `cps` of them a second
+/// give or take half the delay, the escape sequences at once.
+pub fn write(output: &mut impl Write, text: &str, cps: u32) -> io::Result<()> {
+    let mut rng = fastrand::Rng::new();
+    let delay = 1.0 / f64::from(cps);
+
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        let len = match escape_len(rest) {
+            0 => c.len_utf8(),
+            len => len,
+        };
+        output.write_all(&rest.as_bytes()[..len])?;
+        rest = &rest[len..];
+        if c == '\x1b' {
+            continue;
+        }
+        output.flush()?;
+        thread::sleep(Duration::from_secs_f64(delay * (0.5 + rng.f64())));
+    }
+
+    Ok(())
+}
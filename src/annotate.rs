@@ -0,0 +1,124 @@
+use std::{
+    io::{self, Read},
+    ops::Range,
+};
+
+use roost::{
+    position::{self, PositionUnit},
+    source::SourceMap,
+    Diagnostic, RoostError, DEFAULT_ERRNUM, DEFAULT_PATH,
+};
+
+#[derive(clap::Args, Debug)]
+pub struct AnnotateArgs {
+    /// Line of the piped source to annotate (1-based)
+    #[arg(long)]
+    pub line: usize,
+    /// Span on the line, as `start..end`, `start..=last` or `start+length`
+    /// (0-based)
+    #[arg(long, value_parser = parse_columns)]
+    pub cols: Range<usize>,
+    /// Label under the span
+    #[arg(long, default_value = "")]
+    pub message: String,
+    /// Headline of the error, the message if omitted
+    #[arg(long)]
+    pub summary: Option<String>,
+    #[arg(long, default_value = DEFAULT_PATH)]
+    pub path: String,
+    #[arg(long, default_value_t = DEFAULT_ERRNUM)]
+    pub errnum: usize,
+    /// Number of lines shown around the annotated one
+    #[arg(long, default_value_t = 0)]
+    pub context: usize,
+}
+
+fn parse_columns(raw: &str) -> Result<Range<usize>, RoostError> {
+    let range = position::parse_range(raw)?;
+    match range.is_empty() {
+        true => Err(RoostError::ValueError {
+            details: format!("the columns '{}' are empty", raw),
+        }),
+        false => Ok(range),
+    }
+}
+
+/// Annotates the piped source, `--cols` counting `unit`.
+pub fn run(args: &AnnotateArgs, unit: PositionUnit) -> Result<Diagnostic<'static>, RoostError> {
+    let mut source = String::new();
+    io::stdin()
+        .read_to_string(&mut source)
+        .map_err(|err| RoostError::ValueError {
+            details: format!("could not read standard input: {}", err),
+        })?;
+    let lines: Vec<&str> = source.lines().collect();
+
+    let index = args
+        .line
+        .checked_sub(1)
+        .filter(|&index| index < lines.len())
+        .ok_or_else(|| RoostError::ValueError {
+            details: format!(
+                "line {} is out of the piped source ({} lines)",
+                args.line,
+                lines.len()
+            ),
+        })?;
+    let line = lines[index];
+    let cols = match unit {
+        PositionUnit::Bytes => args.cols.clone(),
+        _ => unit.to_bytes(line, args.cols.clone()),
+    };
+    // Columns starting past the last character point at the end of the line
+    let cols = match cols.start == line.len() {
+        true => cols.start..cols.start,
+        false => cols,
+    };
+
+    if (cols.is_empty() && cols.start != line.len())
+        || cols.end > line.len()
+        || !line.is_char_boundary(cols.start)
+        || !line.is_char_boundary(cols.end)
+    {
+        return Err(RoostError::ValueError {
+            details: format!(
+                "columns {}..{} are out of line {} ({} bytes)",
+                args.cols.start,
+                args.cols.end,
+                args.line,
+                line.len()
+            ),
+        });
+    }
+
+    let context_start = index.saturating_sub(args.context);
+    let context_end = (index + 1 + args.context).min(lines.len());
+
+    let to_owned = |lines: &[&str]| {
+        lines
+            .iter()
+            .map(|line| line.to_string())
+            .collect::<Vec<_>>()
+    };
+    let (sources, span) = SourceMap::quote(
+        args.path.clone(),
+        args.line,
+        line,
+        cols,
+        &to_owned(&lines[context_start..index]),
+        &to_owned(&lines[index + 1..context_end]),
+    );
+
+    Ok(Diagnostic {
+        summary: args
+            .summary
+            .clone()
+            .unwrap_or_else(|| args.message.clone())
+            .into(),
+        message: args.message.clone().into(),
+        span,
+        sources,
+        errnum: args.errnum,
+        ..Default::default()
+    })
+}
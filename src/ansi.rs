@@ -0,0 +1,367 @@
+use std::{
+    env,
+    io::{self, IsTerminal},
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use clap::ValueEnum;
+use unicode_width::UnicodeWidthChar;
+
+pub const RESET: &str = "\x1b[0m";
+
+/// Whether `bold()` and `color()` emit escape sequences at all.
+static ENABLED: AtomicBool = AtomicBool::new(true);
+
+/// A foreground color, as set by an SGR escape sequence.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "fuzzing", derive(arbitrary::Arbitrary))]
+pub enum Color {
+    /// One of the 8 basic colors (`30`-`37`)
+    Basic(u8),
+    /// The bright variant of a basic color (`90`-`97`)
+    Bright(u8),
+    /// One of the 256 indexed colors (`38;5;n`)
+    Fixed(u8),
+    /// A 24-bit color (`38;2;r;g;b`)
+    Rgb(Rgb),
+}
+
+pub type Rgb = [u8; 3];
+
+pub const BACKGROUND: Rgb = [0x1e, 0x1e, 0x1e];
+pub const FOREGROUND: Rgb = [0xd4, 0xd4, 0xd4];
+
+const PALETTE: [Rgb; 8] = [
+    [0x00, 0x00, 0x00],
+    [0xcd, 0x31, 0x31],
+    [0x0d, 0xbc, 0x79],
+    [0xe5, 0xe5, 0x10],
+    [0x24, 0x72, 0xc8],
+    [0xbc, 0x3f, 0xbc],
+    [0x11, 0xa8, 0xcd],
+    [0xe5, 0xe5, 0xe5],
+];
+
+const BRIGHT_PALETTE: [Rgb; 8] = [
+    [0x66, 0x66, 0x66],
+    [0xf1, 0x4c, 0x4c],
+    [0x23, 0xd1, 0x8b],
+    [0xf5, 0xf5, 0x43],
+    [0x3b, 0x8e, 0xea],
+    [0xd6, 0x70, 0xd6],
+    [0x29, 0xb8, 0xdb],
+    [0xff, 0xff, 0xff],
+];
+
+/// The RGB value of one of the 256 indexed colors: the 16 basic and bright
+/// colors, a 6x6x6 cube, then a grayscale ramp.
+pub fn fixed_rgb(index: u8) -> Rgb {
+    let level = |value: u8| match value {
+        0 => 0,
+        _ => 55 + value * 40,
+    };
+
+    match index {
+        0..=7 => PALETTE[index as usize],
+        8..=15 => BRIGHT_PALETTE[index as usize - 8],
+        16..=231 => {
+            let index = index - 16;
+            [level(index / 36), level(index / 6 % 6), level(index % 6)]
+        }
+        232..=255 => [8 + (index - 232) * 10; 3],
+    }
+}
+
+/// Color of the text in the images and web pages roost draws.
+pub fn rgb(color: Option<Color>) -> Rgb {
+    match color {
+        Some(Color::Basic(code)) => PALETTE[code as usize % 8],
+        Some(Color::Bright(code)) => BRIGHT_PALETTE[code as usize % 8],
+        Some(Color::Fixed(index)) => fixed_rgb(index),
+        Some(Color::Rgb(rgb)) => rgb,
+        None => FOREGROUND,
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Attrs {
+    pub fg: Option<Color>,
+    pub bold: bool,
+    pub underline: bool,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Cell {
+    pub ch: char,
+    pub attrs: Attrs,
+}
+
+/// Reads the color of a `38;5;n` or `38;2;r;g;b` sequence, past the `38`.
+fn extended_color<'a>(params: &mut impl Iterator<Item = &'a str>) -> Option<Color> {
+    let mut number = || params.next().and_then(|param| param.parse().ok());
+
+    match number()? {
+        5 => number().map(Color::Fixed),
+        2 => Some(Color::Rgb([number()?, number()?, number()?])),
+        _ => None,
+    }
+}
+
+fn apply_sgr(attrs: &mut Attrs, params: &str) {
+    let mut params = params.split(';');
+
+    while let Some(param) = params.next() {
+        // Sub-parameters, as in `4:3` (curly underline), follow a colon.
+        let mut subparams = param.split(':');
+        let code: u8 = subparams
+            .next()
+            .and_then(|code| code.parse().ok())
+            .unwrap_or(0);
+
+        match code {
+            0 => *attrs = Attrs::default(),
+            1 => attrs.bold = true,
+            4 => attrs.underline = subparams.next() != Some("0"),
+            22 => attrs.bold = false,
+            24 => attrs.underline = false,
+            30..=37 => attrs.fg = Some(Color::Basic(code - 30)),
+            38 => attrs.fg = extended_color(&mut params).or(attrs.fg),
+            39 => attrs.fg = None,
+            // The underline color follows the text color in the images.
+            58 => {
+                extended_color(&mut params);
+            }
+            90..=97 => attrs.fg = Some(Color::Bright(code - 90)),
+            _ => {}
+        }
+    }
+}
+
+/// Splits a rendered diagnostic into lines of styled cells, interpreting
+/// the SGR escape sequences emitted by `bold()` and `color()`.
+pub fn parse(text: &str) -> Vec<Vec<Cell>> {
+    let mut lines = vec![Vec::new()];
+    let mut attrs = Attrs::default();
+    let mut chars = text.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\x1b' if chars.peek() == Some(&']') => {
+                // Operating system commands (e.g. OSC 8 hyperlinks) carry no
+                // style: skip them up to their BEL or ST terminator.
+                while let Some(c) = chars.next() {
+                    if c == '\x07' || (c == '\x1b' && chars.next_if_eq(&'\\').is_some()) {
+                        break;
+                    }
+                }
+            }
+            '\x1b' if chars.peek() == Some(&'[') => {
+                chars.next();
+                let mut params = String::new();
+                for c in chars.by_ref() {
+                    if c.is_ascii_alphabetic() {
+                        if c == 'm' {
+                            apply_sgr(&mut attrs, &params);
+                        }
+                        break;
+                    }
+                    params.push(c);
+                }
+            }
+            '\n' => lines.push(Vec::new()),
+            _ => lines
+                .last_mut()
+                .expect("there is always a current line")
+                .push(Cell { ch, attrs }),
+        }
+    }
+
+    if lines.last().is_some_and(|line| line.is_empty()) {
+        lines.pop();
+    }
+
+    lines
+}
+
+/// Removes every escape sequence from `text`, keeping the line structure.
+pub fn strip(text: &str) -> String {
+    parse(text)
+        .iter()
+        .map(|line| line.iter().map(|cell| cell.ch).collect::<String>() + "\n")
+        .collect()
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum ColorChoice {
+    /// Only when writing to a terminal, following the `NO_COLOR` and
+    /// `CLICOLOR_FORCE` environment variables, the latter winning
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorChoice {
+    pub fn enabled(self, to_terminal: bool) -> bool {
+        let forced =
+            env::var_os("CLICOLOR_FORCE").is_some_and(|value| !value.is_empty() && value != "0");
+        let disabled = env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+
+        match self {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => forced || (to_terminal && !disabled),
+        }
+    }
+}
+
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+pub fn enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Whether the terminal is known to draw curly, colored underlines.
+pub fn supports_undercurl() -> bool {
+    let var = |name: &str| env::var(name).unwrap_or_default();
+
+    matches!(
+        var("TERM_PROGRAM").as_str(),
+        "iTerm.app" | "WezTerm" | "vscode" | "ghostty"
+    ) || var("VTE_VERSION")
+        .parse::<u32>()
+        .is_ok_and(|version| version >= 5102)
+        || ["KITTY_WINDOW_ID", "KONSOLE_VERSION"]
+            .iter()
+            .any(|name| env::var_os(name).is_some())
+}
+
+/// Wraps `text` in an OSC 8 hyperlink to `url`.
+pub fn hyperlink(url: &str, text: &str) -> String {
+    format!("\x1b]8;;{}\x1b\\{}\x1b]8;;\x1b\\", url, text)
+}
+
+/// Whether the terminal roost runs in is known to support OSC 8 hyperlinks.
+pub fn supports_hyperlinks() -> bool {
+    let var = |name: &str| env::var(name).unwrap_or_default();
+
+    io::stdout().is_terminal()
+        && (matches!(
+            var("TERM_PROGRAM").as_str(),
+            "iTerm.app" | "WezTerm" | "vscode" | "ghostty" | "Hyper"
+        ) || var("VTE_VERSION")
+            .parse::<u32>()
+            .is_ok_and(|version| version >= 5000)
+            || [
+                "WT_SESSION",
+                "KITTY_WINDOW_ID",
+                "KONSOLE_VERSION",
+                "DOMTERM",
+            ]
+            .iter()
+            .any(|name| env::var_os(name).is_some()))
+}
+
+/// Breaks the lines of `text` longer than `width` visible characters, keeping
+/// the escape sequences out of the count.
+pub fn wrap(text: &str, width: usize) -> String {
+    let mut wrapped = String::new();
+    let mut column = 0;
+    let mut chars = text.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\x1b' => {
+                wrapped.push(ch);
+                let osc = chars.peek() == Some(&']');
+                for c in chars.by_ref() {
+                    wrapped.push(c);
+                    let terminated = match osc {
+                        true => c == '\x07' || (c == '\\' && wrapped.ends_with("\x1b\\")),
+                        false => c.is_ascii_alphabetic() && c != '[',
+                    };
+                    if terminated {
+                        break;
+                    }
+                }
+            }
+            '\n' => {
+                wrapped.push(ch);
+                column = 0;
+            }
+            // Spaces at the edge are dropped rather than wrapped
+            ' ' if column >= width => {}
+            // Asks for the emoji presentation of the symbol before, two
+            // columns wide rather than one
+            '\u{fe0f}' => {
+                wrapped.push(ch);
+                column += 1;
+            }
+            _ => {
+                let ch_width = ch.width().unwrap_or(0);
+                if column > 0 && column + ch_width > width {
+                    wrapped.push('\n');
+                    column = 0;
+                }
+                wrapped.push(ch);
+                column += ch_width;
+            }
+        }
+    }
+
+    wrapped
+}
+
+/// Splits a line into runs of cells sharing the same attributes.
+pub fn runs(line: &[Cell]) -> Vec<(Attrs, String)> {
+    let mut runs: Vec<(Attrs, String)> = Vec::new();
+
+    for cell in line {
+        match runs.last_mut() {
+            Some((attrs, text)) if *attrs == cell.attrs => text.push(cell.ch),
+            _ => runs.push((cell.attrs, cell.ch.to_string())),
+        }
+    }
+
+    runs
+}
+
+pub fn hex(rgb: Rgb) -> String {
+    format!("#{:02x}{:02x}{:02x}", rgb[0], rgb[1], rgb[2])
+}
+
+pub fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strip_keeps_the_text_and_the_lines() {
+        let text = "\x1b[1m\x1b[31merror\x1b[0m: a\n\x1b]8;;file:///a.rs\x1b\\a.rs\x1b]8;;\x1b\\\n";
+
+        assert_eq!(strip(text), "error: a\na.rs\n");
+        assert_eq!(strip("plain"), "plain\n");
+    }
+
+    #[test]
+    fn wrap_counts_visible_columns() {
+        assert_eq!(wrap("abcdef", 4), "abcd\nef");
+        assert_eq!(wrap("\x1b[1mabcd\x1b[0mef", 4), "\x1b[1mabcd\x1b[0m\nef");
+        assert_eq!(wrap("日本語", 4), "日本\n語");
+        assert_eq!(wrap("ab\ncdef", 4), "ab\ncdef");
+    }
+
+    #[test]
+    fn wrap_drops_the_spaces_at_the_edge() {
+        assert_eq!(wrap("abcd efg", 4), "abcd\nefg");
+    }
+}
@@ -0,0 +1,237 @@
+use std::ops::Range;
+
+use crate::{
+    source::SourceMap, Diagnostic, Label, RoostError, Severity, DEFAULT_ERRNUM, DEFAULT_LINENO,
+    DEFAULT_PATH,
+};
+
+/// Builds a [`Diagnostic`], checking the fields the prompts would refuse
+/// only once [`DiagnosticBuilder::build`] is called.
+#[derive(Clone, Debug)]
+pub struct DiagnosticBuilder {
+    severity: Severity,
+    summary: Option<String>,
+    line: Option<String>,
+    span: Option<Range<usize>>,
+    labels: Vec<Label>,
+    message: String,
+    lineno: usize,
+    path: String,
+    code: usize,
+    fixit: Option<String>,
+    fixit_diff: bool,
+    context_before: Vec<String>,
+    context_after: Vec<String>,
+    related: Vec<Diagnostic<'static>>,
+    notes: Vec<String>,
+    helps: Vec<String>,
+}
+
+impl Default for DiagnosticBuilder {
+    fn default() -> Self {
+        DiagnosticBuilder {
+            severity: Severity::Error,
+            summary: None,
+            line: None,
+            span: None,
+            labels: Vec::new(),
+            message: String::new(),
+            lineno: DEFAULT_LINENO,
+            path: DEFAULT_PATH.to_owned(),
+            code: DEFAULT_ERRNUM,
+            fixit: None,
+            fixit_diff: false,
+            context_before: Vec::new(),
+            context_after: Vec::new(),
+            related: Vec::new(),
+            notes: Vec::new(),
+            helps: Vec::new(),
+        }
+    }
+}
+
+impl DiagnosticBuilder {
+    /// Whether it is an error, the default, or a warning.
+    pub fn severity(mut self, severity: Severity) -> Self {
+        self.severity = severity;
+        self
+    }
+
+    /// Headline of the error, after `error[E0069]:`.
+    pub fn summary(mut self, summary: impl Into<String>) -> Self {
+        self.summary = Some(summary.into());
+        self
+    }
+
+    /// Source line the error points at.
+    pub fn line(mut self, line: impl Into<String>) -> Self {
+        self.line = Some(line.into());
+        self
+    }
+
+    /// Byte range of the span in the line, which is empty only when it
+    /// points just past the end of the line.
+    pub fn span(mut self, span: Range<usize>) -> Self {
+        self.span = Some(span);
+        self
+    }
+
+    /// Another span of the line, marked and labelled on its own.
+    pub fn label(mut self, label: Label) -> Self {
+        self.labels.push(label);
+        self
+    }
+
+    /// Label of the span.
+    pub fn message(mut self, message: impl Into<String>) -> Self {
+        self.message = message.into();
+        self
+    }
+
+    /// Number of the line in the file, 1 by default.
+    pub fn lineno(mut self, lineno: usize) -> Self {
+        self.lineno = lineno;
+        self
+    }
+
+    /// File the line belongs to, `<stdin>` by default.
+    pub fn path(mut self, path: impl Into<String>) -> Self {
+        self.path = path.into();
+        self
+    }
+
+    /// Number of the error code, 69 by default.
+    pub fn code(mut self, code: usize) -> Self {
+        self.code = code;
+        self
+    }
+
+    /// Replacement text suggested for the span.
+    pub fn fixit(mut self, fixit: impl Into<String>) -> Self {
+        self.fixit = Some(fixit.into());
+        self
+    }
+
+    /// Shows the fix-it as the line before and after it, in a diff.
+    pub fn fixit_diff(mut self, diff: bool) -> Self {
+        self.fixit_diff = diff;
+        self
+    }
+
+    /// Lines shown before the one of the span, fewer than its line number.
+    pub fn context_before(mut self, lines: Vec<String>) -> Self {
+        self.context_before = lines;
+        self
+    }
+
+    /// Lines shown after the one of the span.
+    pub fn context_after(mut self, lines: Vec<String>) -> Self {
+        self.context_after = lines;
+        self
+    }
+
+    /// Another location quoted after the span, under a `note:` with the
+    /// summary of `related`.
+    pub fn related(mut self, related: Diagnostic<'static>) -> Self {
+        self.related.push(related);
+        self
+    }
+
+    /// A remark after the quoted code, such as rustc's `= note:` lines.
+    pub fn note(mut self, note: impl Into<String>) -> Self {
+        self.notes.push(note.into());
+        self
+    }
+
+    /// Advice after the notes, such as rustc's `= help:` lines.
+    pub fn help(mut self, help: impl Into<String>) -> Self {
+        self.helps.push(help.into());
+        self
+    }
+
+    /// Checks that the summary, the line and a span within it were given,
+    /// and that the context lines fit before the line number.
+    pub fn build(self) -> Result<Diagnostic<'static>, RoostError> {
+        let missing = |field: &str| RoostError::ValueError {
+            details: format!("missing {}", field),
+        };
+        let summary = self.summary.ok_or_else(|| missing("summary"))?;
+        let line = self.line.ok_or_else(|| missing("line"))?;
+        let span = self.span.ok_or_else(|| missing("span"))?;
+
+        if summary.is_empty() {
+            return Err(RoostError::ValueError {
+                details: "the summary cannot be empty".to_owned(),
+            });
+        }
+
+        // A line break would move the span onto another line
+        if let Some(multiline) = [&line]
+            .into_iter()
+            .chain(&self.context_before)
+            .chain(&self.context_after)
+            .find(|line| line.contains(['\n', '\r']))
+        {
+            return Err(RoostError::ValueError {
+                details: format!("the line {:?} cannot hold a line break", multiline),
+            });
+        }
+
+        let in_line = |pos: usize| pos <= line.len() && line.is_char_boundary(pos);
+        let past_end = span.start == line.len() && span.end == line.len();
+        let invalid = |kind: &str, range: &Range<usize>| RoostError::ValueError {
+            details: format!(
+                "invalid {} {}..{} for a line of {} bytes",
+                kind,
+                range.start,
+                range.end,
+                line.len()
+            ),
+        };
+        if (span.start >= span.end && !past_end) || !in_line(span.start) || !in_line(span.end) {
+            return Err(invalid("span", &span));
+        }
+        if self.context_before.len() >= self.lineno {
+            return Err(RoostError::ValueError {
+                details: format!(
+                    "{} lines of context cannot come before line {}",
+                    self.context_before.len(),
+                    self.lineno
+                ),
+            });
+        }
+        if let Some(label) = self.labels.iter().find(|label| {
+            label.range.start >= label.range.end
+                || !in_line(label.range.start)
+                || !in_line(label.range.end)
+        }) {
+            return Err(invalid("label span", &label.range));
+        }
+
+        let (sources, span) = SourceMap::quote(
+            self.path,
+            self.lineno,
+            &line,
+            span,
+            &self.context_before,
+            &self.context_after,
+        );
+
+        Ok(Diagnostic {
+            severity: self.severity,
+            summary: summary.into(),
+            message: self.message.into(),
+            span,
+            labels: self.labels,
+            sources,
+            errnum: self.code,
+            code: None,
+            fixit: self.fixit,
+            fixit_diff: self.fixit_diff,
+            related: self.related,
+            notes: self.notes,
+            helps: self.helps,
+            column_offset: 0,
+        })
+    }
+}
@@ -0,0 +1,181 @@
+//! The `buildlog` subcommand: diagnostics wrapped in the transcript of a
+//! `cargo build` of a made-up crate.
+
+use std::{env, path::PathBuf};
+
+use clap::ValueEnum;
+use fastrand::Rng;
+
+use roost::{
+    bold, color, random,
+    render::{self, counted, rustc, RenderOptions},
+    spec, Diagnostic, RoostError, Severity,
+};
+
+/// Dependencies compiled before the crate, in the order cargo often builds
+/// them.
+const DEPENDENCIES: &[&str] = &[
+    "proc-macro2 v1.0.86",
+    "unicode-ident v1.0.12",
+    "libc v0.2.158",
+    "cfg-if v1.0.0",
+    "memchr v2.7.4",
+    "quote v1.0.37",
+    "syn v2.0.77",
+    "serde v1.0.210",
+    "serde_derive v1.0.210",
+    "itoa v1.0.11",
+    "ryu v1.0.18",
+    "serde_json v1.0.128",
+    "log v0.4.22",
+    "once_cell v1.19.0",
+    "regex-syntax v0.8.4",
+    "aho-corasick v1.1.3",
+    "regex-automata v0.4.7",
+    "regex v1.10.6",
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum TargetKind {
+    Bin,
+    Lib,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct BuildlogArgs {
+    /// TOML or JSON spec file of diagnostics; can be given several times
+    #[arg(long, value_name = "FILE")]
+    pub spec: Vec<PathBuf>,
+    /// Number of random diagnostics, when no spec is given
+    #[arg(long, default_value_t = 2, conflicts_with = "spec")]
+    pub count: usize,
+    /// Seed of the random diagnostics and build times
+    #[arg(long)]
+    pub seed: Option<u64>,
+    /// Name of the crate that fails to compile
+    #[arg(long, default_value = "foo")]
+    pub crate_name: String,
+    #[arg(long, default_value = "0.1.0")]
+    pub crate_version: String,
+    /// Kind of the target of the crate
+    #[arg(long, value_enum, default_value_t = TargetKind::Bin)]
+    pub target: TargetKind,
+    /// Number of dependencies compiled before the crate
+    #[arg(long, default_value_t = 6)]
+    pub deps: usize,
+}
+
+/// A `cargo build` status line, such as `   Compiling foo v0.1.0`.
+fn status(verb: &str, text: &str) -> String {
+    format!("{} {}\n", bold(color(format!("{:>12}", verb), 2)), text)
+}
+
+fn rng(args: &BuildlogArgs) -> Rng {
+    match args.seed {
+        Some(seed) => Rng::with_seed(seed),
+        None => Rng::new(),
+    }
+}
+
+/// The diagnostics of `args`: read from its specs, or else made up.
+pub fn diagnostics(args: &BuildlogArgs) -> Result<Vec<Diagnostic<'static>>, RoostError> {
+    if args.spec.is_empty() {
+        let mut rng = rng(args);
+        return Ok((0..args.count)
+            .map(|_| random::diagnostic(&mut rng))
+            .collect());
+    }
+
+    let mut errs = Vec::new();
+    for path in &args.spec {
+        errs.extend(spec::from_file(path, None)?);
+    }
+    Ok(errs)
+}
+
+/// The transcript of the build printing `errs`, as cargo prints it: the
+/// dependencies and the crate compiling, the diagnostics, then the line
+/// telling the build failed, or finished when there are only warnings.
+pub fn render(args: &BuildlogArgs, options: &RenderOptions, errs: &[Diagnostic]) -> String {
+    let mut rng = rng(args);
+
+    let mut log = String::new();
+    for dependency in DEPENDENCIES.iter().take(args.deps) {
+        log.push_str(&status("Compiling", dependency));
+    }
+    let root = env::current_dir()
+        .map(|dir| dir.join(&args.crate_name))
+        .unwrap_or_else(|_| PathBuf::from(&args.crate_name));
+    log.push_str(&status(
+        "Compiling",
+        &format!(
+            "{} v{} ({})",
+            args.crate_name,
+            args.crate_version,
+            root.display()
+        ),
+    ));
+
+    for err in errs {
+        log.push_str(&render::render(options, err));
+        log.push('\n');
+    }
+
+    let errors: Vec<&Diagnostic> = errs.iter().filter(|err| err.severity.is_error()).collect();
+    let warnings = errs.len() - errors.len();
+    let theme = options.theme.unwrap_or(rustc::THEME);
+    let line = |severity: Severity, text: String| {
+        format!(
+            "{}{}\n",
+            theme
+                .for_severity(severity)
+                .header
+                .paint(severity.to_string()),
+            bold(format!(": {}", text))
+        )
+    };
+    let target = match args.target {
+        TargetKind::Bin => format!("(bin \"{}\")", args.crate_name),
+        TargetKind::Lib => "(lib)".to_owned(),
+    };
+
+    if errors.is_empty() {
+        if warnings > 0 {
+            log.push_str(&line(
+                Severity::Warning,
+                format!(
+                    "`{}` {} generated {}",
+                    args.crate_name,
+                    target,
+                    counted(warnings, "warning")
+                ),
+            ));
+        }
+        log.push_str(&status(
+            "Finished",
+            &format!(
+                "`dev` profile [unoptimized + debuginfo] target(s) in {:.2}s",
+                1.0 + rng.f64() * 20.0
+            ),
+        ));
+        return log;
+    }
+
+    log.push_str(&render::explain_hint(errs).unwrap_or_default());
+    let also = match warnings {
+        0 => String::new(),
+        _ => format!("; {} emitted", counted(warnings, "warning")),
+    };
+    log.push_str(&line(
+        Severity::Error,
+        format!(
+            "could not compile `{}` {} due to {}{}",
+            args.crate_name,
+            target,
+            counted(errors.len(), "previous error"),
+            also
+        ),
+    ));
+
+    log
+}
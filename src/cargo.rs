@@ -0,0 +1,171 @@
+use std::{
+    env,
+    ffi::OsString,
+    fs,
+    path::{Path, PathBuf},
+    process,
+};
+
+use serde::Deserialize;
+
+use crate::{
+    prompt::{field, int_factory},
+    selection::Prefill,
+    RoostError,
+};
+
+const SUBCOMMAND_BIN: &str = "cargo-roost";
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct Target {
+    pub name: String,
+    pub kind: Vec<String>,
+    pub crate_types: Vec<String>,
+    pub src_path: PathBuf,
+    pub edition: String,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct Package {
+    pub id: String,
+    pub name: String,
+    pub manifest_path: PathBuf,
+    pub targets: Vec<Target>,
+}
+
+#[derive(Deserialize)]
+struct Metadata {
+    packages: Vec<Package>,
+    workspace_members: Vec<String>,
+    workspace_root: PathBuf,
+}
+
+pub struct Workspace {
+    pub root: PathBuf,
+    pub package: Package,
+}
+
+/// Whether roost runs as `cargo roost`, i.e. as a `cargo-roost` binary.
+pub fn is_subcommand() -> bool {
+    env::args_os()
+        .next()
+        .map(PathBuf::from)
+        .and_then(|path| path.file_stem().map(|stem| stem == SUBCOMMAND_BIN))
+        .unwrap_or(false)
+}
+
+/// The command line arguments, without the `roost` cargo inserts when
+/// running `cargo roost`.
+pub fn args() -> Vec<OsString> {
+    let mut args: Vec<OsString> = env::args_os().collect();
+
+    if is_subcommand() && args.get(1).is_some_and(|arg| arg == "roost") {
+        args.remove(1);
+    }
+
+    args
+}
+
+impl Workspace {
+    /// Finds the workspace around the current directory with `cargo
+    /// metadata`, picking the package that contains it.
+    pub fn load() -> Result<Workspace, RoostError> {
+        let invalid = |details: String| RoostError::ImportError { details };
+
+        let output = process::Command::new(env::var_os("CARGO").unwrap_or("cargo".into()))
+            .args(["metadata", "--no-deps", "--format-version", "1"])
+            .output()
+            .map_err(|err| invalid(format!("could not run cargo: {}", err)))?;
+        if !output.status.success() {
+            return Err(invalid(format!(
+                "cargo metadata failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+
+        let metadata: Metadata = serde_json::from_slice(&output.stdout)
+            .map_err(|err| invalid(format!("invalid cargo metadata: {}", err)))?;
+        let cwd = env::current_dir().unwrap_or_default();
+
+        let mut members = metadata
+            .packages
+            .into_iter()
+            .filter(|package| metadata.workspace_members.contains(&package.id));
+        let package = members
+            .find(|package| {
+                package
+                    .manifest_path
+                    .parent()
+                    .is_some_and(|dir| cwd.starts_with(dir))
+            })
+            .or_else(|| members.next())
+            .ok_or_else(|| invalid("the workspace has no package".to_owned()))?;
+
+        Ok(Workspace {
+            root: metadata.workspace_root,
+            package,
+        })
+    }
+
+    fn relative(&self, path: &Path) -> String {
+        path.strip_prefix(&self.root)
+            .unwrap_or(path)
+            .display()
+            .to_string()
+    }
+
+    fn source_files(&self) -> Vec<PathBuf> {
+        fn walk(dir: &Path, files: &mut Vec<PathBuf>) {
+            let Ok(entries) = fs::read_dir(dir) else {
+                return;
+            };
+            for path in entries.flatten().map(|entry| entry.path()) {
+                if path.is_dir() {
+                    walk(&path, files);
+                } else if path.extension().is_some_and(|ext| ext == "rs") {
+                    files.push(path);
+                }
+            }
+        }
+
+        let mut files = Vec::new();
+        if let Some(dir) = self.package.manifest_path.parent() {
+            walk(&dir.join("src"), &mut files);
+        }
+        files.sort();
+
+        files
+    }
+
+    /// Lets the user pick one of the package's source files, relative to
+    /// the workspace root.
+    pub fn pick_file(&self) -> Prefill {
+        let files = self.source_files();
+        let main_target = self.package.targets.first().map(|target| &target.src_path);
+        let default = files
+            .iter()
+            .position(|file| Some(file) == main_target)
+            .unwrap_or(0);
+
+        if files.is_empty() {
+            return Prefill::default();
+        }
+
+        let number_len = files.len().to_string().len();
+        for (index, file) in files.iter().enumerate() {
+            println!(
+                "{:>width$}) {}",
+                index + 1,
+                self.relative(file),
+                width = number_len
+            );
+        }
+
+        let index = field("file", &int_factory(1, files.len()), Some(default + 1)) - 1;
+
+        Prefill {
+            path: Some(self.relative(&files[index])),
+            ..Default::default()
+        }
+    }
+}
@@ -0,0 +1,76 @@
+use std::{
+    env,
+    io::Write,
+    process::{Command, Stdio},
+};
+
+use clap::ValueEnum;
+
+use roost::{ansi, RoostError};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum CopyMode {
+    /// Copy the text without its colors
+    Plain,
+    /// Copy the text with its ANSI escape sequences
+    Ansi,
+}
+
+/// The clipboard commands to try, in order, for the current platform.
+fn commands() -> Vec<(&'static str, &'static [&'static str])> {
+    match env::consts::OS {
+        "macos" => vec![("pbcopy", &[])],
+        "windows" => vec![("clip", &[])],
+        _ => {
+            let mut commands: Vec<(&str, &[&str])> = Vec::new();
+            if env::var_os("WAYLAND_DISPLAY").is_some() {
+                commands.push(("wl-copy", &[]));
+            }
+            commands.push(("xclip", &["-selection", "clipboard"]));
+            commands.push(("xsel", &["--clipboard", "--input"]));
+            commands
+        }
+    }
+}
+
+/// Places `text` on the system clipboard through the first clipboard command
+/// available.
+pub fn copy(text: &str, mode: CopyMode) -> Result<(), RoostError> {
+    let text = match mode {
+        CopyMode::Plain => ansi::strip(text),
+        CopyMode::Ansi => text.to_owned(),
+    };
+
+    for (program, args) in commands() {
+        let Ok(mut child) = Command::new(program)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+        else {
+            continue;
+        };
+
+        child
+            .stdin
+            .take()
+            .expect("stdin is piped")
+            .write_all(text.as_bytes())
+            .map_err(|err| RoostError::ClipboardError {
+                details: format!("could not write to {}: {}", program, err),
+            })?;
+
+        return match child.wait() {
+            Ok(status) if status.success() => Ok(()),
+            _ => Err(RoostError::ClipboardError {
+                details: format!("{} failed to copy the diagnostic", program),
+            }),
+        };
+    }
+
+    Err(RoostError::ClipboardError {
+        details: "no clipboard command found (tried pbcopy, clip, wl-copy, xclip and xsel)"
+            .to_owned(),
+    })
+}
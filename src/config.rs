@@ -0,0 +1,168 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+use serde::Deserialize;
+
+use roost::RoostError;
+
+use crate::rules::Rules;
+
+const PROJECT_CONFIG_NAME: &str = ".roost.toml";
+/// Prefix of the environment variables setting the keys, as in
+/// `ROOST_THEME`.
+const ENV_PREFIX: &str = "ROOST_";
+
+#[derive(Debug, Default, Deserialize)]
+pub struct Config {
+    /// Prepended to the paths entered at the `path` prompt
+    pub path_prefix: Option<String>,
+    /// Theme used when `--theme` is not given
+    pub theme: Option<String>,
+    /// Language of the words framing the diagnostics, as `--locale`
+    pub locale: Option<String>,
+    /// Registry of the explanations shown by `roost explain`
+    pub explanations: Option<String>,
+    /// Proposed at the `path` prompt instead of `<stdin>`
+    pub default_path: Option<String>,
+    /// Proposed at the `error number` prompt instead of 69
+    pub default_errnum: Option<usize>,
+    /// Pattern of the codes, as `--code`
+    pub code: Option<String>,
+    /// Digits the error numbers are padded to, as `--code-width`
+    pub code_width: Option<usize>,
+    /// Number the ruler and the span prompt from 1, as `--one-based`
+    pub one_based: Option<bool>,
+    /// End the diagnostics with the line naming roost, as `--watermark`
+    pub watermark: Option<bool>,
+    /// Where `roost share` posts the diagnostics
+    pub share_endpoint: Option<String>,
+    /// Conventions the fields entered at the prompts must follow
+    #[serde(default)]
+    pub rules: Rules,
+}
+
+impl Config {
+    /// Loads the `ROOST_*` environment variables, with the user
+    /// configuration and then the nearest project-local `.roost.toml` filling
+    /// the keys they leave unset.
+    pub fn load() -> Result<Config, RoostError> {
+        let mut config = Config::from_env()?;
+
+        if let Some(path) = user_config_path() {
+            config = config.merge(Config::read(&path)?);
+        }
+        if let Some(path) = env::current_dir()
+            .ok()
+            .and_then(|cwd| find_project_config(&cwd))
+        {
+            config = config.merge(Config::read(&path)?);
+        }
+
+        Ok(config)
+    }
+
+    fn from_env() -> Result<Config, RoostError> {
+        let var = |key: &str| env::var(format!("{}{}", ENV_PREFIX, key.to_uppercase())).ok();
+
+        let default_errnum = match var("default_errnum") {
+            Some(raw) => Some(raw.parse().map_err(|_| RoostError::ConfigError {
+                details: format!("invalid {}DEFAULT_ERRNUM: {}", ENV_PREFIX, raw),
+            })?),
+            None => None,
+        };
+
+        let code_width = match var("code_width") {
+            Some(raw) => Some(raw.parse().map_err(|_| RoostError::ConfigError {
+                details: format!("invalid {}CODE_WIDTH: {}", ENV_PREFIX, raw),
+            })?),
+            None => None,
+        };
+
+        let one_based = match var("one_based") {
+            Some(raw) => Some(raw.parse().map_err(|_| RoostError::ConfigError {
+                details: format!("invalid {}ONE_BASED: {}", ENV_PREFIX, raw),
+            })?),
+            None => None,
+        };
+
+        let watermark = match var("watermark") {
+            Some(raw) => Some(raw.parse().map_err(|_| RoostError::ConfigError {
+                details: format!("invalid {}WATERMARK: {}", ENV_PREFIX, raw),
+            })?),
+            None => None,
+        };
+
+        Ok(Config {
+            path_prefix: var("path_prefix"),
+            theme: var("theme"),
+            locale: var("locale"),
+            explanations: var("explanations"),
+            default_path: var("default_path"),
+            default_errnum,
+            code: var("code"),
+            code_width,
+            one_based,
+            watermark,
+            share_endpoint: var("share_endpoint"),
+            rules: Rules::default(),
+        })
+    }
+
+    fn read(path: &Path) -> Result<Config, RoostError> {
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(_) if !path.exists() => return Ok(Config::default()),
+            Err(err) => {
+                return Err(RoostError::ConfigError {
+                    details: format!("could not read {}: {}", path.display(), err),
+                })
+            }
+        };
+
+        toml::from_str(&contents).map_err(|err| RoostError::ConfigError {
+            details: format!("invalid config {}: {}", path.display(), err.message()),
+        })
+    }
+
+    /// Keeps the values of `self`, falling back to `lower` for unset keys.
+    fn merge(self, lower: Config) -> Config {
+        Config {
+            path_prefix: self.path_prefix.or(lower.path_prefix),
+            theme: self.theme.or(lower.theme),
+            locale: self.locale.or(lower.locale),
+            explanations: self.explanations.or(lower.explanations),
+            default_path: self.default_path.or(lower.default_path),
+            default_errnum: self.default_errnum.or(lower.default_errnum),
+            code: self.code.or(lower.code),
+            code_width: self.code_width.or(lower.code_width),
+            one_based: self.one_based.or(lower.one_based),
+            watermark: self.watermark.or(lower.watermark),
+            share_endpoint: self.share_endpoint.or(lower.share_endpoint),
+            rules: self.rules.merge(lower.rules),
+        }
+    }
+
+    pub fn prefixed_path(&self, path: String) -> String {
+        match &self.path_prefix {
+            Some(prefix) => Path::new(prefix).join(path).display().to_string(),
+            None => path,
+        }
+    }
+}
+
+pub fn user_config_dir() -> Option<PathBuf> {
+    roost::user_dir("XDG_CONFIG_HOME", ".config")
+}
+
+fn user_config_path() -> Option<PathBuf> {
+    user_config_dir().map(|dir| dir.join("config.toml"))
+}
+
+fn find_project_config(start: &Path) -> Option<PathBuf> {
+    start
+        .ancestors()
+        .map(|dir| dir.join(PROJECT_CONFIG_NAME))
+        .find(|path| path.is_file())
+}
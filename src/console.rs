@@ -0,0 +1,40 @@
+/// Lets older Windows consoles interpret the escape sequences roost prints,
+/// instead of showing them as garbage. Returns whether they are interpreted.
+#[cfg(windows)]
+pub fn enable_ansi() -> bool {
+    use windows_sys::Win32::{
+        Foundation::INVALID_HANDLE_VALUE,
+        System::Console::{
+            GetConsoleMode, GetStdHandle, SetConsoleMode, ENABLE_VIRTUAL_TERMINAL_PROCESSING,
+            STD_ERROR_HANDLE, STD_HANDLE, STD_OUTPUT_HANDLE,
+        },
+    };
+
+    let enable = |std_handle: STD_HANDLE| {
+        // SAFETY: the handle comes from `GetStdHandle` and is checked before
+        // use, and `mode` outlives the call writing to it.
+        unsafe {
+            let handle = GetStdHandle(std_handle);
+            let mut mode = 0;
+            if handle == 0
+                || handle == INVALID_HANDLE_VALUE
+                || GetConsoleMode(handle, &mut mode) == 0
+            {
+                // Not a console (a file or a pipe): nothing to enable.
+                return true;
+            }
+
+            mode & ENABLE_VIRTUAL_TERMINAL_PROCESSING != 0
+                || SetConsoleMode(handle, mode | ENABLE_VIRTUAL_TERMINAL_PROCESSING) != 0
+        }
+    };
+
+    enable(STD_OUTPUT_HANDLE) & enable(STD_ERROR_HANDLE)
+}
+
+/// Lets older Windows consoles interpret the escape sequences roost prints,
+/// instead of showing them as garbage. Returns whether they are interpreted.
+#[cfg(not(windows))]
+pub fn enable_ansi() -> bool {
+    true
+}
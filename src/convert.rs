@@ -0,0 +1,116 @@
+//! The `convert` subcommand: diagnostics read in any format roost reads,
+//! written in any format or style it writes.
+
+use std::{ffi::OsStr, path::PathBuf};
+
+use clap::{
+    builder::{PossibleValue, PossibleValuesParser, TypedValueParser},
+    ValueEnum,
+};
+
+use roost::{
+    export::{Format, FormatParser},
+    import,
+    render::Emitter,
+    spec::{self, SpecFormat},
+    Diagnostic, RoostError,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Input {
+    /// TOML spec
+    Toml,
+    /// JSON spec
+    Json,
+    /// One JSON spec per line
+    Ndjson,
+    /// rustc or cargo text diagnostics, as printed in the terminal
+    Rustc,
+    /// `cargo build --message-format=json` or `rustc --error-format=json`
+    RustcJson,
+    /// GCC or clang text diagnostics
+    Gcc,
+    /// CPython traceback
+    PythonTraceback,
+}
+
+/// What `--to` writes: the text of a style, or a format.
+#[derive(Clone, Debug)]
+pub enum Target {
+    Style(Emitter),
+    Format(Format),
+}
+
+/// Parses `--to`: the name of a style or else of a format, plugins
+/// included.
+#[derive(Clone)]
+struct TargetParser;
+
+impl TypedValueParser for TargetParser {
+    type Value = Target;
+
+    fn parse_ref(
+        &self,
+        cmd: &clap::Command,
+        arg: Option<&clap::Arg>,
+        value: &OsStr,
+    ) -> Result<Target, clap::Error> {
+        let values: Vec<PossibleValue> = self.possible_values().into_iter().flatten().collect();
+        let name = PossibleValuesParser::new(values).parse_ref(cmd, arg, value)?;
+
+        match Emitter::from_str(&name, false) {
+            Ok(style) => Ok(Target::Style(style)),
+            Err(_) => FormatParser
+                .parse_ref(cmd, arg, OsStr::new(&name))
+                .map(Target::Format),
+        }
+    }
+
+    fn possible_values(&self) -> Option<Box<dyn Iterator<Item = PossibleValue> + '_>> {
+        let styles = Emitter::value_variants()
+            .iter()
+            .filter_map(Emitter::to_possible_value);
+        let formats = FormatParser.possible_values().into_iter().flatten();
+
+        Some(Box::new(styles.chain(formats)))
+    }
+}
+
+#[derive(clap::Args, Debug)]
+pub struct ConvertArgs {
+    /// File to convert, standard input if omitted or `-`
+    pub file: Option<PathBuf>,
+    /// Format of the file, guessed from its extension by default (TOML
+    /// unless `.json`)
+    #[arg(long, value_enum)]
+    pub from: Option<Input>,
+    /// Style or format to write the diagnostics in, as `--style` or
+    /// `--format`
+    #[arg(long, value_name = "TARGET", value_parser = TargetParser)]
+    pub to: Target,
+}
+
+/// The diagnostics of the file to convert.
+pub fn read(args: &ConvertArgs) -> Result<Vec<Diagnostic<'static>>, RoostError> {
+    let contents = import::read_input(args.file.as_ref())?;
+    let name = match &args.file {
+        Some(path) if path.as_os_str() != "-" => path.display().to_string(),
+        _ => "<stdin>".to_owned(),
+    };
+    let from = args
+        .from
+        .unwrap_or_else(|| match args.file.as_deref().map(SpecFormat::guess) {
+            Some(SpecFormat::Json) => Input::Json,
+            _ => Input::Toml,
+        });
+
+    match from {
+        Input::Toml => spec::from_text(&contents, SpecFormat::Toml, &name),
+        Input::Json => spec::from_text(&contents, SpecFormat::Json, &name),
+        Input::Ndjson => spec::from_text(&contents, SpecFormat::NdjsonIn, &name),
+        Input::Rustc => import::rustc::parse(&contents),
+        Input::RustcJson => import::rustc_json::parse(&contents),
+        Input::Gcc => import::gcc::parse(&contents),
+        Input::PythonTraceback => import::python::parse(&contents),
+    }
+}
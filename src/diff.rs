@@ -0,0 +1,128 @@
+use std::{fs, path::PathBuf, process};
+
+use roost::{
+    bold, color,
+    prompt::{field, int_factory},
+    selection::Selection,
+    RoostError,
+};
+
+use crate::InterviewArgs;
+
+#[derive(clap::Args, Debug)]
+pub struct FromDiffArgs {
+    /// Unified diff to read, `git diff` output if omitted
+    pub file: Option<PathBuf>,
+    /// Use the staged changes (`git diff --staged`)
+    #[arg(long)]
+    pub staged: bool,
+    #[command(flatten)]
+    pub interview: InterviewArgs,
+}
+
+/// A line added by the diff.
+struct AddedLine {
+    path: String,
+    lineno: usize,
+    text: String,
+}
+
+fn read_diff(args: &FromDiffArgs) -> Result<String, RoostError> {
+    let invalid = |details: String| RoostError::ImportError { details };
+
+    if let Some(path) = &args.file {
+        return fs::read_to_string(path)
+            .map_err(|err| invalid(format!("could not read {}: {}", path.display(), err)));
+    }
+
+    let mut command = process::Command::new("git");
+    command.args(["diff", "--no-color", "--no-ext-diff"]);
+    if args.staged {
+        command.arg("--staged");
+    }
+
+    let output = command
+        .output()
+        .map_err(|err| invalid(format!("could not run git: {}", err)))?;
+    if !output.status.success() {
+        return Err(invalid(format!(
+            "git diff failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Parses the new-side line number out of `@@ -a,b +c,d @@`.
+fn hunk_start(header: &str) -> Option<usize> {
+    let new_range = header
+        .split_whitespace()
+        .find(|part| part.starts_with('+'))?;
+    new_range[1..].split(',').next()?.parse().ok()
+}
+
+fn added_lines(diff: &str) -> Vec<AddedLine> {
+    let mut lines = Vec::new();
+    let mut path = None;
+    let mut lineno = 0;
+
+    for line in diff.lines() {
+        if let Some(new_path) = line.strip_prefix("+++ ") {
+            path = match new_path {
+                "/dev/null" => None,
+                new_path => Some(new_path.strip_prefix("b/").unwrap_or(new_path).to_owned()),
+            };
+        } else if line.starts_with("@@") {
+            lineno = hunk_start(line).unwrap_or(0);
+        } else if let (Some(text), Some(path)) = (line.strip_prefix('+'), &path) {
+            lines.push(AddedLine {
+                path: path.clone(),
+                lineno,
+                text: text.to_owned(),
+            });
+            lineno += 1;
+        } else if line.starts_with(' ') {
+            lineno += 1;
+        }
+    }
+
+    lines
+}
+
+/// Lets the user pick one of the added lines, returned as a selection
+/// spanning its non-blank part.
+pub fn pick(args: &FromDiffArgs) -> Result<Selection, RoostError> {
+    let lines: Vec<AddedLine> = added_lines(&read_diff(args)?)
+        .into_iter()
+        .filter(|line| !line.text.trim().is_empty())
+        .collect();
+
+    if lines.is_empty() {
+        return Err(RoostError::ImportError {
+            details: "the diff adds no line".to_owned(),
+        });
+    }
+
+    let number_len = lines.len().to_string().len();
+    for (index, line) in lines.iter().enumerate() {
+        println!(
+            "{} {} {}",
+            bold(format!("{:>width$})", index + 1, width = number_len)),
+            color(format!("{}:{}", line.path, line.lineno), 4),
+            color(line.text.clone(), 2),
+        );
+    }
+
+    let index = field("changed line", &int_factory(1, lines.len()), Some(1)) - 1;
+    let line = &lines[index];
+    let spos = line.text.len() - line.text.trim_start().len();
+
+    Ok(Selection {
+        path: line.path.clone(),
+        lineno: line.lineno,
+        line: line.text.clone(),
+        spos,
+        epos: line.text.trim_end().len(),
+    })
+}
@@ -0,0 +1,107 @@
+use std::{collections::BTreeMap, env, fs, path::PathBuf, process};
+
+use roost::RoostError;
+
+use crate::config::{self, Config};
+
+const REGISTRY_NAME: &str = "explanations.toml";
+
+#[derive(clap::Args, Debug)]
+pub struct ExplainArgs {
+    /// Error code, such as `E0069` or `69`
+    pub code: String,
+    /// Print the official text of the Rust error index, as given by
+    /// `rustc --explain`, when the code is in it
+    #[arg(long)]
+    pub real_explain: bool,
+}
+
+/// Long-form explanations of error codes, read from a TOML file mapping each
+/// code to its text.
+#[derive(Debug, Default)]
+pub struct Explanations {
+    by_code: BTreeMap<String, String>,
+}
+
+impl Explanations {
+    /// Reads the file given by the `explanations` config key, or else the
+    /// `explanations.toml` next to the user configuration.
+    pub fn load(config: &Config) -> Result<Explanations, RoostError> {
+        let path = match &config.explanations {
+            Some(path) => PathBuf::from(path),
+            None => match config::user_config_dir() {
+                Some(dir) => dir.join(REGISTRY_NAME),
+                None => return Ok(Explanations::default()),
+            },
+        };
+
+        let contents = match fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(_) if !path.exists() => return Ok(Explanations::default()),
+            Err(err) => {
+                return Err(RoostError::ConfigError {
+                    details: format!("could not read {}: {}", path.display(), err),
+                })
+            }
+        };
+        let by_code: BTreeMap<String, String> =
+            toml::from_str(&contents).map_err(|err| RoostError::ConfigError {
+                details: format!("invalid registry {}: {}", path.display(), err.message()),
+            })?;
+
+        Ok(Explanations {
+            by_code: by_code
+                .into_iter()
+                .map(|(code, text)| (normalize(&code), text))
+                .collect(),
+        })
+    }
+
+    pub fn get(&self, code: &str) -> Option<&str> {
+        self.by_code.get(&normalize(code)).map(String::as_str)
+    }
+
+    /// The registered codes, as `E0069`.
+    pub fn codes(&self) -> impl Iterator<Item = &String> {
+        self.by_code.keys()
+    }
+}
+
+/// Writes numeric codes as `E0069`, keeping the other ones as they are.
+fn normalize(code: &str) -> String {
+    match code.trim_start_matches('E').parse::<usize>() {
+        Ok(errnum) => format!("E{:04}", errnum),
+        Err(_) => code.to_owned(),
+    }
+}
+
+/// The long description of `code` in the Rust error index, if `rustc` is
+/// installed and knows the code.
+fn rustc_explanation(code: &str) -> Option<String> {
+    let output = process::Command::new(env::var_os("RUSTC").unwrap_or("rustc".into()))
+        .args(["--explain", code])
+        .output()
+        .ok()?;
+
+    let text = String::from_utf8(output.stdout).ok()?;
+    (output.status.success() && !text.trim().is_empty()).then_some(text)
+}
+
+pub fn run(args: &ExplainArgs, explanations: &Explanations) -> Result<(), RoostError> {
+    let code = normalize(&args.code);
+    if args.real_explain {
+        if let Some(explanation) = rustc_explanation(&code) {
+            println!("{}", explanation.trim_end());
+            return Ok(());
+        }
+    }
+
+    let explanation = explanations
+        .get(&code)
+        .ok_or_else(|| RoostError::ValueError {
+            details: format!("no explanation for {}", code),
+        })?;
+
+    println!("{}", explanation.trim_end());
+    Ok(())
+}
@@ -0,0 +1,74 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde_json::json;
+
+use crate::{ansi, bold, color};
+
+const MIN_WIDTH: usize = 80;
+const MIN_HEIGHT: usize = 24;
+
+const KEYSTROKE_DELAY: f64 = 0.08;
+const COMMAND_DELAY: f64 = 0.9;
+const LINE_DELAY: f64 = 0.03;
+
+/// Small xorshift generator for the typing jitter, seeded from the content
+/// so that a given diagnostic always produces the same recording.
+struct Jitter(u64);
+
+impl Jitter {
+    fn next(&mut self) -> f64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 % 1000) as f64 / 1000.0
+    }
+}
+
+fn seconds(time: f64) -> f64 {
+    (time * 1000.0).round() / 1000.0
+}
+
+pub fn render(rendered: &str, command: &str) -> String {
+    let lines = ansi::parse(rendered);
+    let width = lines.iter().map(Vec::len).max().unwrap_or(0).max(MIN_WIDTH);
+    let height = (lines.len() + 2).max(MIN_HEIGHT);
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs());
+
+    let mut jitter = Jitter(
+        rendered.bytes().fold(0x9e3779b97f4a7c15_u64, |seed, byte| {
+            seed.rotate_left(5) ^ byte as u64
+        }) | 1,
+    );
+    let mut time = 0.5;
+    let mut events = vec![json!({
+        "version": 2,
+        "width": width,
+        "height": height,
+        "timestamp": timestamp,
+        "env": { "SHELL": "/bin/bash", "TERM": "xterm-256color" },
+    })];
+
+    events.push(json!([seconds(time), "o", bold(color("$ ".to_owned(), 2))]));
+    for ch in command.chars() {
+        time += KEYSTROKE_DELAY * (0.5 + jitter.next());
+        events.push(json!([seconds(time), "o", ch.to_string()]));
+    }
+    time += 0.2;
+    events.push(json!([seconds(time), "o", "\r\n"]));
+    time += COMMAND_DELAY * (0.5 + jitter.next());
+
+    for line in rendered.lines() {
+        events.push(json!([seconds(time), "o", format!("{}\r\n", line)]));
+        time += LINE_DELAY * jitter.next();
+    }
+
+    events.push(json!([
+        seconds(time + 0.5),
+        "o",
+        bold(color("$ ".to_owned(), 2))
+    ]));
+
+    events.iter().map(|event| format!("{}\n", event)).collect()
+}
@@ -0,0 +1,110 @@
+use serde_json::{json, Value};
+
+use super::char_column;
+use crate::{ansi, cargo::Package, Diagnostic, Label};
+
+/// The span of `err`, labelled with its message.
+fn span(err: &Diagnostic) -> Value {
+    json!({
+        "file_name": err.path(),
+        "byte_start": err.spos(),
+        "byte_end": err.epos(),
+        "line_start": err.lineno(),
+        "line_end": err.lineno(),
+        "column_start": char_column(err.line(), err.spos()),
+        "column_end": char_column(err.line(), err.epos()),
+        "is_primary": true,
+        "text": [{
+            "text": err.line(),
+            "highlight_start": char_column(err.line(), err.spos()),
+            "highlight_end": char_column(err.line(), err.epos()),
+        }],
+        "label": err.message,
+        "suggested_replacement": err.fixit,
+        "suggestion_applicability": err.fixit.as_ref().map(|_| "MachineApplicable"),
+        "expansion": null,
+    })
+}
+
+/// The span of `label`, on the line of `err`.
+fn label_span(err: &Diagnostic, label: &Label) -> Value {
+    let range = &label.range;
+    json!({
+        "file_name": err.path(),
+        "byte_start": range.start,
+        "byte_end": range.end,
+        "line_start": err.lineno(),
+        "line_end": err.lineno(),
+        "column_start": char_column(err.line(), range.start),
+        "column_end": char_column(err.line(), range.end),
+        "is_primary": false,
+        "text": [{
+            "text": err.line(),
+            "highlight_start": char_column(err.line(), range.start),
+            "highlight_end": char_column(err.line(), range.end),
+        }],
+        "label": (!label.message.is_empty()).then_some(&label.message),
+        "suggested_replacement": null,
+        "suggestion_applicability": null,
+        "expansion": null,
+    })
+}
+
+/// The diagnostic as emitted by `rustc --error-format=json`.
+fn diagnostic(err: &Diagnostic, rendered: &str) -> Value {
+    let children: Vec<Value> = err
+        .related
+        .iter()
+        .map(|note| {
+            json!({
+                "message": note.summary,
+                "code": null,
+                "level": "note",
+                "spans": [span(note)],
+                "children": [],
+                "rendered": null,
+            })
+        })
+        .collect();
+
+    json!({
+        "$message_type": "diagnostic",
+        "message": err.summary,
+        "code": { "code": err.get_errid(), "explanation": null },
+        "level": err.severity.to_string(),
+        "spans": std::iter::once(span(err))
+            .chain(err.labels.iter().map(|label| label_span(err, label)))
+            .collect::<Vec<_>>(),
+        "children": children,
+        "rendered": ansi::strip(rendered),
+    })
+}
+
+/// One `compiler-message` line per diagnostic, as `cargo build
+/// --message-format=json` prints them.
+pub fn render(errs: &[Diagnostic], rendered: &[String], package: Option<&Package>) -> String {
+    let target = package.and_then(|package| package.targets.first());
+
+    errs.iter()
+        .zip(rendered)
+        .map(|(err, rendered)| {
+            let message = json!({
+                "reason": "compiler-message",
+                "package_id": package.map(|package| package.id.clone()),
+                "manifest_path": package.map(|package| package.manifest_path.clone()),
+                "target": target.map(|target| json!({
+                    "kind": target.kind,
+                    "crate_types": target.crate_types,
+                    "name": target.name,
+                    "src_path": target.src_path,
+                    "edition": target.edition,
+                    "doc": true,
+                    "doctest": false,
+                    "test": true,
+                })),
+                "message": diagnostic(err, rendered),
+            });
+            format!("{}\n", message)
+        })
+        .collect()
+}
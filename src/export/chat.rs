@@ -0,0 +1,35 @@
+use clap::ValueEnum;
+
+use crate::ansi;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum ChatApp {
+    /// `ansi` code block, colors included
+    #[default]
+    Discord,
+    /// Plain code block
+    Slack,
+}
+
+/// Drops the trailing lines that only contain the gutter.
+fn collapse_trailing_gutter(text: &str) -> String {
+    let mut lines: Vec<&str> = text.lines().collect();
+
+    while lines
+        .last()
+        .is_some_and(|line| matches!(ansi::strip(line).trim(), "" | "|"))
+    {
+        lines.pop();
+    }
+
+    lines.iter().map(|line| format!("{}\n", line)).collect()
+}
+
+pub fn render(rendered: &str, app: ChatApp) -> String {
+    let (language, body) = match app {
+        ChatApp::Discord => ("ansi", rendered.to_owned()),
+        ChatApp::Slack => ("", ansi::strip(rendered)),
+    };
+
+    format!("```{}\n{}```\n", language, collapse_trailing_gutter(&body))
+}
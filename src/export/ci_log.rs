@@ -0,0 +1,128 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Steps run before the build, with the lines they log.
+const SETUP_STEPS: [(&str, &[&str]); 3] = [
+    (
+        "Set up job",
+        &[
+            "Current runner version: '2.321.0'",
+            "Operating System",
+            "  Ubuntu",
+            "  24.04.1",
+            "  LTS",
+        ],
+    ),
+    (
+        "Run actions/checkout@v4",
+        &[
+            "Syncing repository",
+            "Getting Git version info",
+            "Fetching the repository",
+            "Determining the checkout info",
+        ],
+    ),
+    (
+        "Run dtolnay/rust-toolchain@stable",
+        &[
+            "info: syncing channel updates for 'stable-x86_64-unknown-linux-gnu'",
+            "info: default toolchain set to 'stable-x86_64-unknown-linux-gnu'",
+        ],
+    ),
+];
+
+/// Seconds a line of the build takes to be logged, at most.
+const LINE_DELAY: f64 = 0.004;
+
+/// The date and time `millis` milliseconds after the epoch, in ISO 8601
+/// as CI runners print them.
+pub(crate) fn iso8601(millis: u64) -> String {
+    let (days, rest) = (millis / 86_400_000, millis % 86_400_000);
+    // Days to a civil date, after Howard Hinnant's algorithm
+    let z = days as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        year,
+        month,
+        day,
+        rest / 3_600_000,
+        rest / 60_000 % 60,
+        rest / 1000 % 60,
+        rest % 1000
+    )
+}
+
+/// The log of a CI job whose build step runs `command` and prints
+/// `rendered`, each line after the time it was logged at. The step fails
+/// as cargo does when `failed`, and else completes.
+pub fn render(rendered: &str, command: &str, failed: bool) -> String {
+    let mut rng = fastrand::Rng::new();
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_millis() as u64);
+    // The job started long enough ago for its steps to have run since
+    let mut time = now.saturating_sub(rng.u64(20_000..60_000)) as f64 / 1000.0;
+
+    let mut log = String::new();
+    let mut line = |time: f64, text: &str| {
+        log.push_str(&format!("{} {}\n", iso8601((time * 1000.0) as u64), text));
+    };
+
+    for (name, lines) in SETUP_STEPS {
+        let start = time;
+        line(time, &format!("##[group]{}", name));
+        for text in lines {
+            time += rng.f64() * 0.3;
+            line(time, text);
+        }
+        line(time, "##[endgroup]");
+        time += 0.5 + rng.f64() * 4.0;
+        line(time, &format!("{} completed in {:.1}s", name, time - start));
+    }
+
+    let start = time;
+    line(time, &format!("##[group]Run {}", command));
+    line(time, command);
+    line(time, "shell: /usr/bin/bash -e {0}");
+    line(time, "##[endgroup]");
+    time += 2.0 + rng.f64() * 10.0;
+    for text in rendered.lines() {
+        line(time, text);
+        time += rng.f64() * LINE_DELAY;
+    }
+    match failed {
+        true => {
+            line(time, "##[error]Process completed with exit code 101.");
+            line(
+                time,
+                &format!("Run {} failed after {:.1}s", command, time - start),
+            );
+        }
+        false => line(
+            time,
+            &format!("Run {} completed in {:.1}s", command, time - start),
+        ),
+    }
+
+    log
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn iso8601_dates() {
+        assert_eq!(iso8601(0), "1970-01-01T00:00:00.000Z");
+        assert_eq!(iso8601(951_782_400_000), "2000-02-29T00:00:00.000Z");
+        assert_eq!(iso8601(1_700_000_000_123), "2023-11-14T22:13:20.123Z");
+    }
+}
@@ -0,0 +1,31 @@
+use crate::Diagnostic;
+
+fn escape_data(data: &str) -> String {
+    data.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+fn escape_property(property: &str) -> String {
+    escape_data(property)
+        .replace(':', "%3A")
+        .replace(',', "%2C")
+}
+
+pub fn render(err: &Diagnostic) -> String {
+    format!(
+        "::{} file={},line={},col={},endColumn={},title={}::{}\n",
+        err.severity,
+        escape_property(err.path()),
+        err.lineno(),
+        err.spos() + 1,
+        err.epos(),
+        escape_property(&format!(
+            "{}[{}]: {}",
+            err.severity,
+            err.get_errid(),
+            err.summary
+        )),
+        escape_data(&err.message),
+    )
+}
@@ -0,0 +1,29 @@
+use serde_json::{json, Value};
+
+use super::fingerprint;
+use crate::{Diagnostic, Severity};
+
+fn issue(err: &Diagnostic) -> Value {
+    json!({
+        "description": format!("{}: {}", err.summary, err.message),
+        "check_name": err.get_errid(),
+        "fingerprint": fingerprint(err),
+        "severity": match err.severity {
+            Severity::Error => "major",
+            Severity::Warning => "minor",
+        },
+        "location": {
+            "path": err.path(),
+            "positions": {
+                "begin": { "line": err.lineno(), "column": err.spos() + 1 },
+                "end": { "line": err.lineno(), "column": err.epos() },
+            },
+        },
+    })
+}
+
+pub fn render(errs: &[Diagnostic]) -> String {
+    let report: Vec<Value> = errs.iter().map(issue).collect();
+
+    format!("{:#}\n", Value::from(report))
+}
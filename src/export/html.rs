@@ -0,0 +1,36 @@
+use crate::ansi::{self, BACKGROUND, FOREGROUND};
+
+pub fn render(rendered: &str) -> String {
+    let mut string = format!(
+        "<pre class=\"roost\" style=\"background: {}; color: {}; padding: 1em; font-family: monospace;\">",
+        ansi::hex(BACKGROUND),
+        ansi::hex(FOREGROUND),
+    );
+
+    for line in ansi::parse(rendered) {
+        for (attrs, text) in ansi::runs(&line) {
+            let text = ansi::escape_xml(&text);
+            let mut style = String::new();
+            if let Some(color) = attrs.fg {
+                style.push_str(&format!("color: {};", ansi::hex(ansi::rgb(Some(color)))));
+            }
+            if attrs.bold {
+                style.push_str(" font-weight: bold;");
+            }
+            if attrs.underline {
+                style.push_str(" text-decoration: underline wavy;");
+            }
+
+            match style.is_empty() {
+                true => string.push_str(&text),
+                false => {
+                    string.push_str(&format!("<span style=\"{}\">{}</span>", style.trim(), text))
+                }
+            }
+        }
+        string.push('\n');
+    }
+
+    string.push_str("</pre>\n");
+    string
+}
@@ -0,0 +1,55 @@
+use crate::{ansi, Diagnostic};
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+fn testcase(err: &Diagnostic, rendered: &str) -> String {
+    let path = escape(err.path());
+    let summary = escape(&err.summary);
+    let errid = err.get_errid();
+
+    let mut string = format!(
+        "    <testcase name=\"{}[{}]: {}\" classname=\"{}\" file=\"{}\" line=\"{}\">\n",
+        err.severity,
+        errid,
+        summary,
+        path,
+        path,
+        err.lineno()
+    );
+    string.push_str(&format!(
+        "      <failure message=\"{}\" type=\"{}\">{}</failure>\n",
+        summary,
+        errid,
+        escape(&ansi::strip(rendered))
+    ));
+    string.push_str("    </testcase>\n");
+
+    string
+}
+
+pub fn render(errs: &[Diagnostic], rendered: &[String]) -> String {
+    let mut string = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    string.push_str(&format!(
+        "<testsuites name=\"roost\" tests=\"{}\" failures=\"{}\">\n",
+        errs.len(),
+        errs.len()
+    ));
+    string.push_str(&format!(
+        "  <testsuite name=\"roost\" tests=\"{}\" failures=\"{}\">\n",
+        errs.len(),
+        errs.len()
+    ));
+    for (err, rendered) in errs.iter().zip(rendered) {
+        string.push_str(&testcase(err, rendered));
+    }
+    string.push_str("  </testsuite>\n");
+    string.push_str("</testsuites>\n");
+
+    string
+}
@@ -0,0 +1,33 @@
+use crate::{
+    ansi,
+    spec::{Spec, Specs},
+    Diagnostic,
+};
+
+fn spec(errs: &[Diagnostic]) -> String {
+    let result = match errs {
+        [err] => toml::to_string(&Spec::from(err)),
+        _ => toml::to_string(&Specs {
+            diagnostics: errs.iter().map(Spec::from).collect(),
+        }),
+    };
+
+    result.expect("specs are always serializable")
+}
+
+pub fn render(errs: &[Diagnostic], rendered: &str, ansi_fence: bool, with_spec: bool) -> String {
+    let (language, body) = match ansi_fence {
+        true => ("ansi", rendered.to_owned()),
+        false => ("", ansi::strip(rendered)),
+    };
+
+    let mut string = format!("```{}\n{}```\n", language, body);
+
+    if with_spec {
+        string.push_str("\n<details>\n<summary>Spec</summary>\n\n```toml\n");
+        string.push_str(&spec(errs));
+        string.push_str("```\n\n</details>\n");
+    }
+
+    string
+}
@@ -0,0 +1,342 @@
+use std::{ffi::OsStr, io};
+
+use clap::{
+    builder::{PossibleValue, PossibleValuesParser, TypedValueParser},
+    ValueEnum,
+};
+
+use chat::ChatApp;
+
+use crate::{
+    ansi::{self, ColorChoice},
+    cargo::Package,
+    render::{self, RenderOptions},
+    Diagnostic,
+};
+
+pub mod asciicast;
+pub mod cargo_json;
+pub mod chat;
+pub mod ci_log;
+pub mod github;
+pub mod gitlab;
+pub mod html;
+pub mod junit;
+pub mod markdown;
+#[cfg(feature = "plugins")]
+pub mod plugin;
+pub mod png;
+pub mod prose;
+pub mod quickfix;
+pub mod rdjson;
+pub mod sarif;
+pub mod short;
+pub mod svg;
+pub mod teamcity;
+pub mod ui_stderr;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    /// Colored text, as printed in the terminal
+    #[default]
+    Text,
+    /// PNG image of the colored text
+    Png,
+    /// Fenced Markdown code block
+    Markdown,
+    /// One line per diagnostic, like `rustc --error-format=short`
+    Short,
+    /// Plain sentences for screen readers, without the drawings
+    Prose,
+    /// GitHub Actions workflow command annotations
+    Github,
+    /// GitLab Code Quality report (JSON)
+    GitlabCodequality,
+    /// JUnit XML report with the error as a failed test case
+    Junit,
+    /// Reviewdog Diagnostic Format (JSON)
+    Rdjson,
+    /// SARIF 2.1.0 log (JSON), for code scanning
+    Sarif,
+    /// asciinema v2 recording of a failing build
+    Asciicast,
+    /// Code block ready to paste in a chat app
+    Chat,
+    /// Vim quickfix and Emacs compilation-mode lines
+    Quickfix,
+    /// TeamCity service messages
+    Teamcity,
+    /// `cargo build --message-format=json` messages
+    CargoJson,
+    /// HTML `<pre>` block with inline colors
+    Html,
+    /// SVG image of the colored text
+    Svg,
+    /// Expected output of a rustc UI test, as in its `.stderr` file
+    UiStderr,
+    /// The WebAssembly plugin of this name
+    #[cfg(feature = "plugins")]
+    #[value(skip)]
+    Plugin(String),
+}
+
+impl Format {
+    /// Whether the format draws the colors itself, so that they are kept
+    /// whatever the output is.
+    fn draws_colors(&self) -> bool {
+        matches!(
+            self,
+            Format::Png | Format::Html | Format::Svg | Format::Asciicast
+        )
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Format::Png => "image/png",
+            Format::Svg => "image/svg+xml",
+            Format::Html => "text/html; charset=utf-8",
+            Format::Junit => "application/xml",
+            Format::GitlabCodequality | Format::Rdjson => "application/json",
+            Format::Sarif => "application/sarif+json",
+            Format::CargoJson | Format::Asciicast => "application/x-ndjson",
+            Format::Markdown => "text/markdown; charset=utf-8",
+            Format::Text
+            | Format::Short
+            | Format::Prose
+            | Format::Github
+            | Format::Chat
+            | Format::Quickfix
+            | Format::Teamcity
+            | Format::UiStderr => "text/plain; charset=utf-8",
+            #[cfg(feature = "plugins")]
+            Format::Plugin(_) => "application/octet-stream",
+        }
+    }
+}
+
+/// Parses `--format`: one of the formats of roost, or else the name of a
+/// plugin.
+#[derive(Clone)]
+pub struct FormatParser;
+
+impl TypedValueParser for FormatParser {
+    type Value = Format;
+
+    fn parse_ref(
+        &self,
+        cmd: &clap::Command,
+        arg: Option<&clap::Arg>,
+        value: &OsStr,
+    ) -> Result<Format, clap::Error> {
+        let values: Vec<PossibleValue> = self.possible_values().into_iter().flatten().collect();
+        let name = PossibleValuesParser::new(values).parse_ref(cmd, arg, value)?;
+        #[cfg(feature = "plugins")]
+        if Format::from_str(&name, false).is_err() {
+            return Ok(Format::Plugin(name));
+        }
+
+        Ok(Format::from_str(&name, false).expect("the other values are the formats"))
+    }
+
+    fn possible_values(&self) -> Option<Box<dyn Iterator<Item = PossibleValue> + '_>> {
+        let formats = Format::value_variants()
+            .iter()
+            .filter_map(Format::to_possible_value);
+        #[cfg(feature = "plugins")]
+        let formats = formats.chain(
+            plugin::names()
+                .into_iter()
+                .map(|name| PossibleValue::new(name).help("Plugin")),
+        );
+
+        Some(Box::new(formats))
+    }
+}
+
+#[derive(clap::Args, Debug)]
+pub struct ExportOptions {
+    #[arg(long, global = true, value_parser = FormatParser, default_value = "text")]
+    pub format: Format,
+    #[arg(long, global = true, value_enum, default_value_t = ColorChoice::Auto)]
+    pub color: ColorChoice,
+    /// Keep the colors and use an `ansi` code fence (markdown format)
+    #[arg(long, global = true)]
+    pub ansi_fence: bool,
+    /// Append the fields in a collapsible section (markdown format)
+    #[arg(long, global = true)]
+    pub with_spec: bool,
+    /// Command typed before the error appears (asciicast format)
+    #[arg(
+        long,
+        global = true,
+        value_name = "COMMAND",
+        default_value = "cargo build"
+    )]
+    pub cast_command: String,
+    /// Wrap the diagnostic in the timestamped log of a failing CI job (text
+    /// format)
+    #[arg(long, global = true)]
+    pub ci_log: bool,
+    /// Command run by the build step of the CI job (`--ci-log`)
+    #[arg(
+        long,
+        global = true,
+        value_name = "COMMAND",
+        default_value = "cargo build"
+    )]
+    pub ci_command: String,
+    /// End the diagnostics with a line telling they were made up with roost,
+    /// in the formats showing the rendered text
+    #[arg(long, global = true)]
+    pub watermark: bool,
+    /// Chat app the code block is meant for (chat format)
+    #[arg(long, global = true, value_enum, default_value_t = ChatApp::Discord)]
+    pub chat_app: ChatApp,
+    /// Package the errors belong to, when running as `cargo roost`
+    #[arg(skip)]
+    pub package: Option<Package>,
+    /// Whether the output is a terminal, for `--color auto`
+    #[arg(skip)]
+    pub to_terminal: bool,
+}
+
+impl ExportOptions {
+    pub fn colored(&self) -> bool {
+        self.color
+            .enabled(self.to_terminal || self.ansi_fence || self.format.draws_colors())
+    }
+}
+
+/// FNV-1a, so fingerprints stay stable across roost builds.
+pub fn fingerprint(err: &Diagnostic) -> String {
+    let key = format!(
+        "{}:{}:{}:{}:{}",
+        err.path(),
+        err.lineno(),
+        err.spos(),
+        err.get_errid(),
+        err.summary
+    );
+    let hash = key.bytes().fold(0xcbf29ce484222325_u64, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    });
+
+    format!("{:016x}", hash)
+}
+
+/// The 1-based column of the byte `offset` of `line`, counted in code
+/// points as the JSON and SARIF formats declare.
+fn char_column(line: &str, offset: usize) -> usize {
+    line[..offset].chars().count() + 1
+}
+
+fn each(errs: &[Diagnostic], render: fn(&Diagnostic) -> String) -> String {
+    errs.iter().map(render).collect()
+}
+
+/// The diagnostics of each file together, the files in the order they first
+/// appear in.
+fn group_by_file<'a>(errs: &[Diagnostic<'a>]) -> Vec<Diagnostic<'a>> {
+    let mut grouped = errs.to_vec();
+    grouped.sort_by_key(|err| {
+        errs.iter()
+            .position(|first| first.path() == err.path())
+            .unwrap_or(0)
+    });
+    grouped
+}
+
+/// Writes `errs` in the chosen format.
+pub fn write<W: io::Write>(
+    options: &ExportOptions,
+    render_options: &RenderOptions,
+    errs: &[Diagnostic],
+    mut output: W,
+) -> io::Result<()> {
+    let grouped;
+    let errs = match render_options.group_by_file {
+        true => {
+            grouped = group_by_file(errs);
+            &grouped
+        }
+        false => errs,
+    };
+    // Looked up once, since it reads the environment
+    let colored = options.colored();
+    let rendered: Vec<String> = errs
+        .iter()
+        .enumerate()
+        .map(
+            |(index, err)| match index.checked_sub(1).map(|previous| &errs[previous]) {
+                Some(previous) if render_options.group_by_file && previous.path() == err.path() => {
+                    render::render_grouped(render_options, err)
+                }
+                _ => render::render(render_options, err),
+            },
+        )
+        .map(|rendered| match colored {
+            true => rendered,
+            false => ansi::strip(&rendered),
+        })
+        .collect();
+    let mut joined = rendered.join("\n");
+    if let Some(trailer) = render::trailer(
+        render_options,
+        errs,
+        options
+            .package
+            .as_ref()
+            .map(|package| package.name.as_str()),
+    ) {
+        joined.push('\n');
+        joined.push_str(&match colored {
+            true => trailer,
+            false => ansi::strip(&trailer),
+        });
+    }
+
+    if options.watermark {
+        joined.push_str(&format!(
+            "\n— generated with roost v{} —\n",
+            env!("CARGO_PKG_VERSION")
+        ));
+    }
+
+    let text = match &options.format {
+        Format::Text if options.ci_log => ci_log::render(
+            &joined,
+            &options.ci_command,
+            errs.iter().any(|err| err.severity.is_error()),
+        ),
+        Format::Text => joined,
+        Format::Png => return png::write(&joined, output),
+        Format::Markdown => markdown::render(errs, &joined, options.ansi_fence, options.with_spec),
+        Format::Short => each(errs, short::render),
+        Format::Prose => each(errs, prose::render),
+        Format::Github => each(errs, github::render),
+        Format::GitlabCodequality => gitlab::render(errs),
+        Format::Junit => junit::render(errs, &rendered),
+        Format::Rdjson => rdjson::render(errs, &rendered),
+        Format::Sarif => sarif::render(errs, &rendered),
+        Format::Asciicast => asciicast::render(&joined, &options.cast_command),
+        Format::Chat => chat::render(&joined, options.chat_app),
+        Format::Quickfix => each(errs, quickfix::render),
+        Format::Teamcity => each(errs, teamcity::render),
+        Format::CargoJson => cargo_json::render(errs, &rendered, options.package.as_ref()),
+        Format::Html => html::render(&joined),
+        Format::Svg => svg::render(&joined),
+        Format::UiStderr => ui_stderr::render(errs, render_options),
+        #[cfg(feature = "plugins")]
+        Format::Plugin(name) => {
+            let bytes = plugin::render(name, errs, &joined).map_err(io::Error::other)?;
+            return output.write_all(&bytes);
+        }
+    };
+
+    let text = match colored {
+        true => text,
+        false => ansi::strip(&text),
+    };
+
+    output.write_all(text.as_bytes())
+}
@@ -0,0 +1,99 @@
+//! WebAssembly modules rendering the diagnostics in formats of their own,
+//! each one an extra `--format` named after its file in the plugin
+//! directory.
+//!
+//! A plugin exports its `memory`, an `alloc(len: i32) -> i32` function
+//! returning where roost can write `len` bytes of input, and a `render(ptr:
+//! i32, len: i32) -> i64` one returning where its output is and how long it
+//! is, as `ptr << 32 | len`. The input is a JSON object with the
+//! `diagnostics`, as specs, and their `rendered` text.
+
+use std::{fs, path::PathBuf};
+
+use serde_json::json;
+use wasmi::{Engine, Linker, Module, Store};
+
+use crate::{spec::Spec, Diagnostic, RoostError};
+
+/// The `plugins` directory of the user configuration.
+pub fn dir() -> Option<PathBuf> {
+    crate::user_dir("XDG_CONFIG_HOME", ".config").map(|dir| dir.join("plugins"))
+}
+
+/// The names of the plugins, the ones of their `.wasm` files without the
+/// extension, in order.
+pub fn names() -> Vec<String> {
+    let Some(entries) = dir().and_then(|dir| fs::read_dir(dir).ok()) else {
+        return Vec::new();
+    };
+    let mut names: Vec<String> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "wasm"))
+        .filter_map(|path| Some(path.file_stem()?.to_str()?.to_owned()))
+        .collect();
+    names.sort();
+
+    names
+}
+
+/// Renders `errs` with the plugin `name`, `rendered` being their text as
+/// printed in the terminal.
+pub fn render(name: &str, errs: &[Diagnostic], rendered: &str) -> Result<Vec<u8>, RoostError> {
+    let failed = |details: String| RoostError::OutputError {
+        details: format!("plugin {}: {}", name, details),
+    };
+    let path = dir()
+        .map(|dir| dir.join(format!("{}.wasm", name)))
+        .ok_or_else(|| failed("no configuration directory".to_owned()))?;
+    let wasm = fs::read(&path)
+        .map_err(|err| failed(format!("could not read {}: {}", path.display(), err)))?;
+    let input = json!({
+        "diagnostics": errs.iter().map(Spec::from).collect::<Vec<_>>(),
+        "rendered": rendered,
+    })
+    .to_string();
+
+    let engine = Engine::default();
+    let module = Module::new(&engine, wasm).map_err(|err| failed(err.to_string()))?;
+    let mut store = Store::new(&engine, ());
+    let instance = Linker::<()>::new(&engine)
+        .instantiate_and_start(&mut store, &module)
+        .map_err(|err| failed(err.to_string()))?;
+    let memory = instance
+        .get_memory(&store, "memory")
+        .ok_or_else(|| failed("no exported memory".to_owned()))?;
+    let alloc = instance
+        .get_typed_func::<i32, i32>(&store, "alloc")
+        .map_err(|err| failed(err.to_string()))?;
+    let render = instance
+        .get_typed_func::<(i32, i32), i64>(&store, "render")
+        .map_err(|err| failed(err.to_string()))?;
+
+    let len = i32::try_from(input.len()).map_err(|_| failed("input too long".to_owned()))?;
+    let ptr = alloc
+        .call(&mut store, len)
+        .map_err(|err| failed(err.to_string()))?;
+    memory
+        .write(&mut store, ptr as u32 as usize, input.as_bytes())
+        .map_err(|err| failed(err.to_string()))?;
+    let packed = render
+        .call(&mut store, (ptr, len))
+        .map_err(|err| failed(err.to_string()))?;
+
+    // The output is checked to lie in the memory before it is allocated,
+    // whatever length the plugin reports
+    let (start, len) = ((packed as u64 >> 32) as usize, packed as u32 as usize);
+    if start.saturating_add(len) > memory.data_size(&store) {
+        return Err(failed(format!(
+            "output of {} bytes at {} is out of its memory",
+            len, start
+        )));
+    }
+    let mut output = vec![0; len];
+    memory
+        .read(&store, start, &mut output)
+        .map_err(|err| failed(err.to_string()))?;
+
+    Ok(output)
+}
@@ -0,0 +1,110 @@
+use std::io;
+
+use font8x8::{UnicodeFonts, BASIC_FONTS, BLOCK_FONTS, BOX_FONTS, LATIN_FONTS};
+
+use crate::ansi::{self, Cell, Rgb, BACKGROUND};
+
+const SCALE: usize = 2;
+const GLYPH_SIZE: usize = 8;
+const LEADING: usize = 4;
+const PADDING: usize = 16;
+
+const CELL_WIDTH: usize = GLYPH_SIZE * SCALE;
+const CELL_HEIGHT: usize = (GLYPH_SIZE + LEADING) * SCALE;
+
+/// The pixels of `ch`, a row of 8 to a byte from the top, the leftmost one
+/// the lowest bit.
+pub(crate) fn glyph(ch: char) -> [u8; 8] {
+    BASIC_FONTS
+        .get(ch)
+        .or_else(|| BOX_FONTS.get(ch))
+        .or_else(|| BLOCK_FONTS.get(ch))
+        .or_else(|| LATIN_FONTS.get(ch))
+        .or_else(|| BASIC_FONTS.get('?'))
+        .expect("the basic font has a glyph for '?'")
+}
+
+struct Canvas {
+    width: usize,
+    height: usize,
+    pixels: Vec<u8>,
+}
+
+impl Canvas {
+    fn new(width: usize, height: usize) -> Self {
+        Canvas {
+            width,
+            height,
+            pixels: BACKGROUND.repeat(width * height),
+        }
+    }
+
+    fn put(&mut self, x: usize, y: usize, rgb: Rgb) {
+        if x < self.width && y < self.height {
+            let offset = (y * self.width + x) * 3;
+            self.pixels[offset..offset + 3].copy_from_slice(&rgb);
+        }
+    }
+
+    fn draw_cell(&mut self, column: usize, row: usize, cell: &Cell) {
+        let rgb = ansi::rgb(cell.attrs.fg);
+        let origin_x = PADDING + column * CELL_WIDTH;
+        let origin_y = PADDING + row * CELL_HEIGHT + LEADING * SCALE / 2;
+
+        for (gy, bits) in glyph(cell.ch).iter().enumerate() {
+            for gx in 0..GLYPH_SIZE {
+                if bits & (1 << gx) == 0 {
+                    continue;
+                }
+                for dy in 0..SCALE {
+                    for dx in 0..SCALE {
+                        let x = origin_x + gx * SCALE + dx;
+                        let y = origin_y + gy * SCALE + dy;
+                        self.put(x, y, rgb);
+                        if cell.attrs.bold {
+                            self.put(x + 1, y, rgb);
+                        }
+                    }
+                }
+            }
+        }
+
+        if cell.attrs.underline {
+            let baseline = origin_y + (GLYPH_SIZE + 1) * SCALE;
+            for dx in 0..CELL_WIDTH {
+                // A wave with a period of 4 pixels at scale 1
+                let dy = [0, 1, 1, 0][dx / SCALE % 4] * SCALE;
+                for thickness in 0..SCALE {
+                    self.put(origin_x + dx, baseline + dy + thickness, rgb);
+                }
+            }
+        }
+    }
+}
+
+/// Rasterizes the ANSI-colored `text` with the bundled 8x8 bitmap font.
+pub fn write<W: io::Write>(text: &str, output: W) -> io::Result<()> {
+    let lines = ansi::parse(text);
+    let columns = lines.iter().map(Vec::len).max().unwrap_or(0);
+
+    let mut canvas = Canvas::new(
+        columns * CELL_WIDTH + 2 * PADDING,
+        lines.len() * CELL_HEIGHT + 2 * PADDING,
+    );
+
+    for (row, line) in lines.iter().enumerate() {
+        for (column, cell) in line.iter().enumerate() {
+            canvas.draw_cell(column, row, cell);
+        }
+    }
+
+    let mut encoder = png::Encoder::new(output, canvas.width as u32, canvas.height as u32);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+
+    let mut writer = encoder.write_header().map_err(io::Error::other)?;
+    writer
+        .write_image_data(&canvas.pixels)
+        .map_err(io::Error::other)?;
+    writer.finish().map_err(io::Error::other)
+}
@@ -0,0 +1,90 @@
+use crate::{Diagnostic, Severity};
+
+/// `text` without the backticks quoting code, which screen readers spell
+/// out.
+fn unquoted(text: &str) -> String {
+    text.replace('`', "")
+}
+
+/// `text` as a sentence, ended with a period unless it already is.
+fn sentence(text: &str) -> String {
+    let text = unquoted(text.trim());
+    match text.ends_with(['.', '!', '?']) {
+        true => format!("{} ", text),
+        false => format!("{}. ", text),
+    }
+}
+
+/// Where the bytes from `start` to `end` of the line of `err` are, in
+/// 1-based columns.
+fn columns(err: &Diagnostic, start: usize, end: usize) -> String {
+    let column = |offset: usize| super::char_column(err.line(), offset);
+    let (first, last) = (column(start), column(end).saturating_sub(1));
+    match last <= first {
+        true => format!("column {}", first),
+        false => format!("columns {} to {}", first, last),
+    }
+}
+
+/// Where `err` is, as `src/main.rs line 3, columns 5 to 9`.
+fn location(err: &Diagnostic) -> String {
+    format!(
+        "{} line {}, {}",
+        err.path(),
+        err.lineno(),
+        columns(err, err.spos(), err.epos())
+    )
+}
+
+/// `err` told in plain sentences on a line of its own, without the drawings
+/// of the styles, for screen readers.
+pub fn render(err: &Diagnostic) -> String {
+    let severity = match err.severity {
+        Severity::Error => "Error",
+        Severity::Warning => "Warning",
+    };
+    let mut prose = format!(
+        "{} {} in {}: {}",
+        severity,
+        err.get_errid(),
+        location(err),
+        sentence(&err.summary)
+    );
+
+    prose.push_str(&sentence(&format!("The code reads: {}", err.line().trim())));
+    if !err.message.is_empty() {
+        prose.push_str(&sentence(&err.message.replace('\n', " ")));
+    }
+    for label in &err.labels {
+        prose.push_str(&sentence(&format!(
+            "At {}: {}",
+            columns(err, label.range.start, label.range.end),
+            label.message.replace('\n', " ")
+        )));
+    }
+    if let Some(fixit) = &err.fixit {
+        prose.push_str(&sentence(&format!("Suggested replacement: {}", fixit)));
+    }
+    for note in &err.notes {
+        prose.push_str(&sentence(&format!("Note: {}", note)));
+    }
+    for help in &err.helps {
+        prose.push_str(&sentence(&format!("Help: {}", help)));
+    }
+    for note in &err.related {
+        prose.push_str(&format!(
+            "Note in {}: {}",
+            location(note),
+            sentence(&note.summary)
+        ));
+        prose.push_str(&sentence(&format!(
+            "The code reads: {}",
+            note.line().trim()
+        )));
+        if !note.message.is_empty() {
+            prose.push_str(&sentence(&note.message.replace('\n', " ")));
+        }
+    }
+
+    format!("{}\n", prose.trim_end())
+}
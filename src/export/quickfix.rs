@@ -0,0 +1,21 @@
+use crate::{Diagnostic, Severity};
+
+/// Matches Vim's `%f:%l:%c:%t: %m` errorformat and Emacs' compilation-mode.
+pub fn render(err: &Diagnostic) -> String {
+    let mut message = format!("[{}] {}", err.get_errid(), err.summary);
+    if !err.message.is_empty() {
+        message.push_str(&format!(": {}", err.message));
+    }
+
+    format!(
+        "{}:{}:{}:{}: {}\n",
+        err.path(),
+        err.lineno(),
+        err.spos() + 1,
+        match err.severity {
+            Severity::Error => 'E',
+            Severity::Warning => 'W',
+        },
+        message
+    )
+}
@@ -0,0 +1,59 @@
+use serde_json::{json, Value};
+
+use crate::{ansi, Diagnostic};
+
+fn range(err: &Diagnostic) -> Value {
+    json!({
+        "start": { "line": err.lineno(), "column": err.spos() + 1 },
+        "end": { "line": err.lineno(), "column": err.epos() + 1 },
+    })
+}
+
+fn diagnostic(err: &Diagnostic, rendered: &str) -> Value {
+    let suggestions: Vec<Value> = err
+        .fixit
+        .iter()
+        .map(|text| json!({ "range": range(err), "text": text }))
+        .collect();
+    let related: Vec<Value> = err
+        .related
+        .iter()
+        .map(|note| {
+            json!({
+                "message": note.summary,
+                "location": { "path": note.path(), "range": range(note) },
+            })
+        })
+        .collect();
+
+    let mut diagnostic = json!({
+            "message": format!("{}: {}", err.summary, err.message),
+            "location": { "path": err.path(), "range": range(err) },
+            "severity": err.severity.to_string().to_uppercase(),
+            "source": { "name": "roost" },
+            "code": { "value": err.get_errid() },
+            "suggestions": suggestions,
+            "original_output": ansi::strip(rendered),
+    });
+    if !related.is_empty() {
+        diagnostic["related_locations"] = Value::from(related);
+    }
+
+    diagnostic
+}
+
+pub fn render(errs: &[Diagnostic], rendered: &[String]) -> String {
+    let diagnostics: Vec<Value> = errs
+        .iter()
+        .zip(rendered)
+        .map(|(err, rendered)| diagnostic(err, rendered))
+        .collect();
+
+    let result = json!({
+        "source": { "name": "roost" },
+        "severity": "ERROR",
+        "diagnostics": diagnostics,
+    });
+
+    format!("{:#}\n", result)
+}
@@ -0,0 +1,112 @@
+use serde_json::{json, Value};
+
+use super::char_column;
+use crate::{ansi, Diagnostic, Severity};
+
+/// The span of `err` in its line, quoted as the snippet.
+fn region(err: &Diagnostic) -> Value {
+    json!({
+        "startLine": err.lineno(),
+        "startColumn": char_column(err.line(), err.spos()),
+        "endLine": err.lineno(),
+        "endColumn": char_column(err.line(), err.epos()),
+        "snippet": { "text": err.line() },
+    })
+}
+
+fn location(err: &Diagnostic) -> Value {
+    json!({
+        "physicalLocation": {
+            "artifactLocation": { "uri": err.path() },
+            "region": region(err),
+        },
+    })
+}
+
+fn result(err: &Diagnostic, rendered: &str) -> Value {
+    let related: Vec<Value> = err
+        .related
+        .iter()
+        .enumerate()
+        .map(|(index, note)| {
+            let mut related = location(note);
+            related["id"] = Value::from(index);
+            related["message"] = json!({ "text": note.summary });
+            related
+        })
+        .collect();
+    let fixes: Vec<Value> = err
+        .fixit
+        .iter()
+        .map(|text| {
+            json!({
+                "description": { "text": format!("replace with `{}`", text) },
+                "artifactChanges": [{
+                    "artifactLocation": { "uri": err.path() },
+                    "replacements": [{
+                        "deletedRegion": region(err),
+                        "insertedContent": { "text": text },
+                    }],
+                }],
+            })
+        })
+        .collect();
+
+    let mut result = json!({
+        "ruleId": err.get_errid(),
+        "level": match err.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        },
+        "message": {
+            "text": match err.message.is_empty() {
+                true => err.summary.to_string(),
+                false => format!("{}: {}", err.summary, err.message),
+            },
+        },
+        "locations": [location(err)],
+        "properties": { "rendered": ansi::strip(rendered) },
+    });
+    if !related.is_empty() {
+        result["relatedLocations"] = Value::from(related);
+    }
+    if !fixes.is_empty() {
+        result["fixes"] = Value::from(fixes);
+    }
+
+    result
+}
+
+/// A SARIF 2.1.0 log of one run of roost, a result per diagnostic and a
+/// rule per code.
+pub fn render(errs: &[Diagnostic], rendered: &[String]) -> String {
+    let mut rules: Vec<String> = errs.iter().map(Diagnostic::get_errid).collect();
+    rules.sort();
+    rules.dedup();
+    let results: Vec<Value> = errs
+        .iter()
+        .zip(rendered)
+        .map(|(err, rendered)| result(err, rendered))
+        .collect();
+
+    let log = json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "roost",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "rules": rules
+                        .iter()
+                        .map(|id| json!({ "id": id }))
+                        .collect::<Vec<_>>(),
+                },
+            },
+            "columnKind": "unicodeCodePoints",
+            "results": results,
+        }],
+    });
+
+    format!("{:#}\n", log)
+}
@@ -0,0 +1,21 @@
+use crate::{bold, color, Diagnostic, Severity};
+
+pub fn render(err: &Diagnostic) -> String {
+    format!(
+        "{}{}{}\n",
+        bold(format!(
+            "{}:{}:{}: ",
+            err.path(),
+            err.lineno(),
+            err.spos() + 1
+        )),
+        bold(color(
+            format!("{}[{}]", err.severity, err.get_errid()),
+            match err.severity {
+                Severity::Error => 1,
+                Severity::Warning => 3,
+            }
+        )),
+        bold(format!(": {}", err.summary)),
+    )
+}
@@ -0,0 +1,58 @@
+use crate::ansi::{self, BACKGROUND, FOREGROUND};
+
+const FONT_SIZE: f64 = 14.0;
+const CELL_WIDTH: f64 = FONT_SIZE * 0.6;
+const LINE_HEIGHT: f64 = FONT_SIZE * 1.4;
+const PADDING: f64 = 16.0;
+
+fn px(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+pub fn render(rendered: &str) -> String {
+    let lines = ansi::parse(rendered);
+    let columns = lines.iter().map(Vec::len).max().unwrap_or(0);
+    let width = px(columns as f64 * CELL_WIDTH + 2.0 * PADDING);
+    let height = px(lines.len() as f64 * LINE_HEIGHT + 2.0 * PADDING);
+
+    let mut string = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" viewBox=\"0 0 {} {}\">\n",
+        width, height, width, height
+    );
+    string.push_str(&format!(
+        "<rect width=\"100%\" height=\"100%\" fill=\"{}\"/>\n",
+        ansi::hex(BACKGROUND)
+    ));
+    string.push_str(&format!(
+        "<g font-family=\"monospace\" font-size=\"{}\" fill=\"{}\" xml:space=\"preserve\">\n",
+        FONT_SIZE,
+        ansi::hex(FOREGROUND)
+    ));
+
+    for (row, line) in lines.iter().enumerate() {
+        let y = px(PADDING + (row as f64 + 0.75) * LINE_HEIGHT);
+        let mut column = 0;
+
+        string.push_str(&format!("<text y=\"{}\">", y));
+        for (attrs, text) in ansi::runs(line) {
+            let x = px(PADDING + column as f64 * CELL_WIDTH);
+            column += text.chars().count();
+
+            string.push_str(&format!("<tspan x=\"{}\"", x));
+            if let Some(color) = attrs.fg {
+                string.push_str(&format!(" fill=\"{}\"", ansi::hex(ansi::rgb(Some(color)))));
+            }
+            if attrs.bold {
+                string.push_str(" font-weight=\"bold\"");
+            }
+            if attrs.underline {
+                string.push_str(" text-decoration=\"underline\"");
+            }
+            string.push_str(&format!(">{}</tspan>", ansi::escape_xml(&text)));
+        }
+        string.push_str("</text>\n");
+    }
+
+    string.push_str("</g>\n</svg>\n");
+    string
+}
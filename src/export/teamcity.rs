@@ -0,0 +1,39 @@
+use super::fingerprint;
+use crate::Diagnostic;
+
+fn escape(value: &str) -> String {
+    value
+        .replace('|', "||")
+        .replace('\'', "|'")
+        .replace('\n', "|n")
+        .replace('\r', "|r")
+        .replace('[', "|[")
+        .replace(']', "|]")
+}
+
+pub fn render(err: &Diagnostic) -> String {
+    let errid = err.get_errid();
+    let description = escape(&format!("{}[{}]: {}", err.severity, errid, err.summary));
+
+    let mut string = format!(
+        "##teamcity[inspectionType id='{}' name='{}' category='roost' description='{}']\n",
+        errid,
+        escape(&err.summary),
+        description
+    );
+    string.push_str(&format!(
+        "##teamcity[inspection typeId='{}' message='{}' file='{}' line='{}' SEVERITY='{}']\n",
+        errid,
+        escape(&err.message),
+        escape(err.path()),
+        err.lineno(),
+        err.severity.to_string().to_uppercase()
+    ));
+    string.push_str(&format!(
+        "##teamcity[buildProblem description='{}' identity='{}']\n",
+        description,
+        fingerprint(err)
+    ));
+
+    string
+}
@@ -0,0 +1,110 @@
+use std::path::Path;
+
+use crate::{
+    ansi,
+    render::{self, counted, Emitter, Engine, Hyperlinks, RenderOptions},
+    Diagnostic, DEFAULT_PATH,
+};
+
+/// What the line numbers of the gutter read as in the expected output of
+/// UI tests, which is also how wide the gutter is.
+const ANONYMIZED_LINENO: &str = "LL";
+
+/// `line` with the gutter of `width` columns it starts with narrowed to the
+/// one of `LL`, the line number it may hold replaced by `LL`.
+fn anonymize(line: &str, width: usize) -> String {
+    let Some((gutter, rest)) = line.split_at_checked(width) else {
+        return line.to_owned();
+    };
+    if !(rest.starts_with(' ') || rest.starts_with("-->")) || gutter.is_empty() {
+        return line.to_owned();
+    }
+    let number = gutter.trim_start();
+
+    if number.is_empty() {
+        format!("{}{}", " ".repeat(ANONYMIZED_LINENO.len()), rest)
+    } else if number.chars().all(|c| c.is_ascii_digit()) {
+        format!("{}{}", ANONYMIZED_LINENO, rest)
+    } else {
+        line.to_owned()
+    }
+}
+
+/// The path of `err` and its notes with their directory replaced by `$DIR`,
+/// as compiletest writes the directory of the test.
+fn normalize_paths(rendered: &str, err: &Diagnostic) -> String {
+    std::iter::once(err)
+        .chain(&err.related)
+        .map(Diagnostic::path)
+        .filter(|path| *path != DEFAULT_PATH)
+        .fold(rendered.to_owned(), |rendered, path| {
+            let name = Path::new(path)
+                .file_name()
+                .map_or(path.into(), |name| name.to_string_lossy());
+            rendered.replace(&format!("{}:", path), &format!("$DIR/{}:", name))
+        })
+}
+
+/// The diagnostics as the `.stderr` file of a rustc UI test expects them:
+/// rustc's style without colors, the line numbers of the gutters replaced
+/// by `LL` and the paths by `$DIR`, no trailing whitespace nor empty gutter
+/// line closing the snippets, and the count of errors and the explain hint
+/// at the end.
+pub fn render(errs: &[Diagnostic], options: &RenderOptions) -> String {
+    let options = RenderOptions {
+        style: Emitter::Rustc,
+        engine: Engine::Roost,
+        hyperlinks: Hyperlinks::Never,
+        locale: None,
+        emoji: false,
+        boxed: false,
+        banner: None,
+        group_by_file: false,
+        max_width: None,
+        quiet: false,
+        verbose: false,
+        explained: Default::default(),
+        ..options.clone()
+    };
+
+    let mut stderr = String::new();
+    for err in errs {
+        let width = render::lineno_width(err);
+        let rendered = normalize_paths(&ansi::strip(&render::render(&options, err)), err);
+        let mut lines: Vec<String> = rendered
+            .lines()
+            .map(|line| anonymize(line, width).trim_end().to_owned())
+            .collect();
+        // The empty gutter line closing the snippet is left out by rustc now
+        if lines.last().is_some_and(|line| line.trim() == "|") {
+            lines.pop();
+        }
+        for line in lines {
+            stderr.push_str(&line);
+            stderr.push('\n');
+        }
+        stderr.push('\n');
+    }
+
+    let errors = errs.iter().filter(|err| err.severity.is_error()).count();
+    let warnings = errs.len() - errors;
+    let emitted = format!("{} emitted", counted(warnings, "warning"));
+    match (errors, warnings) {
+        (0, 0) => {}
+        (0, _) => stderr.push_str(&format!("warning: {}\n\n", emitted)),
+        (_, 0) => stderr.push_str(&format!(
+            "error: aborting due to {}\n\n",
+            counted(errors, "previous error")
+        )),
+        _ => stderr.push_str(&format!(
+            "error: aborting due to {}; {}\n\n",
+            counted(errors, "previous error"),
+            emitted
+        )),
+    }
+    if let Some(hint) = render::explain_hint(errs) {
+        stderr.push_str(&ansi::strip(&hint));
+    }
+
+    stderr
+}
@@ -0,0 +1,124 @@
+//! `Arbitrary` diagnostics for the fuzz targets of `fuzz/`: valid ones, as
+//! the builder accepts them, with lines of any characters but the control
+//! ones, which no terminal aligns.
+
+use std::ops::Range;
+
+use arbitrary::{Arbitrary, Unstructured};
+
+use crate::{source::Span, Diagnostic, Label, Severity};
+
+/// An arbitrary string of a single line, its control characters replaced
+/// by spaces.
+fn line(u: &mut Unstructured) -> arbitrary::Result<String> {
+    let text: String = u.arbitrary()?;
+
+    Ok(text
+        .chars()
+        .map(|c| if c.is_control() { ' ' } else { c })
+        .collect())
+}
+
+/// An arbitrary range of whole characters of `line`, empty only at its end.
+fn range(u: &mut Unstructured, line: &str) -> arbitrary::Result<Range<usize>> {
+    let boundaries: Vec<usize> = line
+        .char_indices()
+        .map(|(index, _)| index)
+        .chain([line.len()])
+        .collect();
+    let start = *u.choose(&boundaries)?;
+    let end = *u.choose(&boundaries)?;
+    let (start, end) = (start.min(end), start.max(end));
+
+    Ok(match start == end {
+        true => match boundaries.iter().find(|&&boundary| boundary > start) {
+            Some(&next) => start..next,
+            None => start..end,
+        },
+        false => start..end,
+    })
+}
+
+impl<'a> Arbitrary<'a> for Span {
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        let (start, len): (u16, u16) = u.arbitrary()?;
+        let start = usize::from(start);
+
+        Ok(Span {
+            file: Default::default(),
+            range: start..start + usize::from(len),
+        })
+    }
+}
+
+/// A diagnostic with labels, context lines and a fix-it, whose related
+/// notes go one level deep.
+fn diagnostic(u: &mut Unstructured, depth: usize) -> arbitrary::Result<Diagnostic<'static>> {
+    let line = line(u)?;
+    let span = range(u, &line)?;
+    let mut labels = Vec::new();
+    if !line.is_empty() {
+        for _ in 0..u.int_in_range(0..=3)? {
+            labels.push(Label {
+                range: range(u, &line)?,
+                message: line_or_lines(u)?,
+                color: u.arbitrary()?,
+            });
+        }
+    }
+    let summary = match line_or_lines(u)? {
+        summary if summary.is_empty() => "summary".to_owned(),
+        summary => summary,
+    };
+
+    let mut builder = Diagnostic::builder()
+        .severity(u.arbitrary::<Severity>()?)
+        .summary(summary)
+        .line(line)
+        .span(span)
+        .message(line_or_lines(u)?)
+        .lineno(u.int_in_range(3..=100_000)?)
+        .path(self::line(u)?)
+        .code(u.int_in_range(0..=9999)?)
+        .fixit_diff(u.arbitrary()?)
+        .context_before(
+            (0..u.int_in_range(0..=2)?)
+                .map(|_| self::line(u))
+                .collect::<Result<_, _>>()?,
+        )
+        .context_after(
+            (0..u.int_in_range(0..=2)?)
+                .map(|_| self::line(u))
+                .collect::<Result<_, _>>()?,
+        );
+    for label in labels {
+        builder = builder.label(label);
+    }
+    if u.arbitrary()? {
+        builder = builder.fixit(self::line(u)?);
+    }
+    if depth == 0 {
+        for _ in 0..u.int_in_range(0..=2)? {
+            builder = builder.related(diagnostic(u, depth + 1)?);
+        }
+    }
+
+    builder
+        .build()
+        .map_err(|_| arbitrary::Error::IncorrectFormat)
+}
+
+/// A label text, which may have a few lines.
+fn line_or_lines(u: &mut Unstructured) -> arbitrary::Result<String> {
+    let lines = (0..u.int_in_range(0..=2)?)
+        .map(|_| line(u))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(lines.join("\n"))
+}
+
+impl<'a> Arbitrary<'a> for Diagnostic<'static> {
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        diagnostic(u, 0)
+    }
+}
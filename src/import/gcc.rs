@@ -0,0 +1,171 @@
+use super::{byte_offset, read_line, span_in};
+use crate::{ansi, source::SourceMap, Diagnostic, RoostError, Severity, DEFAULT_ERRNUM};
+
+struct Header {
+    severity: Severity,
+    path: String,
+    lineno: usize,
+    column: usize,
+    message: String,
+}
+
+/// Parses `file:line:col: error: message`, also accepting `fatal error` and
+/// `warning`.
+fn parse_header(line: &str) -> Option<Header> {
+    let (severity, (location, message)) = [
+        (Severity::Error, ": error: "),
+        (Severity::Error, ": fatal error: "),
+        (Severity::Warning, ": warning: "),
+    ]
+    .into_iter()
+    .find_map(|(severity, keyword)| Some((severity, line.split_once(keyword)?)))?;
+
+    let mut parts = location.rsplitn(3, ':');
+    let column = parts.next()?.parse().ok()?;
+    let lineno = parts.next()?.parse().ok()?;
+    let path = parts.next()?.to_owned();
+
+    let message = match message.rfind(" [-W") {
+        Some(index) if message.ends_with(']') => &message[..index],
+        _ => message,
+    };
+
+    Some(Header {
+        severity,
+        path,
+        lineno,
+        column,
+        message: message.to_owned(),
+    })
+}
+
+/// Removes the `  3 | ` gutter printed by GCC 9+ and clang 17+.
+fn strip_gutter(line: &str) -> &str {
+    match line.split_once('|') {
+        Some((gutter, rest)) if gutter.trim().chars().all(|c| c.is_ascii_digit()) => {
+            rest.strip_prefix(' ').unwrap_or(rest)
+        }
+        _ => line,
+    }
+}
+
+fn is_caret_line(line: &str) -> bool {
+    line.contains('^') && line.chars().all(|c| matches!(c, ' ' | '^' | '~'))
+}
+
+fn to_diagnostic(header: Header, body: &[&str]) -> Option<Diagnostic<'static>> {
+    let body: Vec<&str> = body.iter().map(|line| strip_gutter(line)).collect();
+    let caret = body.iter().position(|line| is_caret_line(line));
+
+    let line = match caret {
+        Some(index) if index > 0 => body[index - 1].to_owned(),
+        _ => read_line(&header.path, header.lineno)?,
+    };
+    if line.is_empty() {
+        return None;
+    }
+
+    let (spos, epos) = match caret {
+        Some(index) => {
+            let markers = body[index];
+            let start = markers.find(['^', '~'])?;
+            let end = markers.rfind(['^', '~'])? + 1;
+            (byte_offset(&line, start + 1), byte_offset(&line, end + 1))
+        }
+        None => {
+            let spos = byte_offset(&line, header.column);
+            (spos, spos + 1)
+        }
+    };
+    let span = span_in(&line, spos, epos);
+
+    // GCC labels are printed under a `|` leg below the carets
+    let message = caret
+        .and_then(|index| body.get(index + 1..index + 3))
+        .filter(|label| label[0].trim() == "|")
+        .map(|label| label[1].trim().to_owned())
+        .unwrap_or_default();
+
+    let (sources, span) = SourceMap::quote(header.path, header.lineno, &line, span, &[], &[]);
+
+    Some(Diagnostic {
+        severity: header.severity,
+        summary: header.message.into(),
+        message: message.into(),
+        span,
+        sources,
+        errnum: DEFAULT_ERRNUM,
+        ..Default::default()
+    })
+}
+
+pub fn parse(contents: &str) -> Result<Vec<Diagnostic<'static>>, RoostError> {
+    let contents = ansi::strip(contents);
+    let lines: Vec<&str> = contents.lines().collect();
+    let mut errs = Vec::new();
+
+    let mut index = 0;
+    while index < lines.len() {
+        let Some(header) = parse_header(lines[index]) else {
+            index += 1;
+            continue;
+        };
+
+        let body_start = index + 1;
+        let body_end = lines[body_start..]
+            .iter()
+            .position(|line| line.contains(": ") && !line.starts_with(' '))
+            .map_or(lines.len(), |offset| body_start + offset);
+
+        errs.extend(to_diagnostic(header, &lines[body_start..body_end]));
+        index = body_end;
+    }
+
+    Ok(errs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_errors_and_warnings() {
+        let text = "src/demo.c: In function 'main':
+src/demo.c:4:13: error: 'y' undeclared (first use in this function)
+    4 |     int x = y + 1;
+      |             ^
+src/demo.c:5:9: warning: unused variable 'z' [-Wunused-variable]
+    5 |     int z;
+      |         ^
+";
+        let errs = parse(text).unwrap();
+
+        assert_eq!(errs.len(), 2);
+        assert_eq!(errs[0].severity, Severity::Error);
+        assert_eq!(
+            errs[0].summary,
+            "'y' undeclared (first use in this function)"
+        );
+        assert_eq!((errs[0].path(), errs[0].lineno()), ("src/demo.c", 4));
+        assert_eq!(errs[0].line(), "    int x = y + 1;");
+        assert_eq!(errs[0].spos()..errs[0].epos(), 12..13);
+        assert_eq!(errs[1].severity, Severity::Warning);
+        assert_eq!(errs[1].summary, "unused variable 'z'");
+        assert_eq!(errs[1].spos()..errs[1].epos(), 8..9);
+    }
+
+    #[test]
+    fn parse_label_under_the_carets() {
+        let text = "demo.c:3:5: error: too few arguments to function 'f'
+    3 |     f(1);
+      |     ^~~~
+      |     |
+      |     expected 2 arguments
+";
+        let errs = parse(text).unwrap();
+
+        assert_eq!(errs.len(), 1);
+        assert_eq!(&errs[0].line()[errs[0].spos()..errs[0].epos()], "f(1)");
+        assert_eq!(errs[0].message, "expected 2 arguments");
+    }
+}
@@ -0,0 +1,103 @@
+use std::{
+    fs,
+    io::{self, Read},
+    ops::Range,
+    path::PathBuf,
+};
+
+use clap::ValueEnum;
+
+use crate::{
+    position::{ceil_char_boundary, floor_char_boundary},
+    Diagnostic, RoostError,
+};
+
+pub mod gcc;
+pub mod python;
+pub mod rustc;
+pub mod rustc_json;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Source {
+    /// rustc or cargo text diagnostics, as printed in the terminal
+    Rustc,
+    /// `cargo build --message-format=json` or `rustc --error-format=json`
+    RustcJson,
+    /// GCC or clang text diagnostics
+    Gcc,
+    /// CPython traceback
+    PythonTraceback,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct ImportArgs {
+    /// Format of the imported diagnostics
+    #[arg(long, value_enum)]
+    pub from: Source,
+    /// File to import, standard input if omitted or `-`
+    pub file: Option<PathBuf>,
+}
+
+/// The contents of `file`, or of standard input when it is omitted or `-`.
+pub fn read_input(file: Option<&PathBuf>) -> Result<String, RoostError> {
+    let mut contents = String::new();
+
+    match file {
+        Some(path) if path.as_os_str() != "-" => {
+            contents = fs::read_to_string(path).map_err(|err| RoostError::ImportError {
+                details: format!("could not read {}: {}", path.display(), err),
+            })?
+        }
+        _ => {
+            io::stdin()
+                .read_to_string(&mut contents)
+                .map_err(|err| RoostError::ImportError {
+                    details: format!("could not read standard input: {}", err),
+                })?;
+        }
+    }
+
+    Ok(contents)
+}
+
+pub fn run(args: &ImportArgs) -> Result<Vec<Diagnostic<'static>>, RoostError> {
+    let contents = read_input(args.file.as_ref())?;
+
+    match args.from {
+        Source::Rustc => rustc::parse(&contents),
+        Source::RustcJson => rustc_json::parse(&contents),
+        Source::Gcc => gcc::parse(&contents),
+        Source::PythonTraceback => python::parse(&contents),
+    }
+}
+
+/// Converts a 1-based character column of `line` into a byte offset.
+pub fn byte_offset(line: &str, column: usize) -> usize {
+    line.char_indices()
+        .nth(column.saturating_sub(1))
+        .map_or(line.len(), |(offset, _)| offset)
+}
+
+/// The line `lineno` of the file at `path`, for the diagnostics that do not
+/// quote it.
+fn read_line(path: &str, lineno: usize) -> Option<String> {
+    fs::read_to_string(path)
+        .ok()?
+        .lines()
+        .nth(lineno.checked_sub(1)?)
+        .map(str::to_owned)
+}
+
+/// The span from `spos` to `epos` of the non-empty `line`, moved into it,
+/// at least one character long and on character boundaries.
+fn span_in(line: &str, spos: usize, epos: usize) -> Range<usize> {
+    let spos = floor_char_boundary(line, spos.min(line.len() - 1));
+    spos..ceil_char_boundary(line, epos.clamp(spos + 1, line.len()))
+}
+
+/// Extracts the number of codes such as `E0308`.
+pub fn errnum(code: &str) -> Option<usize> {
+    code.trim_start_matches(|c: char| !c.is_ascii_digit())
+        .parse()
+        .ok()
+}
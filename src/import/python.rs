@@ -0,0 +1,141 @@
+use super::{read_line, span_in};
+use crate::{ansi, source::SourceMap, Diagnostic, RoostError, DEFAULT_ERRNUM};
+
+struct Frame {
+    path: String,
+    lineno: usize,
+    code: Option<String>,
+    anchors: Option<String>,
+}
+
+/// Parses `File "path", line N, in function`, where `, in function` is
+/// missing for syntax errors.
+fn parse_frame_header(line: &str) -> Option<(String, usize)> {
+    let rest = line.trim_start().strip_prefix("File \"")?;
+    let (path, rest) = rest.split_once('"')?;
+    let rest = rest.strip_prefix(", line ")?;
+    let lineno = rest.split(',').next()?.trim().parse().ok()?;
+
+    Some((path.to_owned(), lineno))
+}
+
+fn is_anchor_line(line: &str) -> bool {
+    line.contains(['^', '~']) && line.chars().all(|c| matches!(c, ' ' | '^' | '~'))
+}
+
+pub fn parse(contents: &str) -> Result<Vec<Diagnostic<'static>>, RoostError> {
+    let contents = ansi::strip(contents);
+    let lines: Vec<&str> = contents.lines().collect();
+    let invalid = |details: &str| RoostError::ImportError {
+        details: details.to_owned(),
+    };
+
+    let frame_index = lines
+        .iter()
+        .rposition(|line| parse_frame_header(line).is_some())
+        .ok_or_else(|| invalid("no `File \"...\", line N` frame found"))?;
+    let (path, lineno) = parse_frame_header(lines[frame_index]).expect("checked above");
+
+    let mut frame = Frame {
+        path,
+        lineno,
+        code: None,
+        anchors: None,
+    };
+    for line in &lines[frame_index + 1..] {
+        if !line.starts_with(' ') {
+            break;
+        }
+        if frame.code.is_none() {
+            frame.code = Some(line.trim().to_owned());
+        } else if is_anchor_line(line) {
+            frame.anchors = Some(line.trim_end().to_owned());
+        }
+    }
+
+    let exception = lines
+        .iter()
+        .rev()
+        .find(|line| !line.trim().is_empty() && !line.starts_with(' '))
+        .ok_or_else(|| invalid("no exception found"))?;
+    let (exception_type, exception_message) =
+        exception.split_once(": ").unwrap_or((exception.trim(), ""));
+
+    let code = frame.code.unwrap_or_default();
+    let line = read_line(&frame.path, frame.lineno).unwrap_or_else(|| code.clone());
+    if line.trim().is_empty() {
+        return Err(invalid("the last frame has no source line"));
+    }
+
+    // the traceback shows the line without its indentation, 4 spaces in
+    let indent = line.len() - line.trim_start().len();
+    let (spos, epos) = match &frame.anchors {
+        Some(anchors) => {
+            let start = anchors.find(['^', '~']).unwrap_or(4).saturating_sub(4);
+            let end = anchors.rfind(['^', '~']).map_or(start + 1, |end| end - 3);
+            (indent + start, indent + end)
+        }
+        None => (indent, indent + line.trim().len()),
+    };
+    let span = span_in(&line, spos, epos);
+
+    let (sources, span) = SourceMap::quote(frame.path, frame.lineno, &line, span, &[], &[]);
+
+    Ok(vec![Diagnostic {
+        summary: match exception_message {
+            "" => exception_type.to_owned(),
+            message => message.to_owned(),
+        }
+        .into(),
+        message: exception_type.to_owned().into(),
+        span,
+        sources,
+        errnum: DEFAULT_ERRNUM,
+        ..Default::default()
+    }])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_last_frame_and_anchors() {
+        let text = r#"Traceback (most recent call last):
+  File "demo/main.py", line 9, in <module>
+    main()
+  File "demo/divide.py", line 3, in main
+    print(1 / zero)
+          ~~^~~~~~
+ZeroDivisionError: division by zero
+"#;
+        let errs = parse(text).unwrap();
+
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].summary, "division by zero");
+        assert_eq!(errs[0].message, "ZeroDivisionError");
+        assert_eq!((errs[0].path(), errs[0].lineno()), ("demo/divide.py", 3));
+        assert_eq!(&errs[0].line()[errs[0].spos()..errs[0].epos()], "1 / zero");
+    }
+
+    #[test]
+    fn parse_syntax_error() {
+        let text = r#"  File "demo/main.py", line 1
+    x = = 1
+        ^
+SyntaxError: invalid syntax
+"#;
+        let errs = parse(text).unwrap();
+
+        assert_eq!(errs[0].summary, "invalid syntax");
+        assert_eq!(errs[0].spos()..errs[0].epos(), 4..5);
+    }
+
+    #[test]
+    fn parse_refuses_text_without_frame() {
+        assert!(matches!(
+            parse("ValueError: oops\n"),
+            Err(RoostError::ImportError { .. })
+        ));
+    }
+}
@@ -0,0 +1,282 @@
+//! rustc's diagnostics as printed in the terminal, such as pasted from a
+//! build.
+
+use unicode_width::UnicodeWidthChar;
+
+use super::errnum;
+use crate::{ansi, source::SourceMap, Diagnostic, Label, RoostError, Severity, DEFAULT_ERRNUM};
+
+struct Header {
+    level: Level,
+    code: Option<String>,
+    summary: String,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Level {
+    Diagnostic(Severity),
+    Note,
+    Help,
+}
+
+/// Parses `error[E0308]: summary`, also accepting `warning`, `note` and
+/// `help`.
+fn parse_header(line: &str) -> Option<Header> {
+    let (head, summary) = line.split_once(": ")?;
+    let (level, code) = match head.split_once('[') {
+        Some((level, code)) => (level, Some(code.strip_suffix(']')?.to_owned())),
+        None => (head, None),
+    };
+    let level = match level {
+        "error" => Level::Diagnostic(Severity::Error),
+        "warning" => Level::Diagnostic(Severity::Warning),
+        "note" => Level::Note,
+        "help" => Level::Help,
+        _ => return None,
+    };
+
+    Some(Header {
+        level,
+        code,
+        summary: summary.to_owned(),
+    })
+}
+
+/// The text after the gutter of `line`, such as `4 | ` or `  | `, and
+/// whether the gutter has a line number.
+fn strip_gutter(line: &str) -> Option<(&str, bool)> {
+    let (gutter, rest) = line.split_once('|')?;
+    let gutter = gutter.trim();
+    if !gutter.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    Some((rest.strip_prefix(' ').unwrap_or(rest), !gutter.is_empty()))
+}
+
+/// The runs of `^` or `-` of a marker line, as their display columns and
+/// whether they are primary.
+fn marks(markers: &str) -> Vec<(usize, usize, bool)> {
+    let mut marks: Vec<(usize, usize, bool)> = Vec::new();
+    for (column, c) in markers.chars().enumerate() {
+        let primary = match c {
+            '^' => true,
+            '-' => false,
+            _ => continue,
+        };
+        match marks.last_mut() {
+            Some((_, end, last)) if *end == column && *last == primary => *end += 1,
+            _ => marks.push((column, column + 1, primary)),
+        }
+    }
+
+    marks
+}
+
+/// The byte offset of `line` at the display `column`, where rustc aligns
+/// its markers, so that wide characters take two columns.
+fn display_offset(line: &str, column: usize) -> usize {
+    let mut width = 0;
+    for (offset, c) in line.char_indices() {
+        if width >= column {
+            return offset;
+        }
+        width += c.width().unwrap_or(0);
+    }
+
+    line.len()
+}
+
+fn is_marker_line(line: &str) -> bool {
+    line.contains(['^', '-'])
+        && line.chars().all(|c| matches!(c, ' ' | '^' | '-'))
+        && !line.trim().is_empty()
+}
+
+/// The diagnostic of `header` and of the lines under it, or `None` when
+/// they quote no code.
+fn to_diagnostic(header: &Header, body: &[&str]) -> Option<Diagnostic<'static>> {
+    let location = body
+        .iter()
+        .find_map(|line| line.trim_start().strip_prefix("--> "))?;
+    let mut parts = location.rsplitn(3, ':');
+    let (_, lineno, path) = (parts.next()?, parts.next()?, parts.next()?);
+    let lineno: usize = lineno.parse().ok()?;
+
+    // The quoted line, then the markers under it and the legs of their
+    // labels
+    let snippet: Vec<(&str, bool)> = body.iter().filter_map(|line| strip_gutter(line)).collect();
+    let quoted = snippet
+        .iter()
+        .position(|&(text, numbered)| numbered && !text.is_empty() && !is_marker_line(text))?;
+    let line = snippet[quoted].0.to_owned();
+    let markers = snippet.get(quoted + 1).map(|&(text, _)| text)?;
+    let (text_start, legs) = match markers.find(|c: char| !matches!(c, ' ' | '^' | '-')) {
+        Some(index) => markers.split_at(index),
+        None => (markers, ""),
+    };
+    let legs_text = legs.trim();
+    let marks = marks(text_start);
+    let below: Vec<&str> = snippet[quoted + 2..]
+        .iter()
+        .take_while(|&&(text, numbered)| !numbered && !text.trim().is_empty())
+        .map(|&(text, _)| text)
+        .collect();
+    // The text of a mark is after it when it is the last one of the line,
+    // else at its column at the end of its leg
+    let label_text = |index: usize, start: usize| -> String {
+        if index + 1 == marks.len() {
+            return legs_text.to_owned();
+        }
+        below
+            .iter()
+            .filter_map(|text| text.get(start..))
+            .find(|rest| !rest.starts_with(['|', ' ']))
+            .map_or(String::new(), |rest| rest.trim().to_owned())
+    };
+
+    let primary = marks.iter().position(|&(_, _, primary)| primary)?;
+    let range = |start: usize, end: usize| {
+        display_offset(&line, start)..display_offset(&line, end).max(display_offset(&line, start))
+    };
+    let labels: Vec<Label> = marks
+        .iter()
+        .enumerate()
+        .filter(|&(index, _)| index != primary)
+        .map(|(index, &(start, end, _))| Label {
+            range: range(start, end),
+            message: label_text(index, start),
+            color: None,
+        })
+        .collect();
+    let (start, end, _) = marks[primary];
+    let span = range(start, end);
+    let message = label_text(primary, start);
+
+    let footers = |kind: &str| -> Vec<String> {
+        let prefix = format!("= {}: ", kind);
+        body.iter()
+            .filter_map(|line| line.trim_start().strip_prefix(&prefix))
+            .map(str::to_owned)
+            .collect()
+    };
+
+    let (sources, span) = SourceMap::quote(path.to_owned(), lineno, &line, span, &[], &[]);
+
+    Some(Diagnostic {
+        severity: match header.level {
+            Level::Diagnostic(severity) => severity,
+            Level::Note | Level::Help => Severity::Error,
+        },
+        summary: header.summary.clone().into(),
+        message: message.into(),
+        span,
+        sources,
+        errnum: header
+            .code
+            .as_deref()
+            .and_then(errnum)
+            .unwrap_or(DEFAULT_ERRNUM),
+        labels,
+        notes: footers("note"),
+        helps: footers("help"),
+        ..Default::default()
+    })
+}
+
+/// The diagnostics of the text rustc printed. Its notes quoting code
+/// become the related notes of the diagnostic before them, and the other
+/// notes and helps its `= note:` and `= help:` lines. The lines quoting no
+/// code, such as `error: aborting due to 2 previous errors`, are left out.
+pub fn parse(contents: &str) -> Result<Vec<Diagnostic<'static>>, RoostError> {
+    let contents = ansi::strip(contents);
+    let lines: Vec<&str> = contents.lines().collect();
+    let mut errs: Vec<Diagnostic<'static>> = Vec::new();
+
+    let mut index = 0;
+    while index < lines.len() {
+        let Some(header) = parse_header(lines[index]) else {
+            index += 1;
+            continue;
+        };
+
+        let body_start = index + 1;
+        let body_end = lines[body_start..]
+            .iter()
+            .position(|line| parse_header(line).is_some())
+            .map_or(lines.len(), |offset| body_start + offset);
+
+        let err = to_diagnostic(&header, &lines[body_start..body_end]);
+        match (header.level, err, errs.last_mut()) {
+            (Level::Diagnostic(_), Some(err), _) => errs.push(err),
+            (Level::Note, Some(note), Some(last)) => last.related.push(note),
+            (Level::Note, None, Some(last)) => last.notes.push(header.summary),
+            (Level::Help, _, Some(last)) => last.helps.push(header.summary),
+            _ => {}
+        }
+        index = body_end;
+    }
+
+    Ok(errs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MISMATCH: &str = r#"error[E0308]: mismatched types
+ --> src/demo.rs:4:17
+  |
+4 |     let x: u8 = "a";
+  |            --   ^^^ expected `u8`, found `&str`
+  |            |
+  |            expected due to this
+  |
+  = note: a note
+help: try a `char` literal
+ --> src/demo.rs:4:17
+  |
+4 |     let x: u8 = 'a';
+  |                 ~~~
+
+error: aborting due to 1 previous error
+"#;
+
+    #[test]
+    fn parse_diagnostic_with_label() {
+        let errs = parse(MISMATCH).unwrap();
+
+        assert_eq!(errs.len(), 1);
+        let err = &errs[0];
+        assert_eq!(err.severity, Severity::Error);
+        assert_eq!(err.summary, "mismatched types");
+        assert_eq!(err.errnum, 308);
+        assert_eq!((err.path(), err.lineno()), ("src/demo.rs", 4));
+        assert_eq!(err.line(), r#"    let x: u8 = "a";"#);
+        assert_eq!(err.spos()..err.epos(), 16..19);
+        assert_eq!(err.message, "expected `u8`, found `&str`");
+        assert_eq!(err.labels.len(), 1);
+        assert_eq!(err.labels[0].range, 11..13);
+        assert_eq!(err.labels[0].message, "expected due to this");
+        assert_eq!(err.notes, ["a note"]);
+        assert_eq!(err.helps, ["try a `char` literal"]);
+    }
+
+    #[test]
+    fn parse_counts_columns_of_wide_characters() {
+        let text = "warning: unused variable: `日本`\n --> src/demo.rs:1:5\n  |\n1 | let 日本 = 1;\n  |     ^^^^ help: prefix it\n";
+        let errs = parse(text).unwrap();
+
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].severity, Severity::Warning);
+        assert_eq!(errs[0].errnum, DEFAULT_ERRNUM);
+        assert_eq!(&errs[0].line()[errs[0].spos()..errs[0].epos()], "日本");
+    }
+
+    #[test]
+    fn parse_leaves_out_lines_without_code() {
+        assert!(parse("error: aborting due to 2 previous errors\n")
+            .unwrap()
+            .is_empty());
+    }
+}
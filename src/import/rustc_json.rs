@@ -0,0 +1,205 @@
+use serde::Deserialize;
+
+use super::{byte_offset, errnum, span_in};
+use crate::{source::SourceMap, Diagnostic, Label, RoostError, Severity, DEFAULT_ERRNUM};
+
+/// A line of `cargo build --message-format=json`.
+#[derive(Deserialize)]
+struct CargoMessage {
+    reason: String,
+    message: Option<RustcDiagnostic>,
+}
+
+#[derive(Deserialize)]
+struct RustcDiagnostic {
+    message: String,
+    code: Option<Code>,
+    level: String,
+    spans: Vec<Span>,
+    #[serde(default)]
+    children: Vec<RustcDiagnostic>,
+}
+
+#[derive(Deserialize)]
+struct Code {
+    code: String,
+}
+
+#[derive(Deserialize)]
+struct Span {
+    file_name: String,
+    line_start: usize,
+    line_end: usize,
+    column_start: usize,
+    column_end: usize,
+    is_primary: bool,
+    label: Option<String>,
+    text: Vec<SpanLine>,
+}
+
+#[derive(Deserialize)]
+struct SpanLine {
+    text: String,
+}
+
+/// The diagnostic quoting the primary span of `spans`, if there is one,
+/// the other spans of its line becoming labels.
+fn quote(summary: String, spans: Vec<Span>) -> Option<Diagnostic<'static>> {
+    let (primary, others): (Vec<Span>, Vec<Span>) =
+        spans.into_iter().partition(|span| span.is_primary);
+    let span = primary.into_iter().next()?;
+    let line = span.text.into_iter().next()?.text;
+    if line.is_empty() {
+        return None;
+    }
+
+    let epos = match span.line_end == span.line_start {
+        true => byte_offset(&line, span.column_end),
+        false => line.len(),
+    };
+    let range = span_in(&line, byte_offset(&line, span.column_start), epos);
+
+    let labels = others
+        .into_iter()
+        .filter(|other| {
+            other.file_name == span.file_name
+                && other.line_start == span.line_start
+                && other.line_end == span.line_start
+        })
+        .map(|other| Label {
+            range: span_in(
+                &line,
+                byte_offset(&line, other.column_start),
+                byte_offset(&line, other.column_end),
+            ),
+            message: other.label.unwrap_or_default(),
+            color: None,
+        })
+        .collect();
+    let message = span.label.unwrap_or_default();
+    let (sources, span) = SourceMap::quote(span.file_name, span.line_start, &line, range, &[], &[]);
+
+    Some(Diagnostic {
+        summary: summary.into(),
+        message: message.into(),
+        span,
+        labels,
+        sources,
+        errnum: DEFAULT_ERRNUM,
+        ..Default::default()
+    })
+}
+
+fn to_diagnostic(diagnostic: RustcDiagnostic) -> Option<Diagnostic<'static>> {
+    let severity = match diagnostic.level.as_str() {
+        "error" => Severity::Error,
+        "warning" => Severity::Warning,
+        _ => return None,
+    };
+    // The `= note:` and `= help:` lines, which quote no code
+    let (footers, children): (Vec<_>, Vec<_>) = diagnostic
+        .children
+        .into_iter()
+        .partition(|child| child.spans.is_empty());
+    let footers_of = |level: &str| {
+        footers
+            .iter()
+            .filter(|child| child.level == level)
+            .map(|child| child.message.clone())
+            .collect()
+    };
+    let (notes, helps) = (footers_of("note"), footers_of("help"));
+    // The notes quoting another location, such as where an item is defined
+    let related = children
+        .into_iter()
+        .filter(|child| child.level == "note")
+        .filter_map(|child| quote(child.message, child.spans))
+        .collect();
+
+    Some(Diagnostic {
+        severity,
+        errnum: diagnostic
+            .code
+            .and_then(|code| errnum(&code.code))
+            .unwrap_or(DEFAULT_ERRNUM),
+        related,
+        notes,
+        helps,
+        ..quote(diagnostic.message, diagnostic.spans)?
+    })
+}
+
+pub fn parse(contents: &str) -> Result<Vec<Diagnostic<'static>>, RoostError> {
+    let mut errs = Vec::new();
+
+    for (index, line) in contents.lines().enumerate() {
+        if !line.trim_start().starts_with('{') {
+            continue;
+        }
+
+        let invalid = |err: serde_json::Error| RoostError::ImportError {
+            details: format!("line {}: {}", index + 1, err),
+        };
+        let value: serde_json::Value = serde_json::from_str(line).map_err(invalid)?;
+
+        let diagnostic = match value.get("reason") {
+            Some(_) => {
+                let message: CargoMessage = serde_json::from_value(value).map_err(invalid)?;
+                match (message.reason.as_str(), message.message) {
+                    ("compiler-message", Some(diagnostic)) => diagnostic,
+                    _ => continue,
+                }
+            }
+            None => serde_json::from_value(value).map_err(invalid)?,
+        };
+
+        errs.extend(to_diagnostic(diagnostic));
+    }
+
+    Ok(errs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MISMATCH: &str = r#"{"reason":"compiler-artifact","package_id":"demo"}
+{"reason":"compiler-message","package_id":"demo","message":{"message":"mismatched types","code":{"code":"E0308"},"level":"error","spans":[{"file_name":"src/demo.rs","line_start":4,"line_end":4,"column_start":17,"column_end":20,"is_primary":true,"label":"expected `u8`, found `&str`","text":[{"text":"    let x: u8 = \"a\";"}]},{"file_name":"src/demo.rs","line_start":4,"line_end":4,"column_start":12,"column_end":14,"is_primary":false,"label":"expected due to this","text":[{"text":"    let x: u8 = \"a\";"}]}],"children":[{"message":"a note","level":"note","spans":[],"children":[]}]}}
+{"reason":"build-finished","success":false}
+"#;
+
+    #[test]
+    fn parse_cargo_messages() {
+        let errs = parse(MISMATCH).unwrap();
+
+        assert_eq!(errs.len(), 1);
+        let err = &errs[0];
+        assert_eq!(err.severity, Severity::Error);
+        assert_eq!(err.summary, "mismatched types");
+        assert_eq!(err.errnum, 308);
+        assert_eq!((err.path(), err.lineno()), ("src/demo.rs", 4));
+        assert_eq!(err.spos()..err.epos(), 16..19);
+        assert_eq!(err.message, "expected `u8`, found `&str`");
+        assert_eq!(err.labels.len(), 1);
+        assert_eq!(err.labels[0].range, 11..13);
+        assert_eq!(err.notes, ["a note"]);
+    }
+
+    #[test]
+    fn parse_keeps_spans_on_character_boundaries() {
+        // Columns past the line, and a line of multi-byte characters
+        let text = r#"{"message":"m","code":null,"level":"warning","spans":[{"file_name":"a.rs","line_start":1,"line_end":1,"column_start":2,"column_end":9,"is_primary":true,"label":null,"text":[{"text":"aé"}]}],"children":[]}"#;
+        let errs = parse(text).unwrap();
+
+        assert_eq!(errs[0].severity, Severity::Warning);
+        assert_eq!(errs[0].spos()..errs[0].epos(), 1..3);
+    }
+
+    #[test]
+    fn parse_refuses_invalid_json() {
+        assert!(matches!(
+            parse("{\"message\": 1}\n"),
+            Err(RoostError::ImportError { .. })
+        ));
+    }
+}
@@ -0,0 +1,504 @@
+//! Roost renders fake compiler errors, in the style of rustc and a few other
+//! compilers, as colored text or in one of the export formats.
+
+use core::fmt;
+use std::{
+    borrow::Cow,
+    env,
+    error::Error,
+    io::{self, Write},
+    ops::Range,
+    path::PathBuf,
+};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use ansi::Color;
+use builder::DiagnosticBuilder;
+use render::RenderOptions;
+use source::{SourceFile, SourceMap, Span};
+use style::Style;
+use unicode_width::UnicodeWidthStr;
+
+pub mod ansi;
+pub mod builder;
+pub mod cargo;
+pub mod export;
+#[cfg(feature = "fuzzing")]
+mod fuzzing;
+pub mod import;
+#[cfg(feature = "miette")]
+pub mod miette;
+pub mod position;
+pub mod preset;
+pub mod prompt;
+pub mod random;
+pub mod render;
+pub mod selection;
+pub mod source;
+pub mod spec;
+pub mod style;
+pub mod template;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+pub const DEFAULT_LINENO: usize = 1;
+pub const DEFAULT_PATH: &str = "<stdin>";
+pub const DEFAULT_ERRNUM: usize = 69;
+
+#[derive(Debug)]
+#[allow(clippy::enum_variant_names)]
+pub enum RoostError {
+    ValueError {
+        details: String,
+    },
+    ConfigError {
+        details: String,
+    },
+    ImportError {
+        details: String,
+    },
+    ClipboardError {
+        details: String,
+    },
+    OutputError {
+        details: String,
+    },
+    /// The user stopped answering the prompts
+    AbortError {
+        details: String,
+    },
+}
+
+impl RoostError {
+    /// Exit status of the command line interface, after the ones of
+    /// `sysexits.h`.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            RoostError::ValueError { .. } | RoostError::ImportError { .. } => 65,
+            RoostError::ClipboardError { .. } => 69,
+            RoostError::OutputError { .. } => 74,
+            RoostError::ConfigError { .. } => 78,
+            RoostError::AbortError { .. } => 130,
+        }
+    }
+}
+
+impl fmt::Display for RoostError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self {
+            RoostError::ValueError { details }
+            | RoostError::ConfigError { details }
+            | RoostError::ImportError { details }
+            | RoostError::ClipboardError { details }
+            | RoostError::OutputError { details }
+            | RoostError::AbortError { details } => {
+                write!(f, "{}", details)
+            }
+        }
+    }
+}
+
+impl Error for RoostError {
+    fn description(&self) -> &str {
+        match &self {
+            RoostError::ValueError { details }
+            | RoostError::ConfigError { details }
+            | RoostError::ImportError { details }
+            | RoostError::ClipboardError { details }
+            | RoostError::OutputError { details }
+            | RoostError::AbortError { details } => details,
+        }
+    }
+}
+
+/// How serious a diagnostic is, which decides the word of its header.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    serde::Deserialize,
+    serde::Serialize,
+    clap::ValueEnum,
+    schemars::JsonSchema,
+)]
+#[serde(rename_all = "lowercase")]
+#[cfg_attr(feature = "fuzzing", derive(arbitrary::Arbitrary))]
+pub enum Severity {
+    #[default]
+    Error,
+    Warning,
+}
+
+impl Severity {
+    pub fn is_error(&self) -> bool {
+        *self == Severity::Error
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+        }
+    }
+}
+
+/// A secondary span of the line of a diagnostic, with a label of its own.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Label {
+    /// Byte range in the line of the span
+    pub range: Range<usize>,
+    pub message: String,
+    /// Color of its marks, legs and text, else one picked to tell it apart
+    /// from the other labels
+    pub color: Option<Color>,
+}
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "UncheckedDiagnostic<'a>"))]
+pub struct Diagnostic<'a> {
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub severity: Severity,
+    pub summary: Cow<'a, str>,
+    pub message: Cow<'a, str>,
+    pub span: Span,
+    /// Other spans of the line, under marks of their own
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub labels: Vec<Label>,
+    /// The quoted code, `span` pointing into it
+    pub sources: SourceMap<'a>,
+    pub errnum: usize,
+    /// Code printed instead of `E` and the padded `errnum`, such as
+    /// `W0502` or `clippy::needless_return`
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub code: Option<String>,
+    pub fixit: Option<String>,
+    /// Whether the fix-it is shown as the `-` and `+` lines of a diff
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub fixit_diff: bool,
+    /// Other locations quoted after the span, each under a `note:` giving
+    /// its summary, such as where a variable was first bound
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub related: Vec<Diagnostic<'a>>,
+    /// Remarks without a location, under a `note:` each after the quoted
+    /// code, as rustc's `= note:` lines
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub notes: Vec<String>,
+    /// Advice without a location, under a `help:` each after the notes
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub helps: Vec<String>,
+    /// Difference, in characters, between the column of the span in the
+    /// file and in the quoted line, once `--max-width` cut it or its tabs
+    /// were expanded
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub column_offset: isize,
+}
+
+impl Default for Diagnostic<'_> {
+    /// An empty diagnostic on an empty line of `<stdin>`, whose span points
+    /// into its own source map.
+    fn default() -> Self {
+        let (sources, span) =
+            SourceMap::quote(DEFAULT_PATH.to_owned(), DEFAULT_LINENO, "", 0..0, &[], &[]);
+
+        Diagnostic {
+            severity: Severity::default(),
+            summary: Cow::Borrowed(""),
+            message: Cow::Borrowed(""),
+            span,
+            labels: Vec::new(),
+            sources,
+            errnum: 0,
+            code: None,
+            fixit: None,
+            fixit_diff: false,
+            related: Vec::new(),
+            notes: Vec::new(),
+            helps: Vec::new(),
+            column_offset: 0,
+        }
+    }
+}
+
+/// The fields of a deserialized [`Diagnostic`], before its span is checked
+/// to point into its sources and its labels into its line.
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+struct UncheckedDiagnostic<'a> {
+    #[serde(default)]
+    severity: Severity,
+    summary: Cow<'a, str>,
+    message: Cow<'a, str>,
+    span: Span,
+    #[serde(default)]
+    labels: Vec<Label>,
+    sources: SourceMap<'a>,
+    errnum: usize,
+    #[serde(default)]
+    code: Option<String>,
+    fixit: Option<String>,
+    #[serde(default)]
+    fixit_diff: bool,
+    #[serde(default)]
+    related: Vec<Diagnostic<'a>>,
+    #[serde(default)]
+    notes: Vec<String>,
+    #[serde(default)]
+    helps: Vec<String>,
+}
+
+#[cfg(feature = "serde")]
+impl<'a> TryFrom<UncheckedDiagnostic<'a>> for Diagnostic<'a> {
+    type Error = RoostError;
+
+    fn try_from(unchecked: UncheckedDiagnostic<'a>) -> Result<Self, RoostError> {
+        if !unchecked.sources.contains(&unchecked.span) {
+            return Err(RoostError::ValueError {
+                details: format!(
+                    "the span {}..{} is not in the sources",
+                    unchecked.span.range.start, unchecked.span.range.end
+                ),
+            });
+        }
+
+        let err = Diagnostic {
+            severity: unchecked.severity,
+            summary: unchecked.summary,
+            message: unchecked.message,
+            span: unchecked.span,
+            labels: unchecked.labels,
+            sources: unchecked.sources,
+            errnum: unchecked.errnum,
+            code: unchecked.code,
+            fixit: unchecked.fixit,
+            fixit_diff: unchecked.fixit_diff,
+            related: unchecked.related,
+            notes: unchecked.notes,
+            helps: unchecked.helps,
+            column_offset: 0,
+        };
+        let line = err.line();
+        let in_line = |pos: usize| pos <= line.len() && line.is_char_boundary(pos);
+        if let Some(label) = err.labels.iter().find(|label| {
+            label.range.start > label.range.end
+                || !in_line(label.range.end)
+                || !in_line(label.range.start)
+        }) {
+            return Err(RoostError::ValueError {
+                details: format!(
+                    "the label span {}..{} is not in the line",
+                    label.range.start, label.range.end
+                ),
+            });
+        }
+
+        Ok(err)
+    }
+}
+
+impl Diagnostic<'static> {
+    pub fn builder() -> DiagnosticBuilder {
+        DiagnosticBuilder::default()
+    }
+}
+
+impl<'a> Diagnostic<'a> {
+    /// The diagnostic with copies of the text and the sources it borrows,
+    /// to keep it past them.
+    pub fn into_owned(self) -> Diagnostic<'static> {
+        Diagnostic {
+            summary: Cow::Owned(self.summary.into_owned()),
+            message: Cow::Owned(self.message.into_owned()),
+            sources: self.sources.into_owned(),
+            related: self
+                .related
+                .into_iter()
+                .map(Diagnostic::into_owned)
+                .collect(),
+            ..self
+        }
+    }
+
+    pub fn file(&self) -> &SourceFile<'a> {
+        self.sources.get(self.span.file)
+    }
+
+    pub fn path(&self) -> &str {
+        &self.file().path
+    }
+
+    /// The line the span starts on.
+    pub fn line(&self) -> &str {
+        let (_, line) = self.file().line_at(self.span.range.start);
+        &self.file().contents[line]
+    }
+
+    /// The number of the line the span starts on.
+    pub fn lineno(&self) -> usize {
+        let (index, _) = self.file().line_at(self.span.range.start);
+        self.file().first_lineno + index
+    }
+
+    /// The byte offset of the span in its line.
+    pub fn spos(&self) -> usize {
+        let (_, line) = self.file().line_at(self.span.range.start);
+        self.span.range.start - line.start
+    }
+
+    /// The byte offset of the end of the span in its line, or the end of
+    /// the line if the span goes past it.
+    pub fn epos(&self) -> usize {
+        let (_, line) = self.file().line_at(self.span.range.start);
+        self.span.range.end.min(line.end) - line.start
+    }
+
+    /// The byte range of `label`, which is relative to the line, in the
+    /// file.
+    pub fn label_range(&self, label: &Label) -> Range<usize> {
+        let start = self.span.range.start - self.spos();
+        start + label.range.start..start + label.range.end
+    }
+
+    /// The number of terminal columns before the span in its line.
+    pub fn span_indent(&self) -> usize {
+        self.line()[..self.spos()].width()
+    }
+
+    /// The number of terminal columns taken by the span, at least one so
+    /// that it can be marked.
+    pub fn span_width(&self) -> usize {
+        self.line()[self.spos()..self.epos()].width().max(1)
+    }
+
+    /// The lines quoted before the one of the span.
+    pub fn context_before(&self) -> Vec<&str> {
+        let (index, _) = self.file().line_at(self.span.range.start);
+        self.file().lines().take(index).collect()
+    }
+
+    /// The lines quoted after the one of the span.
+    pub fn context_after(&self) -> Vec<&str> {
+        let (index, _) = self.file().line_at(self.span.range.start);
+        self.file().lines().skip(index + 1).collect()
+    }
+
+    /// The 1-based column of the span in the source line, in characters.
+    pub fn column(&self) -> usize {
+        self.column_at(self.spos())
+    }
+
+    /// The 1-based column, in characters, of the byte offset `pos` of the
+    /// quoted line in the source line.
+    pub fn column_at(&self, pos: usize) -> usize {
+        self.line()[..pos]
+            .chars()
+            .count()
+            .saturating_add_signed(self.column_offset)
+            + 1
+    }
+
+    pub fn get_errid(&self) -> String {
+        match &self.code {
+            Some(code) => code.clone(),
+            None => format!("E{:0fill$}", self.errnum, fill = 4),
+        }
+    }
+
+    /// Writes the diagnostic to `out`, as colored text unless colors are
+    /// disabled with [`ansi::set_enabled`].
+    pub fn render<W: Write>(&self, options: &RenderOptions, out: &mut W) -> io::Result<()> {
+        out.write_all(render::render(options, self).as_bytes())
+    }
+}
+
+/// The code `pattern` gives to the error number `errnum`: each run of `#`
+/// in it replaced by the number, padded with zeros to the length of the run,
+/// as `W####` gives `W0502`. A pattern without `#` is the code itself.
+/// `width`, if given, is the padding instead of the length of the runs, 0
+/// leaving the number unpadded.
+pub fn format_code(pattern: &str, errnum: usize, width: Option<usize>) -> String {
+    let mut code = String::new();
+    let mut chars = pattern.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '#' {
+            code.push(c);
+            continue;
+        }
+        let mut fill = 1;
+        while chars.next_if_eq(&'#').is_some() {
+            fill += 1;
+        }
+        code.push_str(&format!("{:0fill$}", errnum, fill = width.unwrap_or(fill)));
+    }
+
+    code
+}
+
+impl fmt::Display for Diagnostic<'_> {
+    /// Renders the diagnostic with the default options, which `to_string`
+    /// collects into a `String`.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&render::render(&RenderOptions::default(), self))
+    }
+}
+
+/// The `roost` directory in the user directory set by the environment
+/// variable `var`, as `XDG_CONFIG_HOME`, or else in `fallback` under the
+/// home directory, as `.config`.
+pub fn user_dir(var: &str, fallback: &str) -> Option<PathBuf> {
+    let dir = env::var_os(var)
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(fallback)))?;
+
+    Some(dir.join("roost"))
+}
+
+pub fn bold(string: String) -> String {
+    Style::new().bold().paint(string)
+}
+
+pub fn color(string: String, code: u8) -> String {
+    Style::new().fg(Color::Basic(code)).paint(string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::layout;
+
+    fn diagnostic(line: &str, span: Range<usize>) -> Diagnostic<'static> {
+        Diagnostic::builder()
+            .summary("unexpected token")
+            .line(line)
+            .span(span)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn column_counts_chars() {
+        let err = diagnostic("let é = 1;", 7..8);
+        assert_eq!(err.spos(), 7);
+        assert_eq!(err.column(), 7);
+    }
+
+    #[test]
+    fn column_survives_layout() {
+        let err = diagnostic("\tlet é = 1;", 8..9);
+        assert_eq!(err.column(), 8);
+        assert_eq!(layout::expand_tabs(&err, 4).unwrap().column(), 8);
+
+        let line = format!("{}let x = 1;", "é".repeat(100));
+        let err = diagnostic(&line, 206..207);
+        assert_eq!(err.column(), 107);
+        assert_eq!(layout::fit(&err, 40).column(), 107);
+    }
+}
@@ -1,256 +1,968 @@
-use clap::Parser;
+use clap::{error::ErrorKind, CommandFactory, Parser, Subcommand, ValueEnum};
 
-use core::fmt;
 use std::{
-    any,
-    error::Error,
-    fs::File,
-    io::{self, Write},
-    path::PathBuf,
+    env, fs,
+    io::{self, IsTerminal, Write},
+    ops::Range,
+    path::{Path, PathBuf},
+    thread,
+    time::{Duration, SystemTime},
 };
 
-const DEFAULT_LINENO: usize = 1;
-const DEFAULT_PATH: &str = "<stdin>";
-const DEFAULT_ERRNUM: usize = 69;
+use annotate::AnnotateArgs;
+use buildlog::BuildlogArgs;
+use clipboard::CopyMode;
+use config::Config;
+use convert::{ConvertArgs, Target};
+use diff::FromDiffArgs;
+use explain::{ExplainArgs, Explanations};
+use output::Outputs;
+use review::Choice;
+use roost::{
+    ansi, bold,
+    cargo::{self, Workspace},
+    color,
+    export::{self, ExportOptions, Format},
+    format_code,
+    import::{self, ImportArgs},
+    position::{self, PositionRange, PositionUnit},
+    preset::Preset,
+    prompt::{
+        self, error_message, exit_with, field, field_with, int_factory, optional_field,
+        range_factory, string, text,
+    },
+    random::{self, RandomArgs},
+    render::{self, locale::Locale, Charset, Emitter, RenderOptions},
+    selection::{self, Prefill, Selection},
+    source::SourceMap,
+    spec::{self, RenderArgs, Spec, SpecFormat},
+    style::{self, ColorDepth},
+    template, Diagnostic, RoostError, DEFAULT_ERRNUM, DEFAULT_LINENO, DEFAULT_PATH,
+};
+use serve::ServeArgs;
+use share::ShareArgs;
+use ui_annotations::UiAnnotationsArgs;
+use unicode_width::UnicodeWidthStr;
+
+mod animate;
+mod annotate;
+mod buildlog;
+mod clipboard;
+mod config;
+mod console;
+mod convert;
+mod diff;
+mod explain;
+mod meme;
+mod output;
+mod pager;
+mod paths;
+mod picker;
+mod review;
+mod rules;
+mod serve;
+mod share;
+#[cfg(feature = "tui")]
+mod tui;
+mod ui_annotations;
+
+const WATCH_INTERVAL: Duration = Duration::from_millis(250);
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+    /// The options of `new`, run when no subcommand is given
+    #[command(flatten)]
+    new: NewArgs,
+    /// File to write the diagnostic to, `-` for standard output; can be
+    /// given several times
+    #[arg(long, global = true, value_name = "FILE")]
+    output: Vec<PathBuf>,
+    /// Append to the `--output` files instead of overwriting them
+    #[arg(long, global = true)]
+    append: bool,
+    #[command(flatten)]
+    render: RenderOptions,
+    #[command(flatten)]
+    export: ExportOptions,
+    /// Also place the rendered diagnostic on the system clipboard
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        value_name = "MODE",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "plain"
+    )]
+    copy: Option<CopyMode>,
+    /// Print long output directly instead of through `$PAGER`
+    #[arg(long, global = true)]
+    no_pager: bool,
+    /// Type the diagnostic out character by character, at CPS characters a
+    /// second
+    #[arg(
+        long,
+        global = true,
+        value_name = "CPS",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = animate::DEFAULT_CPS,
+        value_parser = clap::value_parser!(u32).range(1..)
+    )]
+    animate: Option<u32>,
+    /// What the positions given to roost count: grapheme clusters at the
+    /// prompts and bytes in the options by default
+    #[arg(long, global = true, value_enum, value_name = "UNIT")]
+    position_unit: Option<PositionUnit>,
+    /// Number the ruler and the span prompt from 1, as editors do
+    #[arg(long, global = true)]
+    one_based: bool,
+    /// Replace the summaries and labels with funny ones
+    #[arg(long, global = true)]
+    meme: bool,
+    /// Code printed instead of `E` and the padded error number: a pattern
+    /// whose runs of `#` are replaced by the number, as in `W####`, or else
+    /// the code itself
+    #[arg(long, global = true, value_name = "CODE")]
+    code: Option<String>,
+    /// Digits the error number is padded to with zeros, 0 to leave it
+    /// unpadded
+    #[arg(long, global = true, value_name = "DIGITS")]
+    code_width: Option<usize>,
+}
+
+// Options of the prompts asking for the diagnostic, not documented with
+// `///` which would make them the description of `roost --help`
+#[derive(clap::Args, Debug, Default)]
+pub struct InterviewArgs {
+    /// Read the line from the file at `path` instead of prompting for it
     #[arg(long)]
-    output: Option<PathBuf>,
+    from_file: bool,
+    /// Replacement text suggested for the span
+    #[arg(long, value_name = "TEXT")]
+    fixit: Option<String>,
+    /// Show the fix-it as the line before and after it, in a diff
+    #[arg(long, requires = "fixit")]
+    fixit_diff: bool,
+    /// Select the span by moving a highlight with the arrow keys instead of
+    /// typing its positions
+    #[arg(long)]
+    pick: bool,
+    /// Pre-fill the summary, the message and the code of a common error
+    #[arg(long, value_enum, value_name = "PRESET")]
+    preset: Option<Preset>,
+    /// Write the `roost render` command reproducing the diagnostic to a
+    /// file, instead of printing it after the prompts
+    #[arg(long, value_name = "FILE")]
+    emit_cmd: Option<PathBuf>,
+}
+
+#[derive(clap::Args, Debug, Default)]
+struct NewArgs {
+    /// Pre-fill the fields from an editor selection (`file:line:col-col`,
+    /// 1-based), reading the line from the file
+    #[arg(
+        long,
+        alias = "stdin-selection",
+        value_name = "SELECTION",
+        value_parser = selection::parse
+    )]
+    selection: Option<Selection>,
+    /// Pre-fill the fields from a rustc error pasted at a first prompt
+    #[arg(long, conflicts_with = "selection")]
+    paste: bool,
+    #[command(flatten)]
+    interview: InterviewArgs,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Write a diagnostic by answering prompts (the default)
+    New(NewArgs),
+    /// Re-render diagnostics produced by a real compiler
+    Import(ImportArgs),
+    /// Annotate a line of the source piped on standard input
+    Annotate(AnnotateArgs),
+    /// Point the error at a line added in `git diff`
+    FromDiff(FromDiffArgs),
+    /// Serve an HTTP endpoint rendering JSON specs
+    Serve(ServeArgs),
+    /// Render a spec file
+    Render(RenderArgs),
+    /// Convert diagnostics from one format to another
+    Convert(ConvertArgs),
+    /// Upload the diagnostic to a paste service and print its URL
+    Share(ShareArgs),
+    /// Print the explanation registered for an error code
+    Explain(ExplainArgs),
+    /// Generate plausible diagnostics
+    Random(RandomArgs),
+    /// Wrap diagnostics in the transcript of a failing `cargo build`
+    Buildlog(BuildlogArgs),
+    /// Write the `//~ ERROR` annotations of a compiletest UI test
+    UiAnnotations(UiAnnotationsArgs),
+    /// Print the JSON Schema of the spec files
+    Schema,
+    /// Edit a diagnostic in a form next to a live preview
+    #[cfg(feature = "tui")]
+    Tui,
 }
 
 impl Args {
-    fn get_output(&self) -> Result<Box<dyn io::Write>, io::Error> {
-        match self.output {
-            Some(ref path) => File::options()
-                .write(true)
-                .create(true)
-                .open(path)
-                .map(|f| Box::new(f) as Box<dyn io::Write>),
-            None => Ok(Box::new(io::stdout())),
-        }
+    fn get_output(&self) -> Result<Outputs, RoostError> {
+        Outputs::open(&self.output, self.append)
     }
 }
 
-#[derive(Debug)]
-enum RoostError {
-    ValueError { details: String },
+/// The path of a readable file, prefixed as set by the configuration unless
+/// it is one of the `prefixed` paths, which already are.
+fn readable_file<'a>(
+    config: &'a Config,
+    prefixed: &'a [String],
+) -> impl Fn(&str) -> Result<String, RoostError> + 'a {
+    move |raw_path: &str| {
+        let path = match prefixed.iter().any(|prefixed| prefixed == raw_path) {
+            true => raw_path.to_owned(),
+            false => config.prefixed_path(raw_path.to_owned()),
+        };
+
+        fs::read_to_string(&path)
+            .map(|_| path.clone())
+            .map_err(|err| RoostError::ValueError {
+                details: format!("could not read {}: {}", path, err),
+            })
+    }
 }
 
-impl fmt::Display for RoostError {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match &self {
-            RoostError::ValueError { details } => write!(f, "{}", details),
+/// Prompts for a file and a line number in it, returning the path, the
+/// line number and the line itself.
+fn file_line(
+    config: &Config,
+    default_path: Option<String>,
+    default_lineno: usize,
+) -> (String, usize, String) {
+    let recent = paths::recent();
+    let mut default_path = default_path.or_else(|| recent.first().cloned());
+    // The default and the recent paths are already prefixed
+    let prefixed: Vec<String> = recent.iter().chain(&default_path).cloned().collect();
+    let path_type = rules::enforce(config.rules.path.as_ref(), readable_file(config, &prefixed));
+    // The path is asked for again, without a default, until the file has
+    // lines
+    let (path, contents) = loop {
+        let path = field_with("path", &path_type, default_path.take(), |name, prompt| {
+            paths::read_answer(name, prompt, &recent)
+        });
+        paths::remember(&path);
+        let contents = fs::read_to_string(&path).unwrap_or_default();
+        if contents.lines().next().is_some() {
+            break (path, contents);
         }
-    }
+
+        eprintln!(
+            "{}",
+            bold(color(format!("ERR: {}", text("{} is empty", &[&path])), 1))
+        );
+    };
+    let lines: Vec<&str> = contents.lines().collect();
+
+    let in_file = |raw_value: &str| {
+        int_factory(1, usize::MAX)(raw_value).and_then(|lineno| match lineno <= lines.len() {
+            true => Ok(lineno),
+            false => Err(RoostError::ValueError {
+                details: text("{} has only {} lines", &[&path, &lines.len().to_string()]),
+            }),
+        })
+    };
+    let lineno = field(
+        "line number",
+        &rules::enforce(config.rules.lineno.as_ref(), in_file),
+        Some(default_lineno.clamp(1, lines.len())),
+    );
+
+    (path, lineno, lines[lineno - 1].to_owned())
 }
 
-impl Error for RoostError {
-    fn description(&self) -> &str {
-        match &self {
-            RoostError::ValueError { details } => &details,
+/// What the positions typed for the span count and the first one, grapheme
+/// clusters from 0 unless `--position-unit` or `--one-based` say otherwise.
+fn span_positions(args: &Args, config: &Config) -> (PositionUnit, usize) {
+    (
+        args.position_unit.unwrap_or(PositionUnit::Graphemes),
+        usize::from(args.one_based || config.one_based.unwrap_or(false)),
+    )
+}
+
+/// Prints the line with the position of each grapheme cluster above it, in
+/// `unit` from `base`, and its byte offset too when they differ. The
+/// graphemes of the `selected` byte range are highlighted.
+fn print_line_helper(
+    line: &str,
+    unit: PositionUnit,
+    base: usize,
+    charset: Charset,
+    max_width: Option<usize>,
+    selected: Option<Range<usize>>,
+) {
+    let chars = position::graphemes(line);
+    let position = |offset: usize| unit.from_byte(line, offset) + base;
+    let show_bytes = chars.iter().any(|&(offset, _)| position(offset) != offset);
+    // Each cell fits its labels and its grapheme, with a space to spare
+    let cell_width = [
+        position(line.len()).to_string().len(),
+        line.len().to_string().len(),
+        chars.iter().map(|(_, c)| c.width()).max().unwrap_or(0),
+    ]
+    .into_iter()
+    .max()
+    .unwrap_or(0)
+        + 1;
+    let per_row = max_width
+        .map_or(chars.len(), |width| width / cell_width)
+        .max(1);
+    let is_selected = |offset: usize| {
+        selected
+            .as_ref()
+            .is_some_and(|range| range.contains(&offset))
+    };
+
+    for chunk in chars.chunks(per_row) {
+        let rule = charset
+            .horizontal()
+            .to_string()
+            .repeat(cell_width * chunk.len());
+        println!("{}", rule);
+
+        for (offset, _) in chunk {
+            let label = format!("{:^width$}", position(*offset), width = cell_width);
+            match is_selected(*offset) {
+                true => print!("{}", bold(color(label, 1))),
+                false => print!("{}", label),
+            }
         }
+        println!();
+
+        if show_bytes {
+            for (offset, _) in chunk {
+                print!(
+                    "{}",
+                    color(format!("{:^width$}", offset, width = cell_width), 4)
+                );
+            }
+            println!();
+        }
+
+        // Centered by hand, as `format!` counts chars rather than columns
+        for (offset, c) in chunk {
+            let padding = cell_width.saturating_sub(c.width());
+            let c = match is_selected(*offset) {
+                true => bold(color(c.to_string(), 1)),
+                false => c.to_string(),
+            };
+            print!(
+                "{}{}{}",
+                " ".repeat(padding / 2),
+                c,
+                " ".repeat(padding - padding / 2)
+            );
+        }
+        println!();
+
+        println!("{}", rule);
     }
 }
 
-struct ErrorData {
-    summary: String,
-    line: String,
-    message: String,
-    spos: usize,
-    epos: usize,
-    lineno: usize,
-    path: String,
-    errnum: usize,
-}
+fn interview(
+    args: &Args,
+    interview_args: &InterviewArgs,
+    config: &Config,
+    prefill: &Prefill,
+) -> Diagnostic<'static> {
+    let preset = interview_args.preset;
+    let rules = &config.rules;
+    let summary = field(
+        "summary",
+        &rules::enforce(rules.summary.as_ref(), string),
+        (prefill.summary.clone()).or(preset.map(|preset| preset.summary().to_owned())),
+    );
+
+    let from_file = interview_args.from_file.then(|| {
+        file_line(
+            config,
+            prefill.path.clone(),
+            prefill.lineno.unwrap_or(DEFAULT_LINENO),
+        )
+    });
+
+    let line: String = match &from_file {
+        Some((_, _, line)) => line.clone(),
+        None => field(
+            "line",
+            &rules::enforce(rules.line.as_ref(), string),
+            prefill.line.clone(),
+        ),
+    };
 
-impl ErrorData {
-    fn get_errid(&self) -> String {
-        format!("E{:0fill$}", self.errnum, fill = 4)
+    let (unit, base) = span_positions(args, config);
+    if !args.render.quiet {
+        print_line_helper(
+            &line,
+            unit,
+            base,
+            args.render.charset,
+            args.render
+                .max_width
+                .map(usize::from)
+                .or_else(render::layout::terminal_width),
+            prefill.span.map(|(spos, epos)| spos..epos),
+        );
     }
 
-    fn print(&self, output: &mut Box<dyn io::Write>) {
-        let lineno_len = self.lineno.to_string().len();
-        let empty_line = color(format!("{}| ", " ".repeat(lineno_len + 1)), 4);
-
-        let mut string = bold(color(format!("error[{}]", self.get_errid()), 1));
-        string.extend(bold(format!(": {}\n", self.summary)).chars());
-        string.extend(
-            format!(
-                "{}{}{}:{}:{}\n",
-                " ".repeat(lineno_len),
-                color("--> ".to_owned(), 4),
-                self.path,
-                self.lineno,
-                self.spos + 1,
-            )
-            .chars(),
+    // The positions are entered in `unit` from `base`, as numbered by the
+    // ruler
+    let count = unit.from_byte(&line, line.len());
+    let from_byte =
+        |offset: usize| unit.from_byte(&line, position::floor_char_boundary(&line, offset));
+    let prefill_span = prefill
+        .span
+        .map(|(spos, epos)| (from_byte(spos), from_byte(epos)));
+    let (spos, epos) = match interview_args.pick {
+        true => {
+            let initial = prefill.span.map_or(0..0, |(spos, epos)| spos..epos);
+            let range = picker::pick(&line, initial).unwrap_or_else(|err| exit_with(err));
+            (range.start, range.end)
+        }
+        false => {
+            let (spos, epos) = prefill_span.unwrap_or((0, count));
+            let PositionRange(range) = field(
+                "error span",
+                &range_factory(base, count),
+                Some(PositionRange(spos + base..epos + base)),
+            );
+            let range = unit.to_bytes(&line, range.start - base..range.end - base);
+            (range.start, range.end)
+        }
+    };
+    let message_type = rules::enforce(rules.message.as_ref(), string);
+    let mut message = field(
+        "message",
+        &message_type,
+        (prefill.message.clone()).or(preset.map(|preset| preset.message().to_owned())),
+    );
+    // A trailing backslash continues the message on another line
+    while let Some(line) = message.strip_suffix('\\') {
+        message = format!(
+            "{}\n{}",
+            line,
+            field("message (continued)", &message_type, None)
         );
-        string.extend(empty_line.chars());
-        string.extend("\n".chars());
-        string.extend(color(format!("{} | ", self.lineno), 4).chars());
-        string.extend(self.line[0..self.spos].chars());
-        string.extend(bold(color(self.line[self.spos..self.epos].to_string(), 1)).chars());
-        string.extend(self.line[self.epos..].chars());
-        string.extend("\n".chars());
-        string.extend(empty_line.chars());
-        string.extend(" ".repeat(self.spos).chars());
-        string.extend(bold(color("^".repeat(self.epos - self.spos), 1)).chars());
-        string.extend(format!(" {}", bold(color(self.message.clone(), 1))).chars());
-        string.extend("\n".chars());
-        string.extend(empty_line.chars());
-
-        writeln!(output, "{}", string).expect("unexpected error happened");
     }
+    let (path, lineno) = match from_file {
+        Some((path, lineno, _)) => (path, lineno),
+        None => {
+            let lineno = field(
+                "line number",
+                &rules::enforce(rules.lineno.as_ref(), int_factory(usize::MIN, usize::MAX)),
+                Some(prefill.lineno.unwrap_or(DEFAULT_LINENO)),
+            );
+            let recent = paths::recent();
+            let default_path = prefill
+                .path
+                .clone()
+                .or(config.default_path.clone())
+                .or_else(|| recent.first().cloned())
+                .unwrap_or(DEFAULT_PATH.to_owned());
+            let path_type = rules::enforce(rules.path.as_ref(), string);
+            let answer = field_with(
+                "path",
+                &path_type,
+                Some(default_path.clone()),
+                |name, prompt| paths::read_answer(name, prompt, &recent),
+            );
+            // The default and the recent paths are already prefixed
+            let path = match answer == default_path || recent.contains(&answer) {
+                true => answer,
+                false => config.prefixed_path(answer),
+            };
+            if path != DEFAULT_PATH {
+                paths::remember(&path);
+            }
+            (path, lineno)
+        }
+    };
+    let errnum = field(
+        "error number",
+        &rules::enforce_errnum(
+            rules.errnum.as_ref(),
+            int_factory(usize::MIN, usize::MAX),
+            |errnum| printed_code(args, errnum),
+        ),
+        Some(prefill.errnum.unwrap_or(preset.map_or(
+            config.default_errnum.unwrap_or(DEFAULT_ERRNUM),
+            Preset::errnum,
+        ))),
+    );
+    // Any number of notes then of helps, until an empty answer
+    let notes: Vec<String> =
+        std::iter::from_fn(|| optional_field("note (empty to finish)", &string)).collect();
+    let helps: Vec<String> =
+        std::iter::from_fn(|| optional_field("help (empty to finish)", &string)).collect();
+
+    println!();
+
+    let (sources, span) = SourceMap::quote(path, lineno, &line, spos..epos, &[], &[]);
+
+    let err = Diagnostic {
+        summary: summary.into(),
+        message: message.into(),
+        span,
+        sources,
+        errnum,
+        fixit: interview_args.fixit.clone(),
+        fixit_diff: interview_args.fixit_diff,
+        notes,
+        helps,
+        ..Default::default()
+    };
+    emit_command(interview_args, &err);
+    err
 }
 
-fn string(string: &str) -> Result<String, RoostError> {
-    Ok(string.to_string())
+/// Prints or writes the non-interactive command giving the same diagnostic.
+fn emit_command(interview_args: &InterviewArgs, err: &Diagnostic) {
+    let command = Spec::from(err).to_command();
+
+    match &interview_args.emit_cmd {
+        Some(path) => fs::write(path, command + "\n").unwrap_or_else(|io_err| {
+            exit_with(RoostError::OutputError {
+                details: format!("could not write {}: {}", path.display(), io_err),
+            })
+        }),
+        None => println!("{}\n", bold(format!("$ {}", command))),
+    }
 }
 
-fn bold(string: String) -> String {
-    format!("\x1b[1m{}\x1b[0m", string)
+fn new(
+    args: &Args,
+    new_args: &NewArgs,
+    config: &Config,
+    workspace: Option<&Workspace>,
+) -> Diagnostic<'static> {
+    let prefill = match (&new_args.selection, workspace) {
+        (Some(selection), _) => Prefill::from(selection),
+        (None, _) if new_args.paste => pasted(),
+        (None, Some(workspace)) => workspace.pick_file(),
+        (None, None) => Prefill::default(),
+    };
+    interview(args, &new_args.interview, config, &prefill)
 }
 
-fn color(string: String, code: u8) -> String {
-    format!("\x1b[3{}m{}\x1b[39m", code, string)
+/// Prompts for a rustc error until one is found in the pasted text, and
+/// pre-fills the fields with the first one.
+fn pasted() -> Prefill {
+    loop {
+        let block = prompt::block("paste a rustc error, then an empty line");
+        let errs = import::rustc::parse(&block).unwrap_or_else(|err| exit_with(err));
+        if let Some(err) = errs.first() {
+            return Prefill::from(err);
+        }
+        eprintln!(
+            "{}",
+            bold(color(
+                format!(
+                    "ERR: {}",
+                    text("no rustc error found in the pasted text", &[])
+                ),
+                1
+            ))
+        );
+    }
 }
 
-fn make_prompt(name: String, default: Option<String>) -> String {
-    let mut prompt = name;
+/// Gives the diagnostics the code of `--code`, their number padded to
+/// `--code-width` digits if it is set. The width alone keeps the prefix of
+/// the style.
+fn apply_code(args: &Args, errs: &mut [Diagnostic]) {
+    let Some(pattern) = code_pattern(args) else {
+        return;
+    };
+    for err in errs {
+        err.code = Some(format_code(pattern, err.errnum, args.code_width));
+    }
+}
 
-    if let Some(default_value) = default {
-        prompt.extend(color(format!(" (default={})", default_value), 4).chars());
+/// The pattern of `--code`, or the one of the prefix of the style when
+/// only `--code-width` is set.
+fn code_pattern(args: &Args) -> Option<&str> {
+    match (&args.code, args.code_width) {
+        (Some(pattern), _) => Some(pattern.as_str()),
+        (None, Some(_)) if args.render.style == Emitter::Tsc => Some("TS#"),
+        (None, Some(_)) => Some("E#"),
+        (None, None) => None,
     }
+}
 
-    return bold(format!("{}: ", prompt));
+/// The code `errnum` is printed as, which the rule of the error number
+/// checks.
+fn printed_code(args: &Args, errnum: usize) -> String {
+    match code_pattern(args) {
+        Some(pattern) => format_code(pattern, errnum, args.code_width),
+        None => format!("E{:04}", errnum),
+    }
 }
 
-fn field<T, F>(name: &str, field_type: &F, default: Option<T>) -> T
-where
-    T: fmt::Display + 'static + Clone,
-    F: Fn(&str) -> Result<T, RoostError>,
-{
-    loop {
-        let prompt = make_prompt(name.to_owned(), default.as_ref().map(|t| t.to_string()));
+fn write_failed(err: io::Error) -> ! {
+    exit_with(RoostError::OutputError {
+        details: format!("could not write the diagnostic: {}", err),
+    })
+}
 
-        print!("{}", prompt);
-        io::stdout().flush().expect("could not flush stdout");
+/// Prints the rendered diagnostic to `output`, through the pager when it
+/// overflows the terminal or typed out with `--animate`.
+fn show(args: &Args, output: &mut Outputs, buffer: &[u8]) {
+    let paged = args.export.to_terminal
+        && !args.no_pager
+        && args.animate.is_none()
+        && pager::overflows(buffer)
+        && pager::page(buffer).unwrap_or_else(|err| write_failed(err));
+    match args.animate {
+        _ if paged => {}
+        Some(cps) => animate::write(output, &String::from_utf8_lossy(buffer), cps)
+            .unwrap_or_else(|err| write_failed(err)),
+        None => output
+            .write_all(buffer)
+            .unwrap_or_else(|err| write_failed(err)),
+    }
+}
 
-        let mut result = String::new();
-        io::stdin().read_line(&mut result).expect("failed input");
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
 
-        result = result.trim_end().to_string();
+/// Renders the spec again, after clearing the screen, every time it is
+/// modified.
+fn watch(args: &Args, path: &Path, format: Option<SpecFormat>) -> ! {
+    let mut last_modified = None;
 
-        if result.is_empty() {
-            if let Some(default) = &default {
-                return default.clone();
+    loop {
+        let current = modified(path);
+        if current != last_modified {
+            last_modified = current;
+            print!("\x1b[2J\x1b[H");
+
+            match spec::from_file(path, format) {
+                Ok(mut errs) => {
+                    apply_code(args, &mut errs);
+                    let output = args.get_output().unwrap_or_else(|err| exit_with(err));
+                    export::write(&args.export, &args.render, &errs, output)
+                        .unwrap_or_else(|err| write_failed(err));
+                }
+                Err(err) => eprintln!("{}", error_message(err)),
             }
-            eprintln!(
-                "{}",
-                bold(color(format!("ERR: field '{}' cannot be empty", name), 1))
-            );
+            io::stdout().flush().unwrap_or_else(|err| write_failed(err));
         }
 
-        match field_type(&result) {
-            Ok(value) => return value,
-            Err(_) => {
-                eprintln!(
-                    "{}",
-                    bold(color(
-                        format!("ERR: '{}' is not a valid {}", result, any::type_name::<F>()),
-                        3
-                    ))
-                );
-            }
-        }
+        thread::sleep(WATCH_INTERVAL);
     }
 }
 
-fn int_factory(min_value: usize, max_value: usize) -> impl Fn(&str) -> Result<usize, RoostError> {
-    move |raw_value: &str| {
-        let value = raw_value
-            .parse::<usize>()
-            .map_err(|_| RoostError::ValueError {
-                details: "invalid value".to_string(),
-            })?;
-
-        if value < min_value {
-            return Err(RoostError::ValueError {
-                details: "value is too smol".to_string(),
-            });
-        }
-        if value > max_value {
-            return Err(RoostError::ValueError {
-                details: "value is too big".to_string(),
-            });
+/// Renders each line of stdin as a spec as soon as it is read, so that roost
+/// can pretty-print a continuous stream of diagnostics.
+fn stream(args: &Args, output: &mut impl Write) {
+    for (index, line) in io::stdin().lines().enumerate() {
+        let line = line.unwrap_or_else(|err| {
+            exit_with(RoostError::ValueError {
+                details: format!("could not read stdin: {}", err),
+            })
+        });
+        if line.trim().is_empty() {
+            continue;
         }
 
-        Ok(value)
+        match spec::from_json(&line) {
+            Ok(mut errs) => {
+                apply_code(args, &mut errs);
+                export::write(&args.export, &args.render, &errs, &mut *output)
+                    .and_then(|()| output.flush())
+                    .unwrap_or_else(|err| write_failed(err));
+            }
+            Err(err) => eprintln!("{}", error_message(format!("line {}: {}", index + 1, err))),
+        }
     }
 }
 
-fn print_line_helper(line: String) {
-    let last_char_no_len = line.len().to_string().len() + 1;
-    let helper_len = last_char_no_len * line.len();
+fn main() {
+    let mut args = Args::parse_from(cargo::args());
+
+    let workspace = cargo::is_subcommand().then(|| {
+        let workspace = Workspace::load().unwrap_or_else(|err| exit_with(err));
+        env::set_current_dir(&workspace.root).unwrap_or_else(|err| {
+            exit_with(RoostError::ConfigError {
+                details: format!("could not enter the workspace root: {}", err),
+            })
+        });
+        args.export.package = Some(workspace.package.clone());
+        workspace
+    });
+
+    if let Some(Command::Convert(convert_args)) = &args.command {
+        match &convert_args.to {
+            Target::Style(style) => {
+                args.export.format = Format::Text;
+                args.render.style = *style;
+            }
+            Target::Format(format) => args.export.format = format.clone(),
+        }
+    }
 
-    println!("{}", "─".repeat(helper_len));
+    if let Some(Command::Share(share_args)) = &args.command {
+        args.export.format = match share_args.html {
+            true => Format::Html,
+            false => Format::Text,
+        };
+    }
 
-    for (i, _) in line.chars().enumerate() {
-        print!("{:^width$}", i, width = last_char_no_len);
+    if args.export.format == Format::Png && args.output.is_empty() {
+        Args::command()
+            .error(
+                ErrorKind::MissingRequiredArgument,
+                "`--format png` requires an `--output` file",
+            )
+            .exit();
     }
-    println!();
 
-    for c in line.chars() {
-        print!("{c:^width$}", c = c, width = last_char_no_len);
+    // The styling is generated whenever colors are allowed, and stripped on
+    // export when the output is not a terminal.
+    let ansi_console = console::enable_ansi();
+    ansi::set_enabled(args.export.color.enabled(ansi_console));
+    args.export.to_terminal =
+        output::only_stdout(&args.output) && io::stdout().is_terminal() && ansi_console;
+    if args.export.to_terminal {
+        style::set_depth(ColorDepth::detect());
     }
-    println!();
 
-    println!("{}", "─".repeat(helper_len));
-}
+    args.render
+        .resolve(args.export.to_terminal && args.export.format == Format::Text);
 
-fn main() {
-    let mut output = Args::parse()
-        .get_output()
-        .unwrap_or_else(|_| panic!("An unknown error occurred"));
-
-    let summary = field("summary", &mut string, None);
-    let line: String = field("line", &mut string, None);
-
-    print_line_helper(line.clone());
-
-    let spos = field("error start position", &int_factory(0, line.len()), Some(0));
-    let epos = field(
-        "error end position",
-        &int_factory(spos + 1, line.len() - 1),
-        Some(line.len() - 1),
-    ) + 1;
-    let message = field("message", &mut string, None);
-    let lineno = field(
-        "line number",
-        &int_factory(usize::MIN, usize::MAX),
-        Some(DEFAULT_LINENO),
-    );
-    let path = field("path", &mut string, Some(DEFAULT_PATH.to_owned()));
-    let errnum = field(
-        "error number",
-        &int_factory(usize::MIN, usize::MAX),
-        Some(DEFAULT_ERRNUM),
+    if args.copy.is_some() && args.export.format == Format::Png {
+        Args::command()
+            .error(
+                ErrorKind::ArgumentConflict,
+                "`--copy` only supports text formats, not `--format png`",
+            )
+            .exit();
+    }
+
+    if let Some(Command::Render(render_args)) = &args.command {
+        if render_args.spec.is_none()
+            && render_args.template.is_none()
+            && render_args.inline.summary.is_none()
+            && render_args.from != Some(SpecFormat::NdjsonIn)
+        {
+            Args::command()
+                .error(
+                    ErrorKind::MissingRequiredArgument,
+                    "`render` requires a `--spec` or `--template` file, or a `--summary`, unless reading `--from ndjson-in`",
+                )
+                .exit();
+        }
+        if !render_args.vars.is_empty() && render_args.template.is_none() {
+            Args::command()
+                .error(
+                    ErrorKind::MissingRequiredArgument,
+                    "`--var` requires a `--template` file",
+                )
+                .exit();
+        }
+    }
+
+    let config = Config::load().unwrap_or_else(|err| exit_with(err));
+    if let (None, Some(name)) = (&args.render.theme, &config.theme) {
+        args.render.theme = Some(render::theme::parse(name).unwrap_or_else(|err| exit_with(err)));
+    }
+    if let (None, Some(name)) = (&args.render.locale, &config.locale) {
+        args.render.locale = Some(Locale::from_str(name, true).unwrap_or_else(|_| {
+            exit_with(RoostError::ConfigError {
+                details: format!("invalid locale: {}", name),
+            })
+        }));
+    }
+    prompt::set_locale(args.render.locale.unwrap_or_default());
+    args.export.watermark |= config.watermark.unwrap_or(false);
+    if args.code.is_none() {
+        args.code = config.code.clone();
+    }
+    if args.code_width.is_none() {
+        args.code_width = config.code_width;
+    }
+    // The registry is only read when the diagnostics printed can advertise
+    // its codes, `explain` reading it on its own
+    let renders = !matches!(
+        args.command,
+        Some(Command::Explain(_) | Command::Serve(_) | Command::UiAnnotations(_) | Command::Schema)
     );
+    if renders && !args.render.quiet {
+        let explanations = Explanations::load(&config).unwrap_or_else(|err| exit_with(err));
+        args.render.explained = explanations.codes().cloned().collect();
+    }
 
-    println!();
+    let mut output = args.get_output().unwrap_or_else(|err| exit_with(err));
 
-    let err = ErrorData {
-        summary,
-        line,
-        message,
-        spos,
-        epos,
-        lineno,
-        path,
-        errnum,
+    let mut errs = match &args.command {
+        Some(Command::Import(import_args)) => {
+            import::run(import_args).unwrap_or_else(|err| exit_with(err))
+        }
+        Some(Command::Random(random_args)) => random::run(random_args),
+        Some(Command::Buildlog(buildlog_args)) => {
+            let mut errs =
+                buildlog::diagnostics(buildlog_args).unwrap_or_else(|err| exit_with(err));
+            apply_code(&args, &mut errs);
+            let log = buildlog::render(buildlog_args, &args.render, &errs);
+            let log = match args.export.colored() {
+                true => log,
+                false => ansi::strip(&log),
+            };
+            output
+                .write_all(log.as_bytes())
+                .unwrap_or_else(|err| write_failed(err));
+            return;
+        }
+        Some(Command::UiAnnotations(ui_annotations_args)) => {
+            let annotations =
+                ui_annotations::run(ui_annotations_args).unwrap_or_else(|err| exit_with(err));
+            output
+                .write_all(annotations.as_bytes())
+                .unwrap_or_else(|err| write_failed(err));
+            return;
+        }
+        Some(Command::Schema) => {
+            output
+                .write_all((spec::json_schema() + "\n").as_bytes())
+                .unwrap_or_else(|err| write_failed(err));
+            return;
+        }
+        #[cfg(feature = "tui")]
+        Some(Command::Tui) => {
+            let (unit, base) = span_positions(&args, &config);
+            vec![tui::run(&mut args.render, &config, unit, base)
+                .unwrap_or_else(|err| exit_with(err))]
+        }
+        Some(Command::Annotate(annotate_args)) => {
+            let unit = args.position_unit.unwrap_or(PositionUnit::Bytes);
+            vec![annotate::run(annotate_args, unit).unwrap_or_else(|err| exit_with(err))]
+        }
+        Some(Command::Render(render_args)) => match (&render_args.template, &render_args.spec) {
+            (Some(path), _) => template::from_file(path, render_args.from, &render_args.vars)
+                .unwrap_or_else(|err| exit_with(err)),
+            (None, Some(path)) if render_args.watch => watch(&args, path, render_args.from),
+            (None, Some(path)) => {
+                spec::from_file(path, render_args.from).unwrap_or_else(|err| exit_with(err))
+            }
+            (None, None) => match render_args
+                .inline
+                .to_diagnostic(args.position_unit.unwrap_or(PositionUnit::Bytes))
+            {
+                Some(err) => vec![err.unwrap_or_else(|err| exit_with(err))],
+                None => {
+                    stream(&args, &mut output);
+                    return;
+                }
+            },
+        },
+        Some(Command::Convert(convert_args)) => {
+            convert::read(convert_args).unwrap_or_else(|err| exit_with(err))
+        }
+        Some(Command::Share(share_args)) => match &share_args.spec {
+            Some(path) => spec::from_file(path, None).unwrap_or_else(|err| exit_with(err)),
+            None => vec![new(&args, &args.new, &config, workspace.as_ref())],
+        },
+        Some(Command::Explain(explain_args)) => {
+            let explanations = Explanations::load(&config).unwrap_or_else(|err| exit_with(err));
+            explain::run(explain_args, &explanations).unwrap_or_else(|err| exit_with(err));
+            return;
+        }
+        Some(Command::Serve(serve_args)) => {
+            serve::run(serve_args).unwrap_or_else(|err| exit_with(err));
+            return;
+        }
+        Some(Command::FromDiff(diff_args)) => {
+            let selection = diff::pick(diff_args).unwrap_or_else(|err| exit_with(err));
+            vec![interview(
+                &args,
+                &diff_args.interview,
+                &config,
+                &Prefill::from(&selection),
+            )]
+        }
+        Some(Command::New(new_args)) => vec![new(&args, new_args, &config, workspace.as_ref())],
+        None => vec![new(&args, &args.new, &config, workspace.as_ref())],
+    };
+
+    if args.meme {
+        let memes = meme::load().unwrap_or_else(|err| exit_with(err));
+        meme::apply(&mut errs, &memes);
+    }
+    apply_code(&args, &mut errs);
+
+    // The diagnostic of the prompts can be changed once printed, when it is
+    // printed as text to the terminal the prompts are answered in
+    let interview_args = match &args.command {
+        Some(Command::New(new_args)) => Some(&new_args.interview),
+        Some(Command::FromDiff(diff_args)) => Some(&diff_args.interview),
+        None => Some(&args.new.interview),
+        _ => None,
+    }
+    .filter(|_| {
+        args.export.to_terminal && args.export.format == Format::Text && io::stdin().is_terminal()
+    });
+
+    let buffer = loop {
+        let mut buffer = Vec::new();
+        export::write(&args.export, &args.render, &errs, &mut buffer)
+            .unwrap_or_else(|err| write_failed(err));
+
+        if let Some(Command::Share(share_args)) = &args.command {
+            // The text is shared without its colors, which a paste would show
+            // as escape sequences
+            let rendered = ansi::strip(&String::from_utf8_lossy(&buffer));
+            let url =
+                share::upload(share_args, &config, &rendered).unwrap_or_else(|err| exit_with(err));
+            output
+                .write_all(format!("{}\n", url).as_bytes())
+                .unwrap_or_else(|err| write_failed(err));
+            return;
+        }
+
+        show(&args, &mut output, &buffer);
+
+        let Some(interview_args) = interview_args else {
+            break buffer;
+        };
+        match review::choose() {
+            Choice::Accept => break buffer,
+            Choice::Edit => {
+                review::edit(&args, &config, &mut errs[0]);
+                apply_code(&args, &mut errs);
+                emit_command(interview_args, &errs[0]);
+            }
+            Choice::Theme => {
+                if let Some(theme) = review::theme() {
+                    args.render.theme = Some(theme);
+                }
+            }
+            Choice::Format => {
+                if let Some(format) = review::format() {
+                    args.export.format = format;
+                }
+            }
+        }
     };
 
-    err.print(&mut output);
+    if let Some(mode) = args.copy {
+        clipboard::copy(&String::from_utf8_lossy(&buffer), mode)
+            .unwrap_or_else(|err| exit_with(err));
+    }
 }
@@ -0,0 +1,65 @@
+use std::fs;
+
+use roost::{Diagnostic, RoostError};
+use serde::Deserialize;
+
+use crate::config;
+
+const BUNDLED: &str = include_str!("../assets/memes.toml");
+const USER_FILE: &str = "memes.toml";
+
+/// A summary and a label replacing the ones of a diagnostic.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Meme {
+    summary: String,
+    #[serde(default)]
+    message: String,
+    /// Code of the diagnostic, kept if omitted
+    errnum: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Memes {
+    memes: Vec<Meme>,
+}
+
+fn parse(text: &str, origin: &str) -> Result<Vec<Meme>, RoostError> {
+    toml::from_str::<Memes>(text)
+        .map(|memes| memes.memes)
+        .map_err(|err| RoostError::ConfigError {
+            details: format!("invalid {}: {}", origin, err.message()),
+        })
+}
+
+/// The bundled memes, followed by the ones of the user's `memes.toml`.
+pub fn load() -> Result<Vec<Meme>, RoostError> {
+    let mut memes = parse(BUNDLED, "bundled memes")?;
+
+    if let Some(path) = config::user_config_dir().map(|dir| dir.join(USER_FILE)) {
+        if let Ok(text) = fs::read_to_string(&path) {
+            memes.extend(parse(&text, &path.display().to_string())?);
+        }
+    }
+
+    Ok(memes)
+}
+
+/// Swaps the summary and the label of each diagnostic for consecutive memes,
+/// starting at a random one, so that a run shows each of them once before
+/// any repeats.
+pub fn apply(errs: &mut [Diagnostic], memes: &[Meme]) {
+    if memes.is_empty() {
+        return;
+    }
+
+    let start = fastrand::usize(..memes.len());
+    for (index, err) in errs.iter_mut().enumerate() {
+        let meme = &memes[(start + index) % memes.len()];
+        err.summary = meme.summary.clone().into();
+        err.message = meme.message.clone().into();
+        if let Some(errnum) = meme.errnum {
+            err.errnum = errnum;
+        }
+    }
+}
@@ -0,0 +1,85 @@
+use core::fmt;
+use std::error::Error;
+
+use ::miette::{
+    LabeledSpan, MietteError, MietteSpanContents, Severity, SourceCode, SourceSpan, SpanContents,
+};
+
+use crate::{source::SourceFile, Diagnostic};
+
+/// A roost diagnostic reported through miette, whose `Display` is the
+/// summary rather than the whole rendering.
+#[derive(Clone, Debug)]
+pub struct Synthetic(pub Diagnostic<'static>);
+
+impl From<Diagnostic<'static>> for Synthetic {
+    fn from(diagnostic: Diagnostic<'static>) -> Self {
+        Synthetic(diagnostic)
+    }
+}
+
+impl fmt::Display for Synthetic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0.summary)
+    }
+}
+
+impl Error for Synthetic {}
+
+impl ::miette::Diagnostic for Synthetic {
+    fn code<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
+        Some(Box::new(self.0.get_errid()))
+    }
+
+    fn severity(&self) -> Option<Severity> {
+        Some(match self.0.severity {
+            crate::Severity::Error => Severity::Error,
+            crate::Severity::Warning => Severity::Warning,
+        })
+    }
+
+    fn help<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
+        let fixit = (self.0.fixit.as_ref()).map(|fixit| format!("replace with `{}`", fixit));
+        let helps: Vec<String> = self.0.helps.iter().cloned().chain(fixit).collect();
+        (!helps.is_empty()).then(|| Box::new(helps.join("\n")) as Box<dyn fmt::Display>)
+    }
+
+    fn source_code(&self) -> Option<&dyn SourceCode> {
+        Some(self.0.file())
+    }
+
+    fn labels(&self) -> Option<Box<dyn Iterator<Item = LabeledSpan> + '_>> {
+        let range = &self.0.span.range;
+        let label = Some(self.0.message.to_string()).filter(|message| !message.is_empty());
+
+        Some(Box::new(std::iter::once(LabeledSpan::new(
+            label,
+            range.start,
+            range.end - range.start,
+        ))))
+    }
+}
+
+impl SourceCode for SourceFile<'_> {
+    /// Reads the span from the contents, numbering the lines from the one
+    /// the contents start at.
+    fn read_span<'a>(
+        &'a self,
+        span: &SourceSpan,
+        context_lines_before: usize,
+        context_lines_after: usize,
+    ) -> Result<Box<dyn SpanContents<'a> + 'a>, MietteError> {
+        let contents = self
+            .contents
+            .read_span(span, context_lines_before, context_lines_after)?;
+
+        Ok(Box::new(MietteSpanContents::new_named(
+            self.path.to_string(),
+            contents.data(),
+            *contents.span(),
+            (contents.line() + self.first_lineno).saturating_sub(1),
+            contents.column(),
+            contents.line_count(),
+        )))
+    }
+}
@@ -0,0 +1,109 @@
+use std::{
+    fs::File,
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use roost::{ansi, RoostError};
+
+/// Written to standard output when given as an `--output`.
+pub const STDOUT: &str = "-";
+
+fn is_stdout(path: &Path) -> bool {
+    path.as_os_str() == STDOUT
+}
+
+/// Whether everything is written to standard output.
+pub fn only_stdout(paths: &[PathBuf]) -> bool {
+    paths.iter().all(|path| is_stdout(path))
+}
+
+struct Sink {
+    writer: Box<dyn Write>,
+    /// `.ans` files get a full reset at the end of every line
+    ans_dump: bool,
+}
+
+impl Sink {
+    /// Writes `buf` with a full reset before each of its line breaks, so
+    /// that the dump replays cleanly with `cat` whatever the terminal state
+    /// was before. The bytes are left as they are, whatever chunks they
+    /// come in, as a line break is never part of a multi-byte character.
+    fn write_reset_lines(&mut self, buf: &[u8]) -> io::Result<()> {
+        for line in buf.split_inclusive(|&byte| byte == b'\n') {
+            match line.strip_suffix(b"\n") {
+                Some(line) => {
+                    self.writer.write_all(line)?;
+                    self.writer.write_all(ansi::RESET.as_bytes())?;
+                    self.writer.write_all(b"\n")?;
+                }
+                None => self.writer.write_all(line)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Copies everything written to each of the `--output`s.
+pub struct Outputs {
+    sinks: Vec<Sink>,
+}
+
+impl Outputs {
+    /// Opens the outputs, truncating the files unless `append` is set.
+    /// Without any, everything goes to standard output.
+    pub fn open(paths: &[PathBuf], append: bool) -> Result<Outputs, RoostError> {
+        let stdout = || Sink {
+            writer: Box::new(io::stdout()),
+            ans_dump: false,
+        };
+        if paths.is_empty() {
+            return Ok(Outputs {
+                sinks: vec![stdout()],
+            });
+        }
+
+        let sinks = paths
+            .iter()
+            .map(|path| {
+                if is_stdout(path) {
+                    return Ok(stdout());
+                }
+
+                File::options()
+                    .write(true)
+                    .create(true)
+                    .truncate(!append)
+                    .append(append)
+                    .open(path)
+                    .map(|file| Sink {
+                        writer: Box::new(file),
+                        ans_dump: path.extension().is_some_and(|ext| ext == "ans"),
+                    })
+                    .map_err(|err| RoostError::OutputError {
+                        details: format!("could not open {}: {}", path.display(), err),
+                    })
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Outputs { sinks })
+    }
+}
+
+impl Write for Outputs {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        for sink in &mut self.sinks {
+            match sink.ans_dump {
+                true => sink.write_reset_lines(buf)?,
+                false => sink.writer.write_all(buf)?,
+            }
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.sinks
+            .iter_mut()
+            .try_for_each(|sink| sink.writer.flush())
+    }
+}
@@ -0,0 +1,46 @@
+use std::{
+    env,
+    io::{self, Write},
+    process::{Command, Stdio},
+};
+
+use terminal_size::{terminal_size, Height};
+
+const DEFAULT_PAGER: &str = "less";
+
+/// Whether `text` has more lines than the terminal.
+pub fn overflows(text: &[u8]) -> bool {
+    terminal_size().is_some_and(|(_, Height(height))| {
+        text.iter().filter(|&&byte| byte == b'\n').count() >= height as usize
+    })
+}
+
+/// Shows `text` in `$PAGER`, or `less`, keeping the colors. Returns `false`
+/// without writing anything if the pager could not be started.
+pub fn page(text: &[u8]) -> io::Result<bool> {
+    let pager = env::var("PAGER")
+        .ok()
+        .filter(|pager| !pager.trim().is_empty())
+        .unwrap_or(DEFAULT_PAGER.to_owned());
+    let mut words = pager.split_whitespace();
+    let Some(program) = words.next() else {
+        return Ok(false);
+    };
+
+    let mut command = Command::new(program);
+    command.args(words).stdin(Stdio::piped());
+    if env::var_os("LESS").is_none() {
+        // As git does: keep the colors, and quit at the end of the text.
+        command.env("LESS", "FRX");
+    }
+    let Ok(mut child) = command.spawn() else {
+        return Ok(false);
+    };
+
+    let written = child.stdin.take().expect("stdin is piped").write_all(text);
+    child.wait()?;
+    match written {
+        Err(err) if err.kind() != io::ErrorKind::BrokenPipe => Err(err),
+        _ => Ok(true),
+    }
+}
@@ -0,0 +1,203 @@
+//! The `path` prompt: Tab completing the paths against the filesystem, and
+//! the paths used recently, proposed as the default and with ↑/↓.
+
+use std::{
+    fs,
+    io::{self, IsTerminal, Write},
+    path::PathBuf,
+};
+
+use crossterm::{
+    cursor,
+    event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    queue,
+    style::Print,
+    terminal::{self, ClearType},
+};
+
+use roost::{
+    prompt::{self, exit_with, text},
+    RoostError,
+};
+
+/// How many paths the cache of the recent ones keeps.
+const RECENT_LEN: usize = 10;
+
+/// The file of the recently used paths, one a line from the latest, in the
+/// user cache directory.
+fn recent_file() -> Option<PathBuf> {
+    roost::user_dir("XDG_CACHE_HOME", ".cache").map(|dir| dir.join("recent-paths"))
+}
+
+/// The paths used recently, from the latest.
+pub fn recent() -> Vec<String> {
+    recent_file()
+        .and_then(|file| fs::read_to_string(file).ok())
+        .map(|contents| contents.lines().map(str::to_owned).collect())
+        .unwrap_or_default()
+}
+
+/// Puts `path` first in the recently used paths. Failing to write them only
+/// loses the suggestion, so it is not an error.
+pub fn remember(path: &str) {
+    let Some(file) = recent_file() else {
+        return;
+    };
+    let mut paths = recent();
+    paths.retain(|recent| recent != path);
+    paths.insert(0, path.to_owned());
+    paths.truncate(RECENT_LEN);
+
+    if let Some(dir) = file.parent() {
+        let _ = fs::create_dir_all(dir);
+    }
+    let _ = fs::write(file, paths.join("\n") + "\n");
+}
+
+/// The entries of the directory of `partial` whose name starts like its
+/// last component, as `partial` completed with them, the directories ending
+/// with a `/`. Hidden entries are only given for a `.` typed.
+fn completions(partial: &str) -> Vec<String> {
+    let (dir, name) = match partial.rfind('/') {
+        Some(index) => partial.split_at(index + 1),
+        None => ("", partial),
+    };
+    let Ok(entries) = fs::read_dir(if dir.is_empty() { "." } else { dir }) else {
+        return Vec::new();
+    };
+
+    let mut completions: Vec<String> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let file_name = entry.file_name().into_string().ok()?;
+            let shown = file_name.starts_with(name)
+                && (!file_name.starts_with('.') || name.starts_with('.'));
+            let slash = match entry.path().is_dir() {
+                true => "/",
+                false => "",
+            };
+            shown.then(|| format!("{}{}{}", dir, file_name, slash))
+        })
+        .collect();
+    completions.sort();
+
+    completions
+}
+
+/// The longest start shared by all of `strings`.
+fn common_prefix(strings: &[String]) -> String {
+    let Some((first, rest)) = strings.split_first() else {
+        return String::new();
+    };
+    let mut len = first.len();
+    for string in rest {
+        len = (first.char_indices())
+            .zip(string.chars())
+            .find(|&((_, a), b)| a != b)
+            .map_or(len.min(string.len()), |((index, _), _)| index.min(len));
+    }
+
+    first[..len].to_owned()
+}
+
+/// Prints `prompt` and reads the answer to the field `name` as
+/// `prompt::read_answer` does, with Tab completing the path typed and ↑/↓
+/// going through the `recent` paths, when the input is a terminal.
+pub fn read_answer(name: &str, prompt: &str, recent: &[String]) -> String {
+    if !io::stdin().is_terminal() || !io::stdout().is_terminal() {
+        return prompt::read_answer(name, prompt);
+    }
+
+    let terminal_error = |err: io::Error| RoostError::OutputError {
+        details: format!("could not use the terminal: {}", err),
+    };
+    let answer = terminal::enable_raw_mode()
+        .and_then(|()| edit(prompt, recent))
+        .map_err(terminal_error);
+    let _ = terminal::disable_raw_mode();
+    println!();
+
+    match answer {
+        Ok(Some(answer)) => answer,
+        Ok(None) => exit_with(RoostError::AbortError {
+            details: text("no value given for '{}'", &[&text(name, &[])]),
+        }),
+        Err(err) => exit_with(err),
+    }
+}
+
+/// Edits the answer in raw mode until Enter, or gives `None` for Escape,
+/// Ctrl-C or Ctrl-D on an empty answer.
+fn edit(prompt: &str, recent: &[String]) -> io::Result<Option<String>> {
+    let mut stdout = io::stdout();
+    let mut answer = String::new();
+    // The recent path shown, `None` for the one being typed
+    let mut shown: Option<usize> = None;
+    let mut typed = String::new();
+
+    loop {
+        queue!(
+            stdout,
+            cursor::MoveToColumn(0),
+            terminal::Clear(ClearType::CurrentLine),
+            Print(prompt),
+            Print(&answer)
+        )?;
+        stdout.flush()?;
+
+        let Event::Key(KeyEvent {
+            code,
+            modifiers,
+            kind: KeyEventKind::Press,
+            ..
+        }) = event::read()?
+        else {
+            continue;
+        };
+        let control = modifiers.contains(KeyModifiers::CONTROL);
+        match code {
+            KeyCode::Enter => return Ok(Some(answer)),
+            KeyCode::Esc => return Ok(None),
+            KeyCode::Char('c') if control => return Ok(None),
+            KeyCode::Char('d') if control && answer.is_empty() => return Ok(None),
+            KeyCode::Char(c) if !control => {
+                answer.push(c);
+                shown = None;
+            }
+            KeyCode::Backspace => {
+                answer.pop();
+                shown = None;
+            }
+            KeyCode::Up | KeyCode::Down => {
+                if shown.is_none() {
+                    typed.clone_from(&answer);
+                }
+                shown = match (code, shown) {
+                    (KeyCode::Up, None) if !recent.is_empty() => Some(0),
+                    (KeyCode::Up, Some(index)) => Some((index + 1).min(recent.len() - 1)),
+                    (KeyCode::Down, Some(0)) => None,
+                    (KeyCode::Down, Some(index)) => Some(index - 1),
+                    _ => shown,
+                };
+                answer = shown.map_or(typed.clone(), |index| recent[index].clone());
+            }
+            KeyCode::Tab => {
+                let completions = completions(&answer);
+                let prefix = common_prefix(&completions);
+                // Listed under the prompt when they cannot be told apart
+                if prefix.len() <= answer.len() && completions.len() > 1 {
+                    queue!(
+                        stdout,
+                        Print("\r\n"),
+                        Print(completions.join("  ")),
+                        Print("\r\n")
+                    )?;
+                } else if prefix.len() > answer.len() {
+                    answer = prefix;
+                }
+                shown = None;
+            }
+            _ => {}
+        }
+    }
+}
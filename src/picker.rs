@@ -0,0 +1,158 @@
+//! Selection of the span with the arrow keys, as with `--pick`.
+
+use std::{
+    io::{self, IsTerminal, Write},
+    ops::Range,
+};
+
+use crossterm::{
+    cursor,
+    event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    queue,
+    style::{Attribute, Print, SetAttribute},
+    terminal::{self, ClearType},
+};
+
+use roost::{position, RoostError};
+
+const HELP: &str = "←/→ move, Shift+←/→ extend, Home/End jump, Enter confirm, Esc cancel";
+
+/// The selected graphemes, from `anchor` to `cursor` both included. Both
+/// being at the end of the line selects the spot just past it.
+struct Selection {
+    anchor: usize,
+    cursor: usize,
+}
+
+impl Selection {
+    fn range(&self) -> Range<usize> {
+        self.anchor.min(self.cursor)..self.anchor.max(self.cursor) + 1
+    }
+}
+
+/// Lets the user select a span of `line` in raw mode, starting from the
+/// byte range `initial`, and gives its byte range.
+pub fn pick(line: &str, initial: Range<usize>) -> Result<Range<usize>, RoostError> {
+    if !io::stdin().is_terminal() || !io::stdout().is_terminal() {
+        return Err(RoostError::ValueError {
+            details: "`--pick` needs a terminal".to_owned(),
+        });
+    }
+
+    let graphemes = position::graphemes(line);
+    let count = graphemes.len();
+    let index = |offset: usize| {
+        graphemes
+            .iter()
+            .position(|&(start, _)| start >= offset)
+            .unwrap_or(count)
+    };
+    let mut selection = Selection {
+        anchor: index(initial.start),
+        cursor: index(initial.end)
+            .saturating_sub(1)
+            .max(index(initial.start)),
+    };
+
+    let terminal_error = |err: io::Error| RoostError::OutputError {
+        details: format!("could not use the terminal: {}", err),
+    };
+    terminal::enable_raw_mode().map_err(terminal_error)?;
+    let picked = select(&graphemes, &mut selection);
+    terminal::disable_raw_mode().map_err(terminal_error)?;
+    println!();
+
+    match picked.map_err(terminal_error)? {
+        true => {
+            let range = selection.range();
+            let offset =
+                |index: usize| graphemes.get(index).map_or(line.len(), |&(start, _)| start);
+            Ok(match range.start == count {
+                true => line.len()..line.len(),
+                false => offset(range.start)..offset(range.end),
+            })
+        }
+        false => Err(RoostError::AbortError {
+            details: "no span selected".to_owned(),
+        }),
+    }
+}
+
+/// Moves the selection until Enter (`true`) or Escape (`false`) is pressed.
+fn select(graphemes: &[(usize, &str)], selection: &mut Selection) -> io::Result<bool> {
+    let count = graphemes.len();
+    let mut stdout = io::stdout();
+    println!("{}\r", HELP);
+
+    loop {
+        draw(&mut stdout, graphemes, selection)?;
+
+        let Event::Key(KeyEvent {
+            code,
+            modifiers,
+            kind: KeyEventKind::Press,
+            ..
+        }) = event::read()?
+        else {
+            continue;
+        };
+        let extend = modifiers.contains(KeyModifiers::SHIFT);
+        // The spot past the end of the line can only be selected alone
+        let last = match extend && selection.anchor < count {
+            true => count.saturating_sub(1),
+            false => count,
+        };
+        let cursor = match code {
+            KeyCode::Left => selection.cursor.saturating_sub(1),
+            KeyCode::Right => (selection.cursor + 1).min(last),
+            KeyCode::Home => 0,
+            KeyCode::End => last,
+            KeyCode::Enter => return Ok(true),
+            KeyCode::Esc => return Ok(false),
+            KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => return Ok(false),
+            _ => continue,
+        };
+
+        selection.cursor = cursor;
+        if !extend {
+            selection.anchor = cursor;
+        }
+        if selection.anchor == count && selection.cursor < count {
+            selection.anchor = count.saturating_sub(1);
+        }
+    }
+}
+
+/// Redraws the line, the selected graphemes in reverse video.
+fn draw(
+    stdout: &mut io::Stdout,
+    graphemes: &[(usize, &str)],
+    selection: &Selection,
+) -> io::Result<()> {
+    let range = selection.range();
+    queue!(
+        stdout,
+        cursor::MoveToColumn(0),
+        terminal::Clear(ClearType::CurrentLine)
+    )?;
+    for (index, (_, grapheme)) in graphemes.iter().enumerate() {
+        match range.contains(&index) {
+            true => queue!(
+                stdout,
+                SetAttribute(Attribute::Reverse),
+                Print(grapheme),
+                SetAttribute(Attribute::NoReverse)
+            )?,
+            false => queue!(stdout, Print(grapheme))?,
+        }
+    }
+    if range.start == graphemes.len() {
+        queue!(
+            stdout,
+            SetAttribute(Attribute::Reverse),
+            Print(' '),
+            SetAttribute(Attribute::NoReverse)
+        )?;
+    }
+    stdout.flush()
+}
@@ -0,0 +1,254 @@
+//! Positions in a source line. Spans are byte ranges, while the positions
+//! given to roost can count other units, the prompts counting grapheme
+//! clusters by default so that a position never falls inside a character.
+
+use core::{fmt, ops::Range};
+
+use clap::ValueEnum;
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthChar;
+
+use crate::RoostError;
+
+/// What the positions given to roost count.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum PositionUnit {
+    /// UTF-8 bytes, as in rustc's JSON spans
+    Bytes,
+    /// Unicode scalar values
+    Chars,
+    /// Terminal columns, as shown by most editors
+    Columns,
+    /// Characters as displayed, as numbered by the ruler of the prompts
+    Graphemes,
+}
+
+impl PositionUnit {
+    /// The byte offset of the position `pos`, or the length of the line past
+    /// its end. A position inside a character gives the start of the next
+    /// one.
+    pub fn to_byte(self, line: &str, pos: usize) -> usize {
+        if self == PositionUnit::Bytes {
+            return pos.min(line.len());
+        }
+
+        self.starts(line)
+            .find(|&(position, _)| position >= pos)
+            .map_or(line.len(), |(_, offset)| offset)
+    }
+
+    /// The byte range of the positions `range`, which takes in whole the
+    /// characters its ends fall inside of. An empty range stays empty.
+    pub fn to_bytes(self, line: &str, range: Range<usize>) -> Range<usize> {
+        let end = self.to_byte(line, range.end);
+        if range.is_empty() {
+            return end..end;
+        }
+
+        let start = self.to_byte(line, range.start);
+        // A start inside a character moves back to the start of it
+        let start = match self.from_byte(line, start) > range.start {
+            true => (self.starts(line))
+                .map(|(_, offset)| offset)
+                .take_while(|&offset| offset < start)
+                .last()
+                .unwrap_or(0),
+            false => start,
+        };
+
+        start..end
+    }
+
+    /// The position and the byte offset of the start of each character of
+    /// `line`, as counted by `self`.
+    fn starts(self, line: &str) -> Box<dyn Iterator<Item = (usize, usize)> + '_> {
+        match self {
+            PositionUnit::Bytes => {
+                Box::new(line.char_indices().map(|(offset, _)| (offset, offset)))
+            }
+            PositionUnit::Chars => {
+                Box::new(line.char_indices().map(|(offset, _)| offset).enumerate())
+            }
+            PositionUnit::Graphemes => Box::new(
+                line.grapheme_indices(true)
+                    .map(|(offset, _)| offset)
+                    .enumerate(),
+            ),
+            PositionUnit::Columns => {
+                Box::new(line.char_indices().scan(0, |column, (offset, c)| {
+                    let start = *column;
+                    *column += c.width().unwrap_or(0);
+                    Some((start, offset))
+                }))
+            }
+        }
+    }
+
+    /// The position of the byte at `offset`, which starts a character.
+    pub fn from_byte(self, line: &str, offset: usize) -> usize {
+        let before = &line[..offset];
+        match self {
+            PositionUnit::Bytes => offset,
+            PositionUnit::Chars => before.chars().count(),
+            PositionUnit::Columns => before.chars().map(|c| c.width().unwrap_or(0)).sum(),
+            PositionUnit::Graphemes => before.graphemes(true).count(),
+        }
+    }
+}
+
+/// A range of positions, written `start..end`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PositionRange(pub Range<usize>);
+
+impl fmt::Display for PositionRange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}..{}", self.0.start, self.0.end)
+    }
+}
+
+/// Parses `start..end`, `start..=last`, `start+length` or a bare position,
+/// which stands for the one character starting there. The range can be
+/// empty but not reversed.
+pub fn parse_range(raw: &str) -> Result<Range<usize>, RoostError> {
+    let invalid = || RoostError::ValueError {
+        details: format!(
+            "expected 'start..end', 'start..=last', 'start+length' or 'start', found '{}'",
+            raw
+        ),
+    };
+    let number = |raw: &str| raw.trim().parse::<usize>().map_err(|_| invalid());
+    let add = |position: usize, length: usize| {
+        position
+            .checked_add(length)
+            .ok_or_else(|| RoostError::ValueError {
+                details: format!("the range '{}' ends past the largest position", raw),
+            })
+    };
+
+    let (start, end) = match (raw.split_once(".."), raw.split_once('+')) {
+        (Some((start, end)), _) => match end.strip_prefix('=') {
+            Some(last) => (number(start)?, add(number(last)?, 1)?),
+            None => (number(start)?, number(end)?),
+        },
+        (None, Some((start, length))) => {
+            let start = number(start)?;
+            (start, add(start, number(length)?)?)
+        }
+        (None, None) => {
+            let start = number(raw)?;
+            (start, add(start, 1)?)
+        }
+    };
+
+    match start <= end {
+        true => Ok(start..end),
+        false => Err(invalid()),
+    }
+}
+
+/// The grapheme clusters of `line` with their byte offset, as shown by the
+/// ruler of the prompts.
+pub fn graphemes(line: &str) -> Vec<(usize, &str)> {
+    line.grapheme_indices(true).collect()
+}
+
+/// Moves `offset` back to the start of the character it falls in.
+pub fn floor_char_boundary(line: &str, offset: usize) -> usize {
+    let mut offset = offset.min(line.len());
+    while !line.is_char_boundary(offset) {
+        offset -= 1;
+    }
+    offset
+}
+
+/// Moves `offset` forward to the end of the character it falls in.
+pub fn ceil_char_boundary(line: &str, offset: usize) -> usize {
+    let mut offset = offset.min(line.len());
+    while !line.is_char_boundary(offset) {
+        offset += 1;
+    }
+    offset
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_range_forms() {
+        assert_eq!(parse_range("2..5").unwrap(), 2..5);
+        assert_eq!(parse_range("2..=4").unwrap(), 2..5);
+        assert_eq!(parse_range("2+3").unwrap(), 2..5);
+        assert_eq!(parse_range(" 2 .. 5 ").unwrap(), 2..5);
+        assert_eq!(parse_range("7").unwrap(), 7..8);
+        assert_eq!(parse_range("3..3").unwrap(), 3..3);
+    }
+
+    #[test]
+    fn parse_range_refuses_reversed_and_garbage() {
+        for raw in ["5..2", "", "a..b", "1..=", "-1+2", "1..2..3"] {
+            assert!(
+                matches!(parse_range(raw), Err(RoostError::ValueError { .. })),
+                "{:?}",
+                raw
+            );
+        }
+    }
+
+    #[test]
+    fn parse_range_refuses_overflow() {
+        let max = usize::MAX.to_string();
+        for raw in [
+            format!("0..={}", max),
+            format!("{}+1", max),
+            format!("1+{}", max),
+            max,
+        ] {
+            assert!(
+                matches!(parse_range(&raw), Err(RoostError::ValueError { .. })),
+                "{}",
+                raw
+            );
+        }
+    }
+
+    #[test]
+    fn to_byte_counts_each_unit() {
+        // `é` takes two bytes, `中` three bytes and two columns, and the
+        // `e` with a combining accent two chars but one grapheme
+        let line = "é中e\u{301}x";
+        let offsets = [0, 2, 5, 8, 9];
+        assert_eq!(PositionUnit::Bytes.to_byte(line, 5), 5);
+        for (pos, &offset) in offsets.iter().enumerate() {
+            assert_eq!(PositionUnit::Graphemes.to_byte(line, pos), offset);
+        }
+        assert_eq!(PositionUnit::Chars.to_byte(line, 3), 6);
+        assert_eq!(PositionUnit::Columns.to_byte(line, 3), 5);
+        assert_eq!(PositionUnit::Chars.to_byte(line, 99), line.len());
+    }
+
+    #[test]
+    fn to_byte_rounds_up_inside_a_character() {
+        assert_eq!(PositionUnit::Columns.to_byte("中x", 1), 3);
+    }
+
+    #[test]
+    fn from_byte_counts_each_unit() {
+        let line = "é中e\u{301}x";
+        assert_eq!(PositionUnit::Bytes.from_byte(line, 8), 8);
+        assert_eq!(PositionUnit::Chars.from_byte(line, 8), 4);
+        assert_eq!(PositionUnit::Columns.from_byte(line, 8), 4);
+        assert_eq!(PositionUnit::Graphemes.from_byte(line, 8), 3);
+    }
+
+    #[test]
+    fn to_bytes_takes_whole_characters() {
+        let line = "a中b";
+        assert_eq!(PositionUnit::Columns.to_bytes(line, 1..3), 1..4);
+        assert_eq!(PositionUnit::Columns.to_bytes(line, 2..3), 1..4);
+        assert_eq!(PositionUnit::Columns.to_bytes(line, 1..2), 1..4);
+        assert_eq!(PositionUnit::Columns.to_bytes(line, 2..4), 1..5);
+        assert_eq!(PositionUnit::Columns.to_bytes(line, 2..2), 4..4);
+        assert_eq!(PositionUnit::Columns.to_bytes(line, 3..9), 4..5);
+    }
+}
@@ -0,0 +1,50 @@
+use clap::ValueEnum;
+
+/// Common rustc errors, pre-filling the summary, the message and the code.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Preset {
+    /// E0502, a mutable borrow while an immutable one is alive
+    Borrowck,
+    /// E0308, mismatched types
+    TypeMismatch,
+    /// E0597, a borrowed value dropped too early
+    Lifetime,
+    /// E0382, a value used after being moved
+    MovedValue,
+    /// E0425, an unknown name
+    UnresolvedName,
+}
+
+impl Preset {
+    pub fn summary(self) -> &'static str {
+        match self {
+            Preset::Borrowck => {
+                "cannot borrow `v` as mutable because it is also borrowed as immutable"
+            }
+            Preset::TypeMismatch => "mismatched types",
+            Preset::Lifetime => "`x` does not live long enough",
+            Preset::MovedValue => "borrow of moved value: `s`",
+            Preset::UnresolvedName => "cannot find value `x` in this scope",
+        }
+    }
+
+    pub fn message(self) -> &'static str {
+        match self {
+            Preset::Borrowck => "mutable borrow occurs here",
+            Preset::TypeMismatch => "expected `i32`, found `&str`",
+            Preset::Lifetime => "borrowed value does not live long enough",
+            Preset::MovedValue => "value borrowed here after move",
+            Preset::UnresolvedName => "not found in this scope",
+        }
+    }
+
+    pub fn errnum(self) -> usize {
+        match self {
+            Preset::Borrowck => 502,
+            Preset::TypeMismatch => 308,
+            Preset::Lifetime => 597,
+            Preset::MovedValue => 382,
+            Preset::UnresolvedName => 425,
+        }
+    }
+}
@@ -0,0 +1,261 @@
+//! Interactive prompts of the command line interface.
+
+use core::fmt;
+use std::{
+    io::{self, Write},
+    process,
+    sync::RwLock,
+};
+
+use crate::{
+    bold, color,
+    position::{self, PositionRange},
+    render::locale::Locale,
+    RoostError, Severity,
+};
+
+/// The language of the prompts and of their errors.
+static LOCALE: RwLock<Locale> = RwLock::new(Locale::En);
+
+pub fn set_locale(locale: Locale) {
+    *LOCALE.write().unwrap_or_else(|err| err.into_inner()) = locale;
+}
+
+pub fn locale() -> Locale {
+    *LOCALE.read().unwrap_or_else(|err| err.into_inner())
+}
+
+/// `text` in the language of the prompts, with `values` filled in.
+pub fn text(text: &str, values: &[&str]) -> String {
+    locale().text(text, values)
+}
+
+pub fn string(string: &str) -> Result<String, RoostError> {
+    Ok(string.to_string())
+}
+
+fn make_prompt(name: String, default: Option<String>) -> String {
+    let mut prompt = text(&name, &[]);
+
+    if let Some(default_value) = default {
+        prompt.push_str(&color(
+            format!(" ({}={})", text("default", &[]), default_value),
+            4,
+        ));
+    }
+
+    bold(format!("{}: ", prompt))
+}
+
+/// Prints `prompt` and reads the answer to the field `name`, without the
+/// line ending.
+pub fn read_answer(name: &str, prompt: &str) -> String {
+    print!("{}", prompt);
+    if let Err(err) = io::stdout().flush() {
+        exit_with(RoostError::OutputError {
+            details: format!("could not print the prompt: {}", err),
+        });
+    }
+
+    let mut result = String::new();
+    match io::stdin().read_line(&mut result) {
+        Ok(0) => {
+            // End of input (Ctrl-D, or Ctrl-Z in cmd and PowerShell)
+            println!();
+            exit_with(RoostError::AbortError {
+                details: text("no value given for '{}'", &[&text(name, &[])]),
+            });
+        }
+        Ok(_) => {}
+        Err(err) => exit_with(RoostError::ValueError {
+            details: text(
+                "could not read '{}': {}",
+                &[&text(name, &[]), &err.to_string()],
+            ),
+        }),
+    }
+
+    result.trim_end().to_string()
+}
+
+/// Prints the error of `answer` not being a valid value of the field `name`.
+fn print_invalid(name: &str, answer: &str, err: RoostError) {
+    eprintln!(
+        "{}",
+        bold(color(
+            format!(
+                "ERR: {}",
+                text(
+                    "'{}' is not a valid {}: {}",
+                    &[answer, &text(name, &[]), &err.to_string()]
+                )
+            ),
+            3
+        ))
+    );
+}
+
+pub fn field<T, F>(name: &str, field_type: &F, default: Option<T>) -> T
+where
+    T: fmt::Display + 'static + Clone,
+    F: Fn(&str) -> Result<T, RoostError>,
+{
+    field_with(name, field_type, default, read_answer)
+}
+
+/// Like `field`, the answers being read by `read` from the field name and
+/// the prompt to print. The default is checked by `field_type` as an answer
+/// would be, and no longer offered if it is not valid.
+pub fn field_with<T, F, R>(name: &str, field_type: &F, mut default: Option<T>, read: R) -> T
+where
+    T: fmt::Display + 'static + Clone,
+    F: Fn(&str) -> Result<T, RoostError>,
+    R: Fn(&str, &str) -> String,
+{
+    loop {
+        let prompt = make_prompt(name.to_owned(), default.as_ref().map(|t| t.to_string()));
+        let result = read(name, &prompt);
+
+        if result.is_empty() {
+            if let Some(value) = default.take() {
+                let shown = value.to_string();
+                match field_type(&shown) {
+                    Ok(_) => return value,
+                    Err(err) => print_invalid(name, &shown, err),
+                }
+                continue;
+            }
+            eprintln!(
+                "{}",
+                bold(color(
+                    format!(
+                        "ERR: {}",
+                        text("field '{}' cannot be empty", &[&text(name, &[])])
+                    ),
+                    1
+                ))
+            );
+        }
+
+        match field_type(&result) {
+            Ok(value) => return value,
+            Err(err) => print_invalid(name, &result, err),
+        }
+    }
+}
+
+/// Like `field`, but an empty answer gives `None` instead of being refused.
+pub fn optional_field<T, F>(name: &str, field_type: &F) -> Option<T>
+where
+    F: Fn(&str) -> Result<T, RoostError>,
+{
+    loop {
+        let result = read_answer(name, &make_prompt(name.to_owned(), None));
+        if result.is_empty() {
+            return None;
+        }
+
+        match field_type(&result) {
+            Ok(value) => return Some(value),
+            Err(err) => print_invalid(name, &result, err),
+        }
+    }
+}
+
+/// Prints the prompt of the field `name` and reads lines until an empty one
+/// or the end of the input, such as a block of text pasted at once.
+pub fn block(name: &str) -> String {
+    println!("{}", make_prompt(name.to_owned(), None));
+
+    let mut block = String::new();
+    loop {
+        let mut line = String::new();
+        match io::stdin().read_line(&mut line) {
+            Ok(0) => break,
+            Ok(_) if line.trim_end_matches(['\r', '\n']).is_empty() => break,
+            Ok(_) => block.push_str(&line),
+            Err(err) => exit_with(RoostError::ValueError {
+                details: text(
+                    "could not read '{}': {}",
+                    &[&text(name, &[]), &err.to_string()],
+                ),
+            }),
+        }
+    }
+
+    block
+}
+
+pub fn int_factory(
+    min_value: usize,
+    max_value: usize,
+) -> impl Fn(&str) -> Result<usize, RoostError> {
+    move |raw_value: &str| {
+        let value = raw_value
+            .parse::<usize>()
+            .map_err(|_| RoostError::ValueError {
+                details: text("invalid value", &[]),
+            })?;
+
+        if value < min_value {
+            return Err(RoostError::ValueError {
+                details: text("value is too smol", &[]),
+            });
+        }
+        if value > max_value {
+            return Err(RoostError::ValueError {
+                details: text("value is too big", &[]),
+            });
+        }
+
+        Ok(value)
+    }
+}
+
+/// Reads a span, as accepted by [`position::parse_range`], of a line whose
+/// positions go from `base` to `base + count`. A span starting at the end of
+/// the line points just past it.
+pub fn range_factory(
+    base: usize,
+    count: usize,
+) -> impl Fn(&str) -> Result<PositionRange, RoostError> {
+    move |raw_value: &str| {
+        let range = position::parse_range(raw_value)?;
+
+        if range.start < base {
+            return Err(RoostError::ValueError {
+                details: text("start is too smol", &[]),
+            });
+        }
+        if range.start == base + count {
+            return Ok(PositionRange(range.start..range.start));
+        }
+        if range.end > base + count {
+            return Err(RoostError::ValueError {
+                details: text("end is too big", &[]),
+            });
+        }
+        if range.is_empty() {
+            return Err(RoostError::ValueError {
+                details: text("span is empty", &[]),
+            });
+        }
+
+        Ok(PositionRange(range))
+    }
+}
+
+/// `message` styled as a rustc error without a code.
+pub fn error_message(message: impl fmt::Display) -> String {
+    format!(
+        "{}{}",
+        bold(color(locale().severity(Severity::Error).to_owned(), 1)),
+        bold(format!(": {}", message))
+    )
+}
+
+/// Prints `err` and exits with its status.
+pub fn exit_with(err: RoostError) -> ! {
+    eprintln!("{}", error_message(&err));
+    process::exit(err.exit_code());
+}
@@ -0,0 +1,136 @@
+use std::ops::Range;
+
+use fastrand::Rng;
+
+use crate::{Diagnostic, DEFAULT_PATH};
+
+#[derive(clap::Args, Debug)]
+pub struct RandomArgs {
+    /// Seed of the generator, to get the same diagnostics again
+    #[arg(long)]
+    pub seed: Option<u64>,
+    /// Number of diagnostics to generate
+    #[arg(long, default_value_t = 1)]
+    pub count: usize,
+}
+
+const PATHS: &[&str] = &[
+    "src/main.rs",
+    "src/lib.rs",
+    "src/parser.rs",
+    "src/ast/expr.rs",
+    "src/codegen/mod.rs",
+    DEFAULT_PATH,
+];
+
+const LINES: &[&str] = &[
+    "let total = items.iter().map(|item| item.price).sum();",
+    "fn parse(input: &str) -> Result<Expr, Error> {",
+    "    let mut buffer = Vec::with_capacity(len);",
+    "    self.cursor += token.len();",
+    "    return Err(ParseError::Unexpected(token));",
+    "let config = Config::load(&path)?;",
+    "    match state { State::Idle => run(queue), _ => {} }",
+    "impl Display for Node {",
+    "    let handle = thread::spawn(move || worker.run());",
+    "    println!(\"{}\", result.unwrap());",
+];
+
+/// Summary with the spanned identifier, label and code of an error.
+const ERRORS: &[(&str, &str, usize)] = &[
+    ("mismatched types", "expected `usize`, found `{}`", 308),
+    (
+        "cannot find value `{}` in this scope",
+        "not found in this scope",
+        425,
+    ),
+    (
+        "borrow of moved value: `{}`",
+        "value borrowed here after move",
+        382,
+    ),
+    (
+        "cannot borrow `{}` as mutable, as it is not declared as mutable",
+        "cannot borrow as mutable",
+        596,
+    ),
+    (
+        "`{}` does not live long enough",
+        "borrowed value does not live long enough",
+        597,
+    ),
+    (
+        "no method named `{}` found in the current scope",
+        "method not found",
+        599,
+    ),
+    (
+        "cannot assign twice to immutable variable `{}`",
+        "cannot assign twice to immutable variable",
+        384,
+    ),
+    ("use of unstable library feature `{}`", "", 658),
+];
+
+const KEYWORDS: &[&str] = &["fn", "let", "mut", "move", "match", "impl", "return", "for"];
+
+/// Byte ranges of the identifiers of `line`, keywords excluded.
+fn identifiers(line: &str) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+    let mut start = None;
+
+    for (index, c) in line.char_indices().chain([(line.len(), ' ')]) {
+        match (start, c.is_alphanumeric() || c == '_') {
+            (None, true) => start = Some(index),
+            (Some(begin), false) => {
+                if !KEYWORDS.contains(&&line[begin..index]) {
+                    ranges.push(begin..index);
+                }
+                start = None;
+            }
+            _ => {}
+        }
+    }
+
+    ranges
+}
+
+fn pick<'a>(rng: &mut Rng, items: &[&'a str]) -> &'a str {
+    items[rng.usize(..items.len())]
+}
+
+/// A diagnostic made of a random line, span and error.
+pub fn diagnostic(rng: &mut Rng) -> Diagnostic<'static> {
+    let line = pick(rng, LINES);
+    let spans = identifiers(line);
+    let span = spans[rng.usize(..spans.len())].clone();
+    let (summary, message, errnum) = ERRORS[rng.usize(..ERRORS.len())];
+    let fill = |text: &str| text.replace("{}", &line[span.clone()]);
+
+    let lineno = rng.usize(1..400);
+    let context_before = (0..rng.usize(..=2.min(lineno - 1)))
+        .map(|_| pick(rng, LINES).to_owned())
+        .collect();
+
+    Diagnostic::builder()
+        .summary(fill(summary))
+        .message(fill(message))
+        .line(line)
+        .span(span)
+        .lineno(lineno)
+        .path(pick(rng, PATHS))
+        .code(errnum)
+        .context_before(context_before)
+        .build()
+        .expect("the generated spans are within their line")
+}
+
+/// `args.count` random diagnostics, from `args.seed` or a random seed.
+pub fn run(args: &RandomArgs) -> Vec<Diagnostic<'static>> {
+    let mut rng = match args.seed {
+        Some(seed) => Rng::with_seed(seed),
+        None => Rng::new(),
+    };
+
+    (0..args.count).map(|_| diagnostic(&mut rng)).collect()
+}
@@ -0,0 +1,310 @@
+//! The rows drawn under a quoted line: the marks under each of its spans,
+//! and the labels hanging from them.
+
+use super::{Charset, Labels};
+use crate::style::Style;
+
+/// A span of the quoted line, as drawn under it.
+pub struct Annotation<'a> {
+    /// Terminal columns before the span
+    pub indent: usize,
+    /// The marks under the span, one per column
+    pub marks: String,
+    pub label: &'a str,
+    /// The style of its marks, legs and label
+    pub style: Style,
+}
+
+impl Annotation<'_> {
+    fn end(&self) -> usize {
+        self.indent + self.marks.chars().count()
+    }
+}
+
+/// The row of marks, the ones of the first annotations drawn over the ones
+/// of the next.
+fn marks_row(annotations: &[Annotation]) -> String {
+    let width = annotations.iter().map(Annotation::end).max().unwrap_or(0);
+    let mut cells: Vec<Option<(char, usize)>> = vec![None; width];
+    for (index, annotation) in annotations.iter().enumerate().rev() {
+        for (offset, mark) in annotation.marks.chars().enumerate() {
+            cells[annotation.indent + offset] = Some((mark, index));
+        }
+    }
+
+    let mut row = String::new();
+    let mut cells = cells.into_iter().peekable();
+    while let Some(cell) = cells.next() {
+        let Some((mark, index)) = cell else {
+            row.push(' ');
+            continue;
+        };
+        let mut run = mark.to_string();
+        while let Some(Some((mark, _))) =
+            cells.next_if(|next| next.is_some_and(|(_, next)| next == index))
+        {
+            run.push(mark);
+        }
+        row.push_str(&annotations[index].style.paint(run));
+    }
+
+    row
+}
+
+/// A row of `leg` under each column of `legs`, in their style, then the
+/// text of `text` from its column.
+fn row(
+    gutter: &str,
+    legs: &[(usize, Style)],
+    leg: char,
+    text: Option<(usize, &str, Style)>,
+) -> String {
+    let mut string = gutter.to_owned();
+    let mut column = 0;
+    for &(start, style) in legs {
+        if start < column {
+            continue;
+        }
+        string.push_str(&" ".repeat(start - column));
+        string.push_str(&style.paint(leg.to_string()));
+        column = start + 1;
+    }
+    if let Some((start, text, style)) = text {
+        string.push_str(&" ".repeat(start.saturating_sub(column)));
+        string.push_str(&style.paint(text.to_owned()));
+    }
+    string.push('\n');
+
+    string
+}
+
+/// Whether two of the labelled annotations overlap or are less than two
+/// columns apart, leaving no room for their legs to stand side by side.
+fn crowded(labelled: &[&Annotation]) -> bool {
+    labelled.iter().enumerate().any(|(index, annotation)| {
+        labelled[index + 1..]
+            .iter()
+            .any(|next| next.indent < annotation.end() + 2 && annotation.indent < next.end() + 2)
+    })
+}
+
+/// The labels of crowded annotations, each routed to its own column of the
+/// span by a connector, as ariadne draws them: the rightmost connector
+/// first, all the labels starting from the same column.
+fn connectors(labelled: &[&Annotation], gutter: &str, charset: Charset) -> String {
+    // The narrowest spans pick their column first, as they have the least
+    // room for it
+    let mut by_width: Vec<&Annotation> = labelled.to_vec();
+    by_width.sort_by_key(|annotation| annotation.end() - annotation.indent);
+    let mut legs: Vec<(usize, &Annotation)> = Vec::new();
+    for annotation in by_width {
+        let column = (annotation.indent..annotation.end())
+            .find(|column| legs.iter().all(|(taken, _)| taken != column))
+            .unwrap_or(annotation.indent);
+        legs.push((column, annotation));
+    }
+    legs.sort_by_key(|&(column, _)| column);
+
+    let label_column = legs.last().map_or(0, |&(column, _)| column + 4);
+    let styles: Vec<(usize, Style)> = legs
+        .iter()
+        .map(|&(column, annotation)| (column, annotation.style))
+        .collect();
+    let [_, _, corner, _] = charset.corners();
+    let vertical = charset.vertical();
+
+    let mut string = row(gutter, &styles, vertical, None);
+    for (index, &(column, annotation)) in legs.iter().enumerate().rev() {
+        let mut lines = annotation.label.lines();
+        let connector = format!(
+            "{}{} {}",
+            corner,
+            charset
+                .horizontal()
+                .to_string()
+                .repeat(label_column - column - 2),
+            lines.next().unwrap_or_default()
+        );
+        string.push_str(&row(
+            gutter,
+            &styles[..index],
+            vertical,
+            Some((column, &connector, annotation.style)),
+        ));
+        for line in lines {
+            string.push_str(&row(
+                gutter,
+                &styles[..index],
+                vertical,
+                Some((label_column, line, annotation.style)),
+            ));
+        }
+    }
+
+    string
+}
+
+/// The rows under the quoted line, each starting with `gutter`: the marks
+/// of the annotations, then their labels. With inline labels, the one of
+/// the rightmost annotation follows its marks when nothing is drawn past
+/// them; the others hang below from `|` legs, the rightmost first, as rustc
+/// prints them. Labelled annotations that overlap or nearly touch are
+/// connected to their labels instead, with the lines of `charset`. Without
+/// any label after them, the marks are only drawn if `marks` is set.
+pub fn draw(
+    annotations: &[Annotation],
+    labels: Labels,
+    gutter: &str,
+    marks: bool,
+    charset: Charset,
+) -> String {
+    let mut order: Vec<&Annotation> = annotations.iter().collect();
+    order.sort_by_key(|annotation| (annotation.indent, annotation.end()));
+    let labelled: Vec<&Annotation> = order
+        .iter()
+        .copied()
+        .filter(|annotation| !annotation.label.is_empty())
+        .collect();
+    if crowded(&labelled) {
+        return format!("{}{}\n", gutter, marks_row(annotations))
+            + connectors(&labelled, gutter, charset).as_str();
+    }
+
+    let inline = order.last().copied().filter(|last| {
+        labels == Labels::Inline
+            && !last.label.is_empty()
+            && order.iter().all(|other| other.end() <= last.end())
+    });
+    let pending: Vec<&Annotation> = labelled
+        .into_iter()
+        .filter(|annotation| !inline.is_some_and(|inline| std::ptr::eq(*annotation, inline)))
+        .collect();
+    let legs: Vec<(usize, Style)> = pending
+        .iter()
+        .map(|annotation| (annotation.indent, annotation.style))
+        .collect();
+
+    let mut string = String::new();
+    if marks || inline.is_some() || annotations.len() > 1 {
+        string.push_str(gutter);
+        string.push_str(&marks_row(annotations));
+        if let Some(inline) = inline {
+            let first = inline.label.lines().next().unwrap_or_default();
+            string.push_str(&format!(" {}", inline.style.paint(first.to_owned())));
+        }
+        string.push('\n');
+    }
+    if let Some(inline) = inline {
+        for line in inline.label.lines().skip(1) {
+            string.push_str(&row(
+                gutter,
+                &legs,
+                '|',
+                Some((inline.end() + 1, line, inline.style)),
+            ));
+        }
+    }
+    if !pending.is_empty() {
+        string.push_str(&row(gutter, &legs, '|', None));
+    }
+    for (index, annotation) in pending.iter().enumerate().rev() {
+        for line in annotation.label.lines() {
+            string.push_str(&row(
+                gutter,
+                &legs[..index],
+                '|',
+                Some((annotation.indent, line, annotation.style)),
+            ));
+        }
+    }
+
+    string
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn annotation<'a>(indent: usize, marks: &str, label: &'a str) -> Annotation<'a> {
+        Annotation {
+            indent,
+            marks: marks.to_owned(),
+            label,
+            style: Style::new(),
+        }
+    }
+
+    #[test]
+    fn draw_hangs_labels_from_legs() {
+        let annotations = [
+            annotation(2, "^^^", "expected `u8`"),
+            annotation(10, "--", "due to this"),
+        ];
+
+        assert_eq!(
+            draw(&annotations, Labels::Inline, "| ", false, Charset::Unicode),
+            "|   ^^^     -- due to this\n\
+             |   |\n\
+             |   expected `u8`\n"
+        );
+        assert_eq!(
+            draw(&annotations, Labels::Below, "| ", false, Charset::Unicode),
+            "|   ^^^     --\n\
+             |   |       |\n\
+             |   |       due to this\n\
+             |   expected `u8`\n"
+        );
+    }
+
+    #[test]
+    fn draw_leaves_out_unlabelled_marks() {
+        let annotations = [annotation(0, "^", "")];
+
+        assert_eq!(
+            draw(&annotations, Labels::Inline, "| ", false, Charset::Unicode),
+            ""
+        );
+        assert_eq!(
+            draw(&annotations, Labels::Inline, "| ", true, Charset::Unicode),
+            "| ^\n"
+        );
+    }
+
+    #[test]
+    fn crowded_needs_two_columns_between_spans() {
+        let (first, touching, apart) = (
+            annotation(0, "^^", "a"),
+            annotation(3, "-", "b"),
+            annotation(4, "-", "c"),
+        );
+
+        assert!(crowded(&[&first, &touching]));
+        assert!(!crowded(&[&first, &apart]));
+        assert!(crowded(&[&first, &annotation(1, "--", "overlapping")]));
+    }
+
+    #[test]
+    fn draw_connects_crowded_labels() {
+        let annotations = [
+            annotation(2, "^^^^", "whole call"),
+            annotation(3, "-", "argument\nmoved here"),
+        ];
+
+        assert_eq!(
+            draw(&annotations, Labels::Inline, "| ", false, Charset::Unicode),
+            "|   ^^^^\n\
+             |   ││\n\
+             |   │╰── argument\n\
+             |   │    moved here\n\
+             |   ╰─── whole call\n"
+        );
+        assert_eq!(
+            draw(&annotations, Labels::Inline, "| ", false, Charset::Ascii),
+            "|   ^^^^\n\
+             |   ||\n\
+             |   |+-- argument\n\
+             |   |    moved here\n\
+             |   +--- whole call\n"
+        );
+    }
+}
@@ -0,0 +1,88 @@
+use ::ariadne::{CharSet, Color, Config, IndexType, Label, Report, ReportKind, Source};
+
+use super::{Charset, LABEL_COLORS};
+use crate::{ansi, Diagnostic, Severity};
+
+/// Renders the diagnostic as an `ariadne` report.
+pub fn render(err: &Diagnostic, charset: Charset) -> String {
+    let file = err.file();
+    // ariadne numbers the lines from 1 in the gutter whatever the offset of
+    // the source, so the contents are preceded by as many empty lines
+    let padding = "\n".repeat(file.first_lineno.saturating_sub(1));
+    let contents = padding.clone() + &*file.contents;
+    let range = &err.span.range;
+    let span = (
+        &*file.path,
+        padding.len() + range.start..padding.len() + range.end,
+    );
+
+    let (kind, color) = match err.severity {
+        Severity::Error => (ReportKind::Error, Color::Red),
+        Severity::Warning => (ReportKind::Warning, Color::Yellow),
+    };
+    let mut label = Label::new(span.clone()).with_color(color);
+    if !err.message.is_empty() {
+        label = label.with_message(&err.message);
+    }
+    let mut report = Report::build(kind, span)
+        .with_code(err.get_errid())
+        .with_message(&err.summary)
+        .with_label(label)
+        .with_labels(err.labels.iter().enumerate().map(|(index, label)| {
+            let range = err.label_range(label);
+            let color = match label
+                .color
+                .unwrap_or(LABEL_COLORS[index % LABEL_COLORS.len()])
+            {
+                ansi::Color::Basic(index) | ansi::Color::Fixed(index) => Color::Fixed(index),
+                ansi::Color::Bright(index) => Color::Fixed(index + 8),
+                ansi::Color::Rgb([r, g, b]) => Color::Rgb(r, g, b),
+            };
+            let secondary = Label::new((
+                &*file.path,
+                padding.len() + range.start..padding.len() + range.end,
+            ))
+            .with_color(color);
+            match label.message.is_empty() {
+                true => secondary,
+                false => secondary.with_message(&label.message),
+            }
+        }))
+        .with_config(
+            Config::default()
+                .with_color(ansi::enabled())
+                .with_char_set(match charset {
+                    Charset::Unicode => CharSet::Unicode,
+                    Charset::Ascii => CharSet::Ascii,
+                })
+                .with_index_type(IndexType::Byte),
+        );
+    for note in &err.notes {
+        report = report.with_note(note);
+    }
+    // The related notes are left unquoted, as ariadne would need each file
+    // they point into at once
+    for note in &err.related {
+        report = report.with_note(format!(
+            "{} ({}:{}:{})",
+            note.summary,
+            note.path(),
+            note.lineno(),
+            note.column()
+        ));
+    }
+    for help in &err.helps {
+        report = report.with_help(help);
+    }
+    if let Some(fixit) = &err.fixit {
+        report = report.with_help(format!("replace with `{}`", fixit));
+    }
+
+    let mut rendered = Vec::new();
+    report
+        .finish()
+        .write((&*file.path, Source::from(contents)), &mut rendered)
+        .expect("writing to a buffer cannot fail");
+
+    String::from_utf8_lossy(&rendered).into_owned()
+}
@@ -0,0 +1,182 @@
+use super::{
+    annotations::{self, Annotation},
+    fixit_diff,
+    highlight::Highlighter,
+    label_annotations, lineno_width, linked,
+    locale::Words,
+    located_footers, primary_marks,
+    theme::{Marks, Theme},
+    Charset, Labels, Underline,
+};
+use unicode_width::UnicodeWidthStr;
+
+use crate::{
+    ansi::{self, Color},
+    style::Style,
+    Diagnostic, Severity,
+};
+
+const GUTTER_WIDTH: usize = 5;
+
+pub const THEME: Theme = Theme {
+    header: Style::new().fg(Color::Basic(1)).bold(),
+    warning: Some(Style::new().fg(Color::Basic(5)).bold()),
+    gutter: Style::new(),
+    primary: Style::new().fg(Color::Basic(2)).bold(),
+    secondary: Style::new().fg(Color::Basic(2)),
+    note: Style::new().fg(Color::Basic(0)).bold(),
+    marks: Marks::NATIVE,
+};
+
+#[allow(clippy::too_many_arguments)]
+pub fn render(
+    err: &Diagnostic,
+    theme: &Theme,
+    underline: Option<Underline>,
+    highlighter: &Highlighter,
+    warning_flag: Option<&str>,
+    link: Option<&str>,
+    notes: bool,
+    words: Words,
+) -> String {
+    let location = format!(
+        "{}: ",
+        linked(
+            link,
+            format!("{}:{}:{}", err.path(), err.lineno(), err.column())
+        )
+    );
+
+    let bold = Style::new().bold();
+    let mut string = String::new();
+    bold.paint_into(&mut string, &location);
+    theme
+        .header
+        .paint_into(&mut string, &format!("{}:", words.severity(err.severity)));
+    bold.paint_into(&mut string, &format!(" {}", err.summary));
+    if let Some(flag) = warning_flag {
+        let flag = match err.severity {
+            Severity::Error => format!(" [-Werror,-W{}]", flag),
+            Severity::Warning => format!(" [-W{}]", flag),
+        };
+        bold.paint_into(&mut string, &flag);
+    }
+    string.push('\n');
+    let gutter_width = GUTTER_WIDTH.max(lineno_width(err));
+    string.push_str(&snippet(err, theme, underline, highlighter, gutter_width));
+
+    if notes && !err.message.is_empty() {
+        let indent = " ".repeat(ansi::strip(&location).width() + words.note().width() + 2);
+        bold.paint_into(&mut string, &location);
+        theme
+            .note
+            .paint_into(&mut string, &format!("{}:", words.note()));
+        string.push_str(&format!(
+            " {}\n",
+            err.message
+                .lines()
+                .collect::<Vec<_>>()
+                .join(&format!("\n{}", indent))
+        ));
+    }
+
+    for label in err
+        .labels
+        .iter()
+        .filter(|label| notes && !label.message.is_empty())
+    {
+        let location = format!(
+            "{}:{}:{}: ",
+            err.path(),
+            err.lineno(),
+            err.column_at(label.range.start)
+        );
+        bold.paint_into(&mut string, &location);
+        theme
+            .note
+            .paint_into(&mut string, &format!("{}:", words.note()));
+        string.push_str(&format!(" {}\n", label.message));
+    }
+    string.push_str(&located_footers(err, theme, words));
+
+    let note_theme = Theme {
+        primary: theme.note,
+        ..*theme
+    };
+    for note in &err.related {
+        let location = format!("{}:{}:{}: ", note.path(), note.lineno(), note.column());
+        bold.paint_into(&mut string, &location);
+        theme
+            .note
+            .paint_into(&mut string, &format!("{}:", words.note()));
+        string.push_str(&format!(" {}\n", note.summary));
+        string.push_str(&snippet(
+            note,
+            &note_theme,
+            underline,
+            highlighter,
+            gutter_width,
+        ));
+    }
+
+    string
+}
+
+/// The quoted line of `err`, the marks under its span and the fix-it hint.
+fn snippet(
+    err: &Diagnostic,
+    theme: &Theme,
+    underline: Option<Underline>,
+    highlighter: &Highlighter,
+    gutter_width: usize,
+) -> String {
+    let empty_gutter = theme
+        .gutter
+        .paint(format!("{} | ", " ".repeat(gutter_width)));
+    let padding = " ".repeat(err.span_indent());
+    let span = match underline {
+        Some(Underline::Undercurl) => theme.primary.undercurl(),
+        _ => Style::new(),
+    };
+
+    let mut string = String::new();
+    let gutter = format!("{:>width$} | ", err.lineno(), width = gutter_width);
+    theme.gutter.paint_into(&mut string, &gutter);
+    string.push_str(&highlighter.paint(err.line(), err.spos()..err.epos(), span));
+    string.push('\n');
+    let mut annotations = vec![Annotation {
+        indent: err.span_indent(),
+        marks: primary_marks(underline, theme, err.span_width(), |width| {
+            format!("^{}", "~".repeat(width - 1))
+        }),
+        label: "",
+        style: theme.primary,
+    }];
+    // Clang only marks the other ranges, their labels are notes
+    annotations.extend(
+        label_annotations(err, theme, '~')
+            .into_iter()
+            .map(|annotation| Annotation {
+                label: "",
+                ..annotation
+            }),
+    );
+    string.push_str(&annotations::draw(
+        &annotations,
+        Labels::Inline,
+        &empty_gutter,
+        underline != Some(Underline::Undercurl),
+        Charset::default(),
+    ));
+
+    if let Some(diff) = fixit_diff(err, theme, gutter_width) {
+        string.push_str(&diff);
+    } else if let Some(fixit) = &err.fixit {
+        string.push_str(&empty_gutter);
+        string.push_str(&padding);
+        theme.secondary.paint_into(&mut string, fixit);
+        string.push('\n');
+    }
+
+    string
+}
@@ -0,0 +1,126 @@
+use super::{
+    annotations::{self, Annotation},
+    fixit_diff,
+    highlight::Highlighter,
+    label_annotations, lineno_width, linked,
+    locale::Words,
+    located_footers, primary_marks, span_style,
+    theme::{Marks, Theme},
+    Charset, Labels, Underline,
+};
+use crate::{ansi::Color, bold, style::Style, Diagnostic};
+
+pub const GUTTER_WIDTH: usize = 5;
+
+pub const THEME: Theme = Theme {
+    header: Style::new().fg(Color::Basic(1)).bold(),
+    warning: Some(Style::new().fg(Color::Basic(5)).bold()),
+    gutter: Style::new(),
+    primary: Style::new().fg(Color::Basic(1)).bold(),
+    secondary: Style::new().fg(Color::Basic(2)),
+    note: Style::new().fg(Color::Basic(6)).bold(),
+    marks: Marks::NATIVE,
+};
+
+#[allow(clippy::too_many_arguments)]
+pub fn render(
+    err: &Diagnostic,
+    theme: &Theme,
+    underline: Option<Underline>,
+    labels: Labels,
+    charset: Charset,
+    highlighter: &Highlighter,
+    link: Option<&str>,
+    words: Words,
+) -> String {
+    let location = |err: &Diagnostic, link: Option<&str>| {
+        let location = format!("{}:{}:{}", err.path(), err.lineno(), err.column());
+        bold(format!("{}: ", linked(link, location)))
+    };
+
+    let mut string = location(err, link);
+    theme
+        .header
+        .paint_into(&mut string, &format!("{}:", words.severity(err.severity)));
+    string.push_str(&format!(" {}\n", err.summary));
+    let gutter_width = GUTTER_WIDTH.max(lineno_width(err));
+    string.push_str(&snippet(
+        err,
+        theme,
+        underline,
+        labels,
+        charset,
+        highlighter,
+        gutter_width,
+    ));
+    if let Some(diff) = fixit_diff(err, theme, gutter_width) {
+        string.push_str(&diff);
+    }
+    string.push_str(&located_footers(err, theme, words));
+
+    let note_theme = Theme {
+        primary: theme.note,
+        ..*theme
+    };
+    for note in &err.related {
+        string.push_str(&location(note, None));
+        theme
+            .note
+            .paint_into(&mut string, &format!("{}:", words.note()));
+        string.push_str(&format!(" {}\n", note.summary));
+        string.push_str(&snippet(
+            note,
+            &note_theme,
+            underline,
+            labels,
+            charset,
+            highlighter,
+            gutter_width,
+        ));
+    }
+
+    string
+}
+
+/// The quoted line of `err` and the marks under its span.
+fn snippet(
+    err: &Diagnostic,
+    theme: &Theme,
+    underline: Option<Underline>,
+    labels: Labels,
+    charset: Charset,
+    highlighter: &Highlighter,
+    gutter_width: usize,
+) -> String {
+    let empty_gutter = theme
+        .gutter
+        .paint(format!("{} | ", " ".repeat(gutter_width)));
+
+    let mut string = String::new();
+    let gutter = format!("{:>width$} | ", err.lineno(), width = gutter_width);
+    theme.gutter.paint_into(&mut string, &gutter);
+    string.push_str(&highlighter.paint(
+        err.line(),
+        err.spos()..err.epos(),
+        span_style(theme.primary, underline),
+    ));
+    string.push('\n');
+    let mut annotations = vec![Annotation {
+        indent: err.span_indent(),
+        marks: primary_marks(underline, theme, err.span_width(), |width| {
+            format!("^{}", "~".repeat(width - 1))
+        }),
+        label: &err.message,
+        style: theme.primary,
+    }];
+    annotations.extend(label_annotations(err, theme, '~'));
+    string.push_str(&annotations::draw(
+        &annotations,
+        labels,
+        &empty_gutter,
+        underline != Some(Underline::Undercurl),
+        charset,
+    ));
+
+    string
+}
@@ -0,0 +1,121 @@
+use std::ops::Range;
+
+use super::RenderOptions;
+use crate::style::Style;
+
+#[cfg(feature = "syntax-highlighting")]
+use std::{path::Path, sync::OnceLock};
+
+#[cfg(feature = "syntax-highlighting")]
+use syntect::{
+    easy::HighlightLines,
+    highlighting::{FontStyle, Theme, ThemeSet},
+    parsing::{SyntaxReference, SyntaxSet},
+};
+
+#[cfg(feature = "syntax-highlighting")]
+use crate::ansi::{self, Color};
+
+#[cfg(feature = "syntax-highlighting")]
+const THEME: &str = "base16-ocean.dark";
+
+#[cfg(feature = "syntax-highlighting")]
+fn syntaxes() -> &'static SyntaxSet {
+    static SYNTAXES: OnceLock<SyntaxSet> = OnceLock::new();
+    SYNTAXES.get_or_init(SyntaxSet::load_defaults_nonewlines)
+}
+
+#[cfg(feature = "syntax-highlighting")]
+fn theme() -> &'static Theme {
+    static THEMES: OnceLock<Theme> = OnceLock::new();
+    THEMES.get_or_init(|| {
+        ThemeSet::load_defaults()
+            .themes
+            .remove(THEME)
+            .expect("syntect bundles the base16-ocean.dark theme")
+    })
+}
+
+/// Paints the source lines quoted in a diagnostic, with syntax highlighting
+/// when roost is built with the `syntax-highlighting` feature.
+pub struct Highlighter {
+    #[cfg(feature = "syntax-highlighting")]
+    syntax: Option<&'static SyntaxReference>,
+}
+
+impl Highlighter {
+    /// Highlights the language given by `--lang`, or else guessed from the
+    /// extension of `path`, defaulting to Rust.
+    #[cfg(feature = "syntax-highlighting")]
+    pub fn new(options: &RenderOptions, path: &str) -> Self {
+        let syntaxes = syntaxes();
+        let syntax = match &options.lang {
+            Some(lang) => syntaxes.find_syntax_by_token(lang),
+            None => Path::new(path)
+                .extension()
+                .and_then(|extension| syntaxes.find_syntax_by_extension(extension.to_str()?))
+                .or_else(|| syntaxes.find_syntax_by_extension("rs")),
+        };
+
+        Highlighter { syntax }
+    }
+
+    #[cfg(not(feature = "syntax-highlighting"))]
+    pub fn new(_options: &RenderOptions, _path: &str) -> Self {
+        Highlighter {}
+    }
+
+    /// Splits `line` into pieces of the same style.
+    #[cfg(feature = "syntax-highlighting")]
+    fn pieces<'a>(&self, line: &'a str) -> Vec<(Style, &'a str)> {
+        let highlighted = self.syntax.filter(|_| ansi::enabled()).and_then(|syntax| {
+            HighlightLines::new(syntax, theme())
+                .highlight_line(line, syntaxes())
+                .ok()
+        });
+
+        match highlighted {
+            Some(pieces) => pieces
+                .into_iter()
+                .map(|(style, text)| {
+                    let color = style.foreground;
+                    let painted = Style::new().fg(Color::Rgb([color.r, color.g, color.b]));
+                    match style.font_style.contains(FontStyle::BOLD) {
+                        true => (painted.bold(), text),
+                        false => (painted, text),
+                    }
+                })
+                .collect(),
+            None => vec![(Style::new(), line)],
+        }
+    }
+
+    #[cfg(not(feature = "syntax-highlighting"))]
+    fn pieces<'a>(&self, line: &'a str) -> Vec<(Style, &'a str)> {
+        vec![(Style::new(), line)]
+    }
+
+    /// Paints `line`, overlaying `span_style` on the bytes in `span`.
+    pub fn paint(&self, line: &str, span: Range<usize>, span_style: Style) -> String {
+        let mut before = String::new();
+        let mut after = String::new();
+        let mut offset = 0;
+
+        for (style, text) in self.pieces(line) {
+            let range = offset..offset + text.len();
+            offset = range.end;
+
+            let clip = |from: usize, to: usize| {
+                let (start, end) = (range.start.max(from), range.end.min(to));
+                match start < end {
+                    true => style.paint(line[start..end].to_owned()),
+                    false => String::new(),
+                }
+            };
+            before.push_str(&clip(0, span.start));
+            after.push_str(&clip(span.end, line.len()));
+        }
+
+        before + span_style.paint(line[span].to_owned()).as_str() + after.as_str()
+    }
+}
@@ -0,0 +1,388 @@
+use terminal_size::{terminal_size, Width};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+use super::Charset;
+use crate::{ansi, export::png, source::SourceMap, style::Style, Diagnostic, Label};
+
+const ELLIPSIS: &str = "...";
+/// Columns of a letter of a banner.
+const BANNER_LETTER_WIDTH: usize = 8;
+/// Narrowest window kept around the span, whatever the width asked for.
+const MIN_WINDOW: usize = 8;
+
+pub fn terminal_width() -> Option<usize> {
+    terminal_size().map(|(Width(width), _)| width as usize)
+}
+
+/// The byte offset of the first char of `line` starting at or after the
+/// terminal column `column`.
+fn byte_offset(line: &str, column: usize) -> usize {
+    let mut width = 0;
+    for (offset, c) in line.char_indices() {
+        if width >= column {
+            return offset;
+        }
+        width += c.width().unwrap_or(0);
+    }
+    line.len()
+}
+
+/// Cuts `line` to the chars in the columns `start..end`, with an ellipsis on
+/// each side where something was cut.
+fn cut(line: &str, start: usize, end: usize) -> String {
+    let (start, end) = (byte_offset(line, start), byte_offset(line, end));
+    let mut cut = String::new();
+
+    if start > 0 && start < line.len() {
+        cut.push_str(ELLIPSIS);
+    }
+    cut.push_str(&line[start..end]);
+    if end < line.len() {
+        cut.push_str(ELLIPSIS);
+    }
+
+    cut
+}
+
+/// Keeps the part of the source lines around the span that fits in `width`
+/// columns, as rustc does for long lines.
+pub fn fit<'a>(err: &Diagnostic<'a>, width: usize) -> Diagnostic<'a> {
+    let source_line = err.line();
+    let total = source_line.width();
+    if total <= width {
+        return err.clone();
+    }
+
+    let window = width.saturating_sub(2 * ELLIPSIS.len()).max(MIN_WINDOW);
+    let start = err
+        .span_indent()
+        .saturating_sub(window.saturating_sub(err.span_width()) / 2)
+        .min(total.saturating_sub(window));
+    let end = (start + window).min(total);
+
+    let line = cut(source_line, start, end);
+    let (start_byte, end_byte) = (
+        byte_offset(source_line, start),
+        byte_offset(source_line, end),
+    );
+    let shift = match start_byte > 0 {
+        true => ELLIPSIS.len(),
+        false => 0,
+    };
+    let moved = |pos: usize| shift + pos.clamp(start_byte, end_byte) - start_byte;
+    let spos = moved(err.spos());
+    let epos = moved(err.epos());
+    let epos = match epos > spos {
+        true => epos,
+        false => spos + line[spos..].chars().next().map_or(0, char::len_utf8),
+    };
+    let cut_context = |lines: Vec<&str>| -> Vec<String> {
+        lines
+            .into_iter()
+            .map(|line| cut(line, start, end))
+            .collect()
+    };
+    let (sources, span) = SourceMap::quote(
+        err.path().to_owned(),
+        err.lineno(),
+        &line,
+        spos..epos,
+        &cut_context(err.context_before()),
+        &cut_context(err.context_after()),
+    );
+
+    // The labels out of the window are left out
+    let labels = err
+        .labels
+        .iter()
+        .map(|label| Label {
+            range: moved(label.range.start)..moved(label.range.end),
+            ..label.clone()
+        })
+        .filter(|label| !label.range.is_empty())
+        .collect();
+
+    Diagnostic {
+        span,
+        labels,
+        sources,
+        column_offset: err.column() as isize - 1 - line[..spos].chars().count() as isize,
+        ..err.clone()
+    }
+}
+
+/// Breaks the lines of a label between words so that they fit in `width`
+/// columns, keeping the words longer than that whole.
+pub fn wrap_label(label: &str, width: usize) -> String {
+    let width = width.max(MIN_WINDOW);
+    let mut wrapped = Vec::new();
+
+    for line in label.lines() {
+        let mut row = String::new();
+        for word in line.split(' ') {
+            if !row.is_empty() && row.width() + 1 + word.width() > width {
+                wrapped.push(std::mem::take(&mut row));
+            } else if !row.is_empty() {
+                row.push(' ');
+            }
+            row.push_str(word);
+        }
+        wrapped.push(row);
+    }
+
+    wrapped.join("\n")
+}
+
+/// Replaces the tabs of the quoted lines with `tab_width` spaces, as rustc
+/// does, moving the span accordingly. `None` if there are no tabs.
+pub fn expand_tabs<'a>(err: &Diagnostic<'a>, tab_width: usize) -> Option<Diagnostic<'a>> {
+    let lines = [err.context_before(), vec![err.line()], err.context_after()].concat();
+    if !lines.iter().any(|line| line.contains('\t')) {
+        return None;
+    }
+
+    let tab = " ".repeat(tab_width);
+    let expand = |lines: Vec<&str>| -> Vec<String> {
+        lines
+            .into_iter()
+            .map(|line| line.replace('\t', &tab))
+            .collect()
+    };
+    let source_line = err.line();
+    let shifted = |pos: usize| {
+        let tabs = source_line[..pos].matches('\t').count();
+        pos - tabs + tabs * tab_width
+    };
+    let (spos, epos) = (shifted(err.spos()), shifted(err.epos()));
+    let labels = err
+        .labels
+        .iter()
+        .map(|label| Label {
+            range: shifted(label.range.start)..shifted(label.range.end),
+            ..label.clone()
+        })
+        .collect();
+
+    let line = source_line.replace('\t', &tab);
+    let (sources, span) = SourceMap::quote(
+        err.path().to_owned(),
+        err.lineno(),
+        &line,
+        spos..epos,
+        &expand(err.context_before()),
+        &expand(err.context_after()),
+    );
+
+    Some(Diagnostic {
+        span,
+        labels,
+        sources,
+        column_offset: err.column() as isize - 1 - line[..spos].chars().count() as isize,
+        ..err.clone()
+    })
+}
+
+/// Draws a rounded box around `text`, with `title` in its top border.
+pub fn frame(text: &str, title: &str, charset: Charset, style: Style) -> String {
+    let [top_left, top_right, bottom_left, bottom_right] = charset.corners();
+    let (horizontal, vertical) = (charset.horizontal(), charset.vertical());
+    let lines: Vec<(&str, usize)> = text
+        .lines()
+        .map(|line| (line, ansi::strip(line).trim_end_matches('\n').width()))
+        .collect();
+    let inner = lines
+        .iter()
+        .map(|(_, width)| *width)
+        .max()
+        .unwrap_or(0)
+        .max(title.width() + 3);
+    let rule = |width: usize| horizontal.to_string().repeat(width);
+
+    let mut framed = style.paint(format!(
+        "{}{}[{}]{}{}\n",
+        top_left,
+        horizontal,
+        title,
+        rule(inner - title.width() - 1),
+        top_right,
+    ));
+    for (line, width) in lines {
+        framed.push_str(&format!(
+            "{} {}{} {}\n",
+            style.paint(vertical.to_string()),
+            line,
+            " ".repeat(inner - width),
+            style.paint(vertical.to_string()),
+        ));
+    }
+    framed.push_str(&style.paint(format!(
+        "{}{}{}\n",
+        bottom_left,
+        rule(inner + 2),
+        bottom_right
+    )));
+
+    framed
+}
+
+/// The words of `text`, cut into lines of at most `letters` letters, the
+/// words longer than that across several.
+fn banner_lines(text: &str, letters: usize) -> Vec<Vec<char>> {
+    let mut lines: Vec<Vec<char>> = vec![Vec::new()];
+    for word in text.split_whitespace() {
+        let word: Vec<char> = word.chars().collect();
+        let line = lines.last_mut().expect("there is always a line");
+        if !line.is_empty() && line.len() + 1 + word.len() <= letters {
+            line.push(' ');
+            line.extend(word);
+            continue;
+        }
+        for chunk in word.chunks(letters) {
+            match lines.last_mut().expect("there is always a line") {
+                line if line.is_empty() => line.extend(chunk),
+                _ => lines.push(chunk.to_vec()),
+            }
+        }
+    }
+
+    lines
+}
+
+/// `text` in letters eight cells wide, drawn in `style` with blocks of the
+/// font of the PNG export, two rows of its pixels to a line, or with `#` on
+/// eight lines with the ASCII charset. The words are wrapped to fit in
+/// `max_width`.
+pub fn banner(text: &str, charset: Charset, max_width: Option<usize>, style: Style) -> String {
+    let letters = max_width.map_or(usize::MAX, |width| (width / BANNER_LETTER_WIDTH).max(1));
+    let pixel = |glyph: &[u8; 8], x: usize, y: usize| glyph[y] & (1 << x) != 0;
+
+    let mut banner = String::new();
+    for line in banner_lines(text, letters) {
+        let glyphs: Vec<[u8; 8]> = line.iter().map(|&c| png::glyph(c)).collect();
+        let rows: Vec<String> = match charset {
+            Charset::Unicode => (0..8)
+                .step_by(2)
+                .map(|y| {
+                    glyphs
+                        .iter()
+                        .flat_map(|glyph| {
+                            (0..BANNER_LETTER_WIDTH).map(move |x| {
+                                match (pixel(glyph, x, y), pixel(glyph, x, y + 1)) {
+                                    (true, true) => '█',
+                                    (true, false) => '▀',
+                                    (false, true) => '▄',
+                                    (false, false) => ' ',
+                                }
+                            })
+                        })
+                        .collect()
+                })
+                .collect(),
+            Charset::Ascii => (0..8)
+                .map(|y| {
+                    glyphs
+                        .iter()
+                        .flat_map(|glyph| {
+                            (0..BANNER_LETTER_WIDTH)
+                                .map(move |x| if pixel(glyph, x, y) { '#' } else { ' ' })
+                        })
+                        .collect()
+                })
+                .collect(),
+        };
+        for row in rows {
+            banner.push_str(&style.paint(row.trim_end().to_owned()));
+            banner.push('\n');
+        }
+    }
+
+    banner
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn diagnostic(line: &str, span: std::ops::Range<usize>) -> Diagnostic<'static> {
+        Diagnostic::builder()
+            .summary("unexpected token")
+            .line(line)
+            .span(span)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn fit_keeps_short_lines() {
+        let err = diagnostic("let x = 1;", 8..9);
+
+        assert_eq!(fit(&err, 40).line(), "let x = 1;");
+    }
+
+    #[test]
+    fn fit_cuts_around_the_span() {
+        let line = format!("let x = {}b;", "a + ".repeat(30));
+        let spos = line.len() - 2;
+        let err = diagnostic(&line, spos..spos + 1);
+        let fitted = fit(&err, 40);
+
+        assert!(fitted.line().starts_with(ELLIPSIS), "{}", fitted.line());
+        assert!(fitted.line().width() <= 40, "{}", fitted.line());
+        assert_eq!(&fitted.line()[fitted.spos()..fitted.epos()], "b");
+        assert_eq!(fitted.column(), err.column());
+    }
+
+    #[test]
+    fn fit_keeps_the_span_of_a_line_cut_on_both_sides() {
+        let line = format!("{}needle{}", "x".repeat(50), "y".repeat(50));
+        let err = diagnostic(&line, 50..56);
+        let fitted = fit(&err, 30);
+
+        assert!(fitted.line().starts_with(ELLIPSIS), "{}", fitted.line());
+        assert!(fitted.line().ends_with(ELLIPSIS), "{}", fitted.line());
+        assert_eq!(&fitted.line()[fitted.spos()..fitted.epos()], "needle");
+    }
+
+    #[test]
+    fn expand_tabs_moves_the_span_and_the_labels() {
+        let err = Diagnostic::builder()
+            .summary("unexpected token")
+            .line("\tlet x =\t1;")
+            .span(9..10)
+            .label(Label {
+                range: 1..4,
+                message: "in this binding".to_owned(),
+                color: None,
+            })
+            .build()
+            .unwrap();
+        let expanded = expand_tabs(&err, 4).unwrap();
+
+        assert_eq!(expanded.line(), "    let x =    1;");
+        assert_eq!(&expanded.line()[expanded.spos()..expanded.epos()], "1");
+        assert_eq!(expanded.labels[0].range, 4..7);
+        assert_eq!(expanded.column(), 10);
+    }
+
+    #[test]
+    fn expand_tabs_leaves_lines_without_tabs() {
+        assert!(expand_tabs(&diagnostic("let x = 1;", 8..9), 4).is_none());
+    }
+
+    #[test]
+    fn wrap_label_breaks_between_words() {
+        assert_eq!(wrap_label("one two three four", 9), "one two\nthree\nfour");
+        assert_eq!(wrap_label("short", 40), "short");
+    }
+
+    #[test]
+    fn wrap_label_keeps_long_words_and_lines() {
+        assert_eq!(
+            wrap_label("incomprehensibly so", 10),
+            "incomprehensibly\nso"
+        );
+        assert_eq!(wrap_label("first\nsecond line", 40), "first\nsecond line");
+        // Never narrower than the narrowest window
+        assert_eq!(wrap_label("a b c d e", 1), "a b c d\ne");
+    }
+}
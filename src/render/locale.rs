@@ -0,0 +1,397 @@
+//! The words the styles frame the diagnostics with, and the texts of the
+//! prompts, in the languages of `--locale`. The text of the diagnostics is
+//! left as given.
+
+use clap::ValueEnum;
+
+use crate::Severity;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum Locale {
+    /// English, as the compilers print them
+    #[default]
+    En,
+    /// French
+    Fr,
+    /// Spanish
+    Es,
+    /// German
+    De,
+    /// Japanese
+    Ja,
+}
+
+/// The words of a locale that head the diagnostics and their notes, each
+/// after an emoji with `--emoji`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Words {
+    pub locale: Locale,
+    pub emoji: bool,
+}
+
+impl Words {
+    fn decorated(self, emoji: &str, word: &str) -> String {
+        match self.emoji {
+            true => format!("{} {}", emoji, word),
+            false => word.to_owned(),
+        }
+    }
+
+    /// The header of a diagnostic of `severity`, such as `🛑 error`.
+    pub fn severity(self, severity: Severity) -> String {
+        let emoji = match severity {
+            Severity::Error => "🛑",
+            Severity::Warning => "⚠️",
+        };
+        self.decorated(emoji, self.locale.severity(severity))
+    }
+
+    pub fn note(self) -> String {
+        self.decorated("📝", self.locale.note())
+    }
+
+    pub fn help(self) -> String {
+        self.decorated("💡", self.locale.help())
+    }
+}
+
+/// The texts of the prompts, in English then in French, Spanish, German and
+/// Japanese, each `{}` standing for a value filled in, in the same order.
+const PROMPT_TEXTS: &[[&str; 5]] = &[
+    ["summary", "résumé", "resumen", "Zusammenfassung", "概要"],
+    ["line", "ligne", "línea", "Zeile", "行"],
+    [
+        "error span",
+        "portée de l'erreur",
+        "tramo del error",
+        "Fehlerbereich",
+        "エラーの範囲",
+    ],
+    ["message", "message", "mensaje", "Meldung", "メッセージ"],
+    [
+        "message (continued)",
+        "message (suite)",
+        "mensaje (continuación)",
+        "Meldung (Fortsetzung)",
+        "メッセージ (続き)",
+    ],
+    [
+        "line number",
+        "numéro de ligne",
+        "número de línea",
+        "Zeilennummer",
+        "行番号",
+    ],
+    ["path", "chemin", "ruta", "Pfad", "パス"],
+    [
+        "error number",
+        "numéro d'erreur",
+        "número de error",
+        "Fehlernummer",
+        "エラー番号",
+    ],
+    [
+        "note (empty to finish)",
+        "note (vide pour terminer)",
+        "nota (vacía para terminar)",
+        "Hinweis (leer zum Beenden)",
+        "注記 (空で終了)",
+    ],
+    [
+        "help (empty to finish)",
+        "aide (vide pour terminer)",
+        "ayuda (vacía para terminar)",
+        "Hilfe (leer zum Beenden)",
+        "ヘルプ (空で終了)",
+    ],
+    [
+        "paste a rustc error, then an empty line",
+        "collez une erreur de rustc, puis une ligne vide",
+        "pegue un error de rustc y luego una línea vacía",
+        "einen rustc-Fehler einfügen, dann eine leere Zeile",
+        "rustc のエラーを貼り付け、空行で終了",
+    ],
+    [
+        "no rustc error found in the pasted text",
+        "aucune erreur de rustc dans le texte collé",
+        "no se encontró ningún error de rustc en el texto pegado",
+        "kein rustc-Fehler im eingefügten Text gefunden",
+        "貼り付けたテキストに rustc のエラーがありません",
+    ],
+    [
+        "looks good? [enter] accept, [e]dit field, [t]heme, [f]ormat",
+        "ça vous va ? [entrée] accepter, [e] modifier un champ, [t] thème, [f] format",
+        "¿le parece bien? [intro] aceptar, [e] editar un campo, [t] tema, [f] formato",
+        "passt das? [Eingabe] übernehmen, [e] Feld bearbeiten, [t] Thema, [f] Format",
+        "これでよいですか? [Enter] 確定、[e] 項目を編集、[t] テーマ、[f] 形式",
+    ],
+    ["choice", "choix", "opción", "Auswahl", "選択"],
+    [
+        "'{}' is not one of the choices",
+        "'{}' ne fait pas partie des choix",
+        "'{}' no es una de las opciones",
+        "'{}' gehört nicht zur Auswahl",
+        "'{}' は選択肢にありません",
+    ],
+    [
+        "field to edit (empty to cancel)",
+        "champ à modifier (vide pour annuler)",
+        "campo a editar (vacío para cancelar)",
+        "zu bearbeitendes Feld (leer zum Abbrechen)",
+        "編集する項目 (空で取り消し)",
+    ],
+    ["severity", "gravité", "gravedad", "Schweregrad", "重大度"],
+    ["notes", "notes", "notas", "Hinweise", "ノート"],
+    ["helps", "aides", "ayudas", "Hilfen", "ヘルプ"],
+    [
+        "theme (empty to cancel)",
+        "thème (vide pour annuler)",
+        "tema (vacío para cancelar)",
+        "Thema (leer zum Abbrechen)",
+        "テーマ (空で取り消し)",
+    ],
+    [
+        "format (empty to cancel)",
+        "format (vide pour annuler)",
+        "formato (vacío para cancelar)",
+        "Format (leer zum Abbrechen)",
+        "形式 (空で取り消し)",
+    ],
+    [
+        "expected one of {}",
+        "attendu parmi {}",
+        "se esperaba uno de {}",
+        "erwartet wird eines von {}",
+        "{} のいずれかを指定してください",
+    ],
+    ["file", "fichier", "archivo", "Datei", "ファイル"],
+    [
+        "changed line",
+        "ligne modifiée",
+        "línea modificada",
+        "geänderte Zeile",
+        "変更された行",
+    ],
+    ["default", "défaut", "predeterminado", "Standard", "既定"],
+    [
+        "field '{}' cannot be empty",
+        "le champ '{}' ne peut pas être vide",
+        "el campo '{}' no puede estar vacío",
+        "das Feld '{}' darf nicht leer sein",
+        "'{}' は空にできません",
+    ],
+    [
+        "'{}' is not a valid {}: {}",
+        "'{}' n'est pas valide comme {} : {}",
+        "'{}' no es válido como {}: {}",
+        "'{}' ist als {} ungültig: {}",
+        "'{}' は有効な{}ではありません: {}",
+    ],
+    [
+        "no value given for '{}'",
+        "aucune valeur donnée pour '{}'",
+        "no se dio ningún valor para '{}'",
+        "kein Wert für '{}' angegeben",
+        "'{}' の値が入力されていません",
+    ],
+    [
+        "could not read '{}': {}",
+        "impossible de lire '{}' : {}",
+        "no se pudo leer '{}': {}",
+        "'{}' konnte nicht gelesen werden: {}",
+        "'{}' を読み込めませんでした: {}",
+    ],
+    [
+        "invalid value",
+        "valeur invalide",
+        "valor no válido",
+        "ungültiger Wert",
+        "無効な値です",
+    ],
+    [
+        "value is too smol",
+        "la valeur est trop ptite",
+        "el valor es muy pequeño",
+        "der Wert ist zu klein",
+        "値が小さすぎます",
+    ],
+    [
+        "value is too big",
+        "la valeur est trop grande",
+        "el valor es muy grande",
+        "der Wert ist zu groß",
+        "値が大きすぎます",
+    ],
+    [
+        "start is too smol",
+        "le début est trop ptit",
+        "el inicio es muy pequeño",
+        "der Anfang ist zu klein",
+        "開始位置が小さすぎます",
+    ],
+    [
+        "end is too big",
+        "la fin est trop grande",
+        "el final es muy grande",
+        "das Ende ist zu groß",
+        "終了位置が大きすぎます",
+    ],
+    [
+        "span is empty",
+        "la portée est vide",
+        "el tramo está vacío",
+        "der Bereich ist leer",
+        "範囲が空です",
+    ],
+    [
+        "{} is empty",
+        "{} est vide",
+        "{} está vacío",
+        "{} ist leer",
+        "{} は空です",
+    ],
+    [
+        "{} has only {} lines",
+        "{} n'a que {} lignes",
+        "{} solo tiene {} líneas",
+        "{} hat nur {} Zeilen",
+        "{} には {} 行しかありません",
+    ],
+    [
+        "must be at most {} characters long",
+        "doit faire au plus {} caractères",
+        "debe tener como máximo {} caracteres",
+        "darf höchstens {} Zeichen lang sein",
+        "{} 文字以内でなければなりません",
+    ],
+    [
+        "must match '{}'",
+        "doit correspondre à '{}'",
+        "debe coincidir con '{}'",
+        "muss auf '{}' passen",
+        "'{}' に一致しなければなりません",
+    ],
+    [
+        "must be a number",
+        "doit être un nombre",
+        "debe ser un número",
+        "muss eine Zahl sein",
+        "数値でなければなりません",
+    ],
+    [
+        "must be at least {}",
+        "doit valoir au moins {}",
+        "debe ser al menos {}",
+        "muss mindestens {} sein",
+        "{} 以上でなければなりません",
+    ],
+    [
+        "must be at most {}",
+        "doit valoir au plus {}",
+        "debe ser como máximo {}",
+        "darf höchstens {} sein",
+        "{} 以下でなければなりません",
+    ],
+];
+
+/// `count` and the form of the noun for it, `one` or `many`.
+fn counted(count: usize, one: &str, many: &str) -> String {
+    match count {
+        1 => format!("1 {}", one),
+        _ => format!("{} {}", count, many),
+    }
+}
+
+impl Locale {
+    /// `text`, one of the prompts in English, in the language of the locale
+    /// with `values` filled in its `{}`. Texts without a translation are
+    /// kept in English.
+    pub fn text(self, text: &str, values: &[&str]) -> String {
+        let translated = PROMPT_TEXTS
+            .iter()
+            .find(|texts| texts[0] == text)
+            .map_or(text, |texts| texts[self as usize]);
+        let mut parts = translated.split("{}");
+        let mut filled = parts.next().unwrap_or_default().to_owned();
+        for (part, value) in parts.zip(values.iter().chain(std::iter::repeat(&""))) {
+            filled.push_str(value);
+            filled.push_str(part);
+        }
+
+        filled
+    }
+
+    /// The header of a diagnostic of `severity`, such as `error`.
+    pub fn severity(self, severity: Severity) -> &'static str {
+        match (self, severity) {
+            (Locale::En, Severity::Error) => "error",
+            (Locale::En, Severity::Warning) => "warning",
+            (Locale::Fr, Severity::Error) => "erreur",
+            (Locale::Fr, Severity::Warning) => "avertissement",
+            (Locale::Es, Severity::Error) => "error",
+            (Locale::Es, Severity::Warning) => "advertencia",
+            (Locale::De, Severity::Error) => "Fehler",
+            (Locale::De, Severity::Warning) => "Warnung",
+            (Locale::Ja, Severity::Error) => "エラー",
+            (Locale::Ja, Severity::Warning) => "警告",
+        }
+    }
+
+    /// The header of a related note.
+    pub fn note(self) -> &'static str {
+        match self {
+            Locale::En => "note",
+            Locale::Fr => "note",
+            Locale::Es => "nota",
+            Locale::De => "Hinweis",
+            Locale::Ja => "注記",
+        }
+    }
+
+    /// The header of a fix-it suggestion.
+    pub fn help(self) -> &'static str {
+        match self {
+            Locale::En => "help",
+            Locale::Fr => "aide",
+            Locale::Es => "ayuda",
+            Locale::De => "Hilfe",
+            Locale::Ja => "ヘルプ",
+        }
+    }
+
+    /// What rustc ends a report of `errors` errors with, such as `aborting
+    /// due to 2 previous errors`.
+    pub fn aborting(self, errors: usize) -> String {
+        match self {
+            Locale::En => format!(
+                "aborting due to {}",
+                counted(errors, "previous error", "previous errors")
+            ),
+            Locale::Fr => format!(
+                "abandon en raison de {}",
+                counted(errors, "erreur précédente", "erreurs précédentes")
+            ),
+            Locale::Es => format!(
+                "abortando debido a {}",
+                counted(errors, "error previo", "errores previos")
+            ),
+            Locale::De => format!(
+                "Abbruch aufgrund von {}",
+                counted(errors, "vorherigem Fehler", "vorherigen Fehlern")
+            ),
+            Locale::Ja => format!("{} 個の以前のエラーのため中止します", errors),
+        }
+    }
+
+    /// What rustc counts the warnings of a report with, such as `1 warning
+    /// emitted`.
+    pub fn emitted(self, warnings: usize) -> String {
+        match self {
+            Locale::En => format!("{} emitted", counted(warnings, "warning", "warnings")),
+            Locale::Fr => counted(warnings, "avertissement émis", "avertissements émis"),
+            Locale::Es => counted(warnings, "advertencia emitida", "advertencias emitidas"),
+            Locale::De => format!("{} ausgegeben", counted(warnings, "Warnung", "Warnungen")),
+            Locale::Ja => format!("{} 個の警告が出力されました", warnings),
+        }
+    }
+}
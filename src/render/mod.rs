@@ -0,0 +1,841 @@
+use std::{
+    collections::BTreeSet,
+    path::{self, Path},
+};
+
+use clap::ValueEnum;
+use unicode_width::UnicodeWidthStr;
+
+use crate::{
+    ansi::{self, Color},
+    bold,
+    style::Style,
+    Diagnostic, Label, Severity, DEFAULT_PATH,
+};
+use annotations::Annotation;
+use highlight::Highlighter;
+use locale::{Locale, Words};
+use theme::Theme;
+
+pub mod annotations;
+pub mod ariadne;
+pub mod clang;
+pub mod gcc;
+pub mod highlight;
+pub mod layout;
+pub mod locale;
+pub mod python;
+pub mod rustc;
+pub mod snippets;
+pub mod theme;
+pub mod tsc;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum Engine {
+    /// roost's own layout, imitating `--style`
+    #[default]
+    Roost,
+    /// The `annotate-snippets` crate, as used by rustc
+    AnnotateSnippets,
+    /// The `ariadne` crate
+    Ariadne,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum Emitter {
+    /// rustc diagnostics
+    #[default]
+    Rustc,
+    /// GCC diagnostics
+    Gcc,
+    /// clang diagnostics
+    Clang,
+    /// CPython tracebacks
+    Python,
+    /// TypeScript compiler diagnostics
+    Tsc,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum Hyperlinks {
+    /// Only when printing text to a terminal that supports them
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum LinkScheme {
+    /// `file://<path>`
+    #[default]
+    File,
+    /// `vscode://file/<path>:<line>:<col>`
+    Vscode,
+    /// `idea://open?file=<path>&line=<line>&column=<col>`
+    Idea,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Underline {
+    /// `^^^^`
+    Caret,
+    /// `~~~~`
+    Tilde,
+    /// `----`
+    Dash,
+    /// Curly underline of the span itself, on terminals that support it
+    Undercurl,
+}
+
+impl Underline {
+    fn mark(self) -> char {
+        match self {
+            Underline::Caret => '^',
+            Underline::Tilde => '~',
+            Underline::Dash => '-',
+            Underline::Undercurl => ' ',
+        }
+    }
+}
+
+/// The row of marks under a primary span of `width` columns: the ones of
+/// `--underline`, else of the theme, else the style's `native` ones. A chosen
+/// mark under a single column becomes a lone `^`, as rustc does.
+pub fn primary_marks(
+    underline: Option<Underline>,
+    theme: &Theme,
+    width: usize,
+    native: fn(usize) -> String,
+) -> String {
+    let mark = match (underline, theme.marks.primary) {
+        (Some(Underline::Undercurl), _) => return " ".repeat(width),
+        (Some(underline), _) => underline.mark(),
+        (None, Some(mark)) => mark,
+        (None, None) => return native(width),
+    };
+
+    match width {
+        1 => "^".to_owned(),
+        _ => mark.to_string().repeat(width),
+    }
+}
+
+/// Colors given to the labels after the first, which gets the secondary
+/// style of the theme.
+const LABEL_COLORS: [Color; 5] = [
+    Color::Basic(6),
+    Color::Basic(5),
+    Color::Basic(2),
+    Color::Basic(3),
+    Color::Basic(4),
+];
+
+/// The labels of `err` under `mark`, or the secondary mark of the theme.
+/// Each is painted in its own color, or else in one telling it apart from
+/// the others.
+pub fn label_annotations<'a>(
+    err: &'a Diagnostic,
+    theme: &Theme,
+    mark: char,
+) -> Vec<Annotation<'a>> {
+    let line = err.line();
+    let mark = theme.marks.secondary.unwrap_or(mark);
+    let mut palette = LABEL_COLORS
+        .into_iter()
+        .filter(|&color| Some(color) != theme.secondary.fg)
+        .cycle();
+
+    err.labels
+        .iter()
+        .enumerate()
+        .map(|(index, label)| {
+            let style = match (label.color, index) {
+                (Some(color), _) => theme.secondary.fg(color),
+                (None, 0) => theme.secondary,
+                (None, _) => theme
+                    .secondary
+                    .fg(palette.next().unwrap_or(Color::Basic(4))),
+            };
+            Annotation {
+                indent: line[..label.range.start].width(),
+                marks: mark
+                    .to_string()
+                    .repeat(line[label.range.clone()].width().max(1)),
+                label: &label.message,
+                style,
+            }
+        })
+        .collect()
+}
+
+/// The style of the span in the source line.
+pub fn span_style(style: Style, underline: Option<Underline>) -> Style {
+    match underline {
+        Some(Underline::Undercurl) => style.undercurl(),
+        _ => style,
+    }
+}
+
+/// The line of `err` before and after its fix-it, as the `-` line in red
+/// and the `+` line in green of a diff, the line number right-aligned in
+/// `width` columns. `None` unless the fix-it is to be shown as a diff.
+pub fn fixit_diff(err: &Diagnostic, theme: &Theme, width: usize) -> Option<String> {
+    let fixit = err.fixit.as_ref().filter(|_| err.fixit_diff)?;
+    let line = err.line();
+    let fixed = format!("{}{}{}", &line[..err.spos()], fixit, &line[err.epos()..]);
+    let row = |sign: char, color: u8, line: &str| {
+        format!(
+            "{}{}\n",
+            theme
+                .gutter
+                .paint(format!("{:>width$} ", err.lineno(), width = width)),
+            Style::new()
+                .fg(Color::Basic(color))
+                .paint(format!("{} {}", sign, line))
+        )
+    };
+
+    Some(row('-', 1, line) + row('+', 2, &fixed).as_str())
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Banner {
+    /// The code of the diagnostic, as `E0308`
+    Code,
+    /// Its summary
+    Summary,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Labels {
+    /// After the marks, on the same row
+    Inline,
+    /// On its own row, connected to the marks by a `|`
+    Below,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum Charset {
+    /// Box-drawing characters
+    #[default]
+    Unicode,
+    /// `-`, `|` and `>`, for terminals and paste targets that mangle Unicode
+    Ascii,
+}
+
+impl Charset {
+    pub fn horizontal(self) -> char {
+        match self {
+            Charset::Unicode => '─',
+            Charset::Ascii => '-',
+        }
+    }
+
+    pub fn vertical(self) -> char {
+        match self {
+            Charset::Unicode => '│',
+            Charset::Ascii => '|',
+        }
+    }
+
+    /// Top left, top right, bottom left and bottom right corners of a box.
+    pub fn corners(self) -> [char; 4] {
+        match self {
+            Charset::Unicode => ['╭', '╮', '╰', '╯'],
+            Charset::Ascii => ['+', '+', '+', '+'],
+        }
+    }
+}
+
+#[derive(clap::Args, Clone, Debug)]
+pub struct RenderOptions {
+    #[arg(long, global = true, value_enum, default_value_t = Emitter::Rustc)]
+    pub style: Emitter,
+    /// What lays out the diagnostic
+    #[arg(long, global = true, value_enum, default_value_t = Engine::Roost)]
+    pub engine: Engine,
+    /// Warning promoted to an error, shown as `[-Werror,-W<FLAG>]` (clang style)
+    #[arg(long, global = true, value_name = "FLAG")]
+    pub warning_flag: Option<String>,
+    /// Exception raised at the end of the traceback (python style)
+    #[arg(
+        long,
+        global = true,
+        value_name = "NAME",
+        default_value = "SyntaxError"
+    )]
+    pub exception: String,
+    /// Make the location a clickable OSC 8 hyperlink to the file
+    #[arg(long, global = true, value_enum, default_value_t = Hyperlinks::Auto)]
+    pub hyperlinks: Hyperlinks,
+    /// What the location hyperlink opens
+    #[arg(long, global = true, value_enum, default_value_t = LinkScheme::File)]
+    pub link_scheme: LinkScheme,
+    /// Built-in theme (rustc, gcc, clang, python, tsc, dracula, solarized,
+    /// deuteranopia, protanopia, tritanopia, high-contrast) or TOML theme
+    /// file, defaulting to the style's own colors
+    #[arg(long, global = true, value_name = "THEME", value_parser = theme::parse)]
+    pub theme: Option<Theme>,
+    /// How the span is underlined, defaulting to the style's own marks
+    #[arg(long, global = true, value_enum)]
+    pub underline: Option<Underline>,
+    /// Where the message goes relative to the marks (rustc and gcc styles),
+    /// as the style does by default
+    #[arg(long, global = true, value_enum)]
+    pub labels: Option<Labels>,
+    /// Characters used to draw rules and boxes
+    #[arg(long, global = true, value_enum, default_value_t = Charset::Unicode)]
+    pub charset: Charset,
+    /// Language of the words framing the diagnostics, such as `error` and
+    /// `note` (rustc, gcc, clang and tsc styles), the given text left as is
+    #[arg(long, global = true, value_enum)]
+    pub locale: Option<Locale>,
+    /// Put an emoji before the headers of the diagnostics and of their notes
+    /// (rustc, gcc, clang and tsc styles)
+    #[arg(long, global = true)]
+    pub emoji: bool,
+    /// Draw a rounded box around the diagnostic, with the error ID in its top
+    /// border
+    #[arg(long, global = true)]
+    pub boxed: bool,
+    /// Print the code, or the summary, in big letters above the diagnostic
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        value_name = "TEXT",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "code"
+    )]
+    pub banner: Option<Banner>,
+    /// Group the diagnostics by file, naming each file once (rustc style),
+    /// and count them at the end
+    #[arg(long, global = true)]
+    pub group_by_file: bool,
+    /// Width the diagnostic fits in, defaulting to the one of the terminal
+    #[arg(long, global = true, value_name = "N", value_parser = clap::value_parser!(u16).range(16..))]
+    pub max_width: Option<u16>,
+    /// Number of spaces replacing each tab of the quoted code
+    #[arg(long, global = true, value_name = "N", default_value_t = 4)]
+    pub tab_width: usize,
+    /// Leave out the notes, the explain hints and the ruler of the prompts
+    #[arg(short, long, global = true, conflicts_with = "verbose")]
+    pub quiet: bool,
+    /// Add the explain hints of every error, and the count of errors at the end
+    #[arg(short, long, global = true)]
+    pub verbose: bool,
+    /// Codes with an explanation, advertised under the diagnostics
+    #[arg(skip)]
+    pub explained: BTreeSet<String>,
+    /// Language of the quoted code, guessed from the path by default
+    #[cfg(feature = "syntax-highlighting")]
+    #[arg(long, global = true, value_name = "LANG")]
+    pub lang: Option<String>,
+}
+
+impl Default for RenderOptions {
+    /// The options of a command line without any flag.
+    fn default() -> Self {
+        #[derive(clap::Parser)]
+        #[command(no_binary_name = true)]
+        struct Defaults {
+            #[command(flatten)]
+            render: RenderOptions,
+        }
+
+        <Defaults as clap::Parser>::parse_from([] as [&str; 0]).render
+    }
+}
+
+impl RenderOptions {
+    /// The words heading the diagnostics, in the language of `--locale`.
+    pub fn words(&self) -> Words {
+        Words {
+            locale: self.locale.unwrap_or_default(),
+            emoji: self.emoji,
+        }
+    }
+
+    /// Settles `--hyperlinks auto`, `--underline undercurl` and the default
+    /// `--max-width` once it is known whether the diagnostic is printed to a
+    /// terminal.
+    pub fn resolve(&mut self, to_terminal: bool) {
+        if self.hyperlinks == Hyperlinks::Auto {
+            self.hyperlinks = match to_terminal && ansi::supports_hyperlinks() {
+                true => Hyperlinks::Always,
+                false => Hyperlinks::Never,
+            };
+        }
+        if self.max_width.is_none() && to_terminal {
+            self.max_width = layout::terminal_width().and_then(|width| u16::try_from(width).ok());
+        }
+        if self.underline == Some(Underline::Undercurl)
+            && to_terminal
+            && !ansi::supports_undercurl()
+        {
+            self.underline = Some(Underline::Caret);
+        }
+    }
+}
+
+/// The URL the location of `err` links to, if it points at an actual file.
+fn link(options: &RenderOptions, err: &Diagnostic) -> Option<String> {
+    if options.hyperlinks != Hyperlinks::Always || err.path() == DEFAULT_PATH {
+        return None;
+    }
+
+    let path = path::absolute(Path::new(err.path())).ok()?;
+    let path = path
+        .to_string_lossy()
+        .replace('%', "%25")
+        .replace(' ', "%20");
+    let (line, column) = (err.lineno(), err.column());
+
+    Some(match options.link_scheme {
+        LinkScheme::File => format!("file://{}", path),
+        LinkScheme::Vscode => format!("vscode://file{}:{}:{}", path, line, column),
+        LinkScheme::Idea => format!("idea://open?file={}&line={}&column={}", path, line, column),
+    })
+}
+
+/// Makes `text` a hyperlink to `link`, when there is one.
+pub fn linked(link: Option<&str>, text: String) -> String {
+    match link {
+        Some(url) => ansi::hyperlink(url, &text),
+        None => text,
+    }
+}
+
+/// The notes then the helps of `err` as GCC and Clang print remarks: each
+/// after the location of `err` and the word heading it, its lines after
+/// the first aligned with its text.
+pub fn located_footers(err: &Diagnostic, theme: &Theme, words: Words) -> String {
+    let location = format!("{}:{}:{}: ", err.path(), err.lineno(), err.column());
+    let notes = err.notes.iter().map(|note| (words.note(), note));
+    let helps = err.helps.iter().map(|help| (words.help(), help));
+
+    let mut string = String::new();
+    for (word, text) in notes.chain(helps) {
+        let indent = format!("\n{}", " ".repeat(location.width() + word.width() + 2));
+        string.push_str(&bold(location.clone()));
+        string.push_str(&theme.note.paint(format!("{}:", word)));
+        string.push_str(&format!(
+            " {}\n",
+            text.lines().collect::<Vec<_>>().join(&indent)
+        ));
+    }
+
+    string
+}
+
+/// The number of digits of the largest line number quoted by `err` and its
+/// notes, which all share one gutter.
+pub fn lineno_width(err: &Diagnostic) -> usize {
+    std::iter::once(err)
+        .chain(&err.related)
+        .map(|quoted| {
+            (quoted.lineno() + quoted.context_after().len())
+                .to_string()
+                .len()
+        })
+        .max()
+        .unwrap_or(1)
+}
+
+/// Columns left of the quoted code by each style, for line numbers of
+/// `lineno_width` digits.
+fn margin(style: Emitter, lineno_width: usize) -> usize {
+    match style {
+        Emitter::Rustc => lineno_width + 3,
+        Emitter::Gcc | Emitter::Clang => gcc::GUTTER_WIDTH.max(lineno_width) + 3,
+        Emitter::Python => 4,
+        Emitter::Tsc => lineno_width + 1,
+    }
+}
+
+/// Column of the terminal where the label starts in each style, the
+/// continuation lines of the label going under it.
+fn label_column(
+    options: &RenderOptions,
+    err: &Diagnostic,
+    labels: Labels,
+    lineno_width: usize,
+) -> usize {
+    let after_marks = match labels {
+        Labels::Inline => err.span_width() + 1,
+        Labels::Below => 0,
+    };
+
+    match options.style {
+        Emitter::Rustc | Emitter::Gcc => {
+            margin(options.style, lineno_width) + err.span_indent() + after_marks
+        }
+        Emitter::Clang => format!(
+            "{}:{}:{}: {}: ",
+            err.path(),
+            err.lineno(),
+            err.column(),
+            options.words().note()
+        )
+        .width(),
+        Emitter::Python => options.exception.width() + 2,
+        Emitter::Tsc => 2,
+    }
+}
+
+/// Lays out a related note as the diagnostic itself: tabs expanded, then cut
+/// and its label wrapped to fit in `max_width`.
+fn lay_out_note<'a>(
+    options: &RenderOptions,
+    note: &Diagnostic<'a>,
+    labels: Labels,
+    max_width: Option<usize>,
+    lineno_width: usize,
+) -> Diagnostic<'a> {
+    let expanded = layout::expand_tabs(note, options.tab_width);
+    let note = expanded.as_ref().unwrap_or(note);
+    let Some(width) = max_width else {
+        return note.clone();
+    };
+
+    let note = layout::fit(
+        note,
+        width.saturating_sub(margin(options.style, lineno_width)),
+    );
+    Diagnostic {
+        message: layout::wrap_label(
+            &note.message,
+            width.saturating_sub(label_column(options, &note, labels, lineno_width)),
+        )
+        .into(),
+        ..note
+    }
+}
+
+/// The theme given with `--theme`, or else the one of the style.
+fn theme(options: &RenderOptions) -> Theme {
+    options.theme.unwrap_or(match options.style {
+        Emitter::Rustc => rustc::THEME,
+        Emitter::Gcc => gcc::THEME,
+        Emitter::Clang => clang::THEME,
+        Emitter::Python => python::THEME,
+        Emitter::Tsc => tsc::THEME,
+    })
+}
+
+pub fn render(options: &RenderOptions, err: &Diagnostic) -> String {
+    render_located(options, err, true)
+}
+
+/// Renders a diagnostic following another one of the same file in a report
+/// grouped by file, its location leaving out the path of the file (rustc
+/// style). The other styles name the file on every line anyway.
+pub fn render_grouped(options: &RenderOptions, err: &Diagnostic) -> String {
+    render_located(options, err, false)
+}
+
+fn render_located(options: &RenderOptions, err: &Diagnostic, location: bool) -> String {
+    let expanded = layout::expand_tabs(err, options.tab_width);
+    let err = expanded.as_ref().unwrap_or(err);
+    let max_width = options
+        .max_width
+        .map(|width| usize::from(width) - if options.boxed { 4 } else { 0 });
+    // The other engines do not indent the lines of a label, so they get it
+    // as a single one
+    let joined = || Diagnostic {
+        message: err.message.lines().collect::<Vec<_>>().join(" ").into(),
+        ..err.clone()
+    };
+    match options.engine {
+        Engine::Roost => {}
+        Engine::AnnotateSnippets => {
+            return finish(
+                options,
+                err,
+                snippets::render(&joined(), max_width),
+                max_width,
+            )
+        }
+        Engine::Ariadne => {
+            return finish(
+                options,
+                err,
+                ariadne::render(&joined(), options.charset),
+                max_width,
+            )
+        }
+    }
+
+    let lineno_width = lineno_width(err);
+    let fitted = max_width.map(|width| {
+        layout::fit(
+            err,
+            width.saturating_sub(margin(options.style, lineno_width)),
+        )
+    });
+    let err = fitted.as_ref().unwrap_or(err);
+    let labels = options.labels.unwrap_or(match options.style {
+        Emitter::Gcc => Labels::Below,
+        _ => Labels::Inline,
+    });
+    let wrapped = max_width.map(|width| Diagnostic {
+        message: layout::wrap_label(
+            &err.message,
+            width.saturating_sub(label_column(options, err, labels, lineno_width)),
+        )
+        .into(),
+        // The other labels hang below their own marks
+        labels: err
+            .labels
+            .iter()
+            .map(|label| Label {
+                message: layout::wrap_label(
+                    &label.message,
+                    width.saturating_sub(
+                        margin(options.style, lineno_width)
+                            + err.line()[..label.range.start].width(),
+                    ),
+                ),
+                ..label.clone()
+            })
+            .collect(),
+        ..err.clone()
+    });
+    let err = wrapped.as_ref().unwrap_or(err);
+    let notes = (!err.related.is_empty()).then(|| Diagnostic {
+        related: err
+            .related
+            .iter()
+            .map(|note| lay_out_note(options, note, labels, max_width, lineno_width))
+            .collect(),
+        ..err.clone()
+    });
+    let err = notes.as_ref().unwrap_or(err);
+    let link = link(options, err);
+    let link = link.as_deref();
+    let highlighter = Highlighter::new(options, err.path());
+
+    let theme = theme(options).for_severity(err.severity);
+    let words = options.words();
+
+    let rendered = match options.style {
+        Emitter::Rustc => rustc::render(
+            err,
+            &theme,
+            options.underline,
+            labels,
+            options.charset,
+            &highlighter,
+            link,
+            location,
+            explain_command(options, err),
+            words,
+        ),
+        Emitter::Gcc => gcc::render(
+            err,
+            &theme,
+            options.underline,
+            labels,
+            options.charset,
+            &highlighter,
+            link,
+            words,
+        ),
+        Emitter::Clang => clang::render(
+            err,
+            &theme,
+            options.underline,
+            &highlighter,
+            options.warning_flag.as_deref(),
+            link,
+            !options.quiet,
+            words,
+        ),
+        Emitter::Python => python::render(
+            err,
+            &theme,
+            options.underline,
+            &options.exception,
+            &highlighter,
+            link,
+        ),
+        Emitter::Tsc => tsc::render(err, &theme, options.underline, &highlighter, link, words),
+    };
+
+    finish(options, err, rendered, max_width)
+}
+
+/// The command advertised under the diagnostic: `roost explain` when its
+/// code has an explanation, and rustc's own with `--verbose`.
+fn explain_command(options: &RenderOptions, err: &Diagnostic) -> Option<&'static str> {
+    match options.quiet {
+        true => None,
+        false if options.explained.contains(&err.get_errid()) => Some("roost explain"),
+        false => options.verbose.then_some("rustc --explain"),
+    }
+}
+
+/// The lines rustc ends a failed build with, pointing at `rustc --explain`
+/// for the codes of the errors of `errs`. `None` without errors.
+pub fn explain_hint(errs: &[Diagnostic]) -> Option<String> {
+    let codes: BTreeSet<String> = errs
+        .iter()
+        .filter(|err| err.severity.is_error())
+        .map(Diagnostic::get_errid)
+        .collect();
+    let first = codes.first()?;
+
+    Some(match codes.len() {
+        1 => bold(format!(
+            "For more information about this error, try `rustc --explain {}`.\n",
+            first
+        )),
+        _ => {
+            bold(format!(
+                "Some errors have detailed explanations: {}.\n",
+                codes.iter().cloned().collect::<Vec<_>>().join(", ")
+            )) + bold(format!(
+                "For more information about an error, try `rustc --explain {}`.\n",
+                first
+            ))
+            .as_str()
+        }
+    })
+}
+
+/// `count` and `noun`, in the plural unless there is one.
+pub fn counted(count: usize, noun: &str) -> String {
+    match count {
+        1 => format!("1 {}", noun),
+        _ => format!("{} {}s", count, noun),
+    }
+}
+
+/// The lines ending the output of the compiler, counting the errors and
+/// warnings of `errs`. They are printed after several diagnostics, or after
+/// one with `--verbose` or `--group-by-file`. `package` is the name of the
+/// crate, for cargo's `could not compile` line.
+pub fn trailer(
+    options: &RenderOptions,
+    errs: &[Diagnostic],
+    package: Option<&str>,
+) -> Option<String> {
+    let batch = errs.len() > 1 || options.verbose || options.group_by_file;
+    if !batch || options.quiet || errs.is_empty() {
+        return None;
+    }
+    let errors = errs.iter().filter(|err| err.severity.is_error()).count();
+    let warnings = errs.len() - errors;
+    let theme = theme(options);
+    let words = options.words();
+    let locale = words.locale;
+    // cargo's own lines are left as cargo prints them, having no locale
+    let line = |severity: Severity, words: Words, text: String| {
+        format!(
+            "{}{}\n",
+            theme
+                .for_severity(severity)
+                .header
+                .paint(words.severity(severity)),
+            bold(format!(": {}", text))
+        )
+    };
+
+    match options.style {
+        Emitter::Rustc => {
+            let mut string = String::new();
+            if errors == 0 {
+                string.push_str(&line(Severity::Warning, words, locale.emitted(warnings)));
+                if let Some(package) = package {
+                    string.push('\n');
+                    string.push_str(&line(
+                        Severity::Warning,
+                        Words::default(),
+                        format!("`{}` generated {}", package, counted(warnings, "warning")),
+                    ));
+                }
+                return Some(string);
+            }
+            let also = |locale: Locale| match warnings {
+                0 => String::new(),
+                _ => format!("; {}", locale.emitted(warnings)),
+            };
+            string.push_str(&line(
+                Severity::Error,
+                words,
+                format!("{}{}", locale.aborting(errors), also(locale)),
+            ));
+            if let Some(package) = package {
+                string.push('\n');
+                string.push_str(&line(
+                    Severity::Error,
+                    Words::default(),
+                    format!(
+                        "could not compile `{}` due to {}{}",
+                        package,
+                        counted(errors, "previous error"),
+                        also(Locale::En)
+                    ),
+                ));
+            }
+            Some(string)
+        }
+        Emitter::Clang => {
+            let counts = [(warnings, "warning"), (errors, "error")]
+                .into_iter()
+                .filter(|(count, _)| *count > 0)
+                .map(|(count, noun)| counted(count, noun))
+                .collect::<Vec<_>>()
+                .join(" and ");
+            Some(format!("{} generated.\n", counts))
+        }
+        Emitter::Tsc if errors > 0 => Some(format!("Found {}.\n", counted(errors, "error"))),
+        Emitter::Tsc | Emitter::Gcc | Emitter::Python => None,
+    }
+}
+
+/// Renders the diagnostic in the rustc style as plain text within `width`
+/// columns (at least 16), the same whatever the terminal, the environment and
+/// the color settings, for snapshot tests.
+pub fn render_plain(err: &Diagnostic, width: usize) -> String {
+    let options = RenderOptions {
+        hyperlinks: Hyperlinks::Never,
+        max_width: Some(u16::try_from(width.max(16)).unwrap_or(u16::MAX)),
+        ..Default::default()
+    };
+
+    ansi::strip(&render(&options, err))
+}
+
+/// Wraps and frames the rendered diagnostic, whatever laid it out.
+fn finish(
+    options: &RenderOptions,
+    err: &Diagnostic,
+    rendered: String,
+    max_width: Option<usize>,
+) -> String {
+    let rendered = match max_width {
+        Some(width) => ansi::wrap(&rendered, width),
+        None => rendered,
+    };
+
+    let rendered = match options.boxed {
+        true => layout::frame(
+            &rendered,
+            &err.get_errid(),
+            options.charset,
+            theme(options).gutter,
+        ),
+        false => rendered,
+    };
+
+    let text = match options.banner {
+        Some(Banner::Code) => err.get_errid(),
+        Some(Banner::Summary) => err.summary.to_string(),
+        None => return rendered,
+    };
+    let style = theme(options).for_severity(err.severity).header;
+    layout::banner(&text, options.charset, max_width, style) + "\n" + rendered.as_str()
+}
@@ -0,0 +1,102 @@
+use super::{
+    highlight::Highlighter,
+    linked, primary_marks,
+    theme::{Marks, Theme},
+    Underline,
+};
+use unicode_width::UnicodeWidthStr;
+
+use crate::{ansi::Color, style::Style, Diagnostic};
+
+pub const THEME: Theme = Theme {
+    header: Style::new().fg(Color::Basic(5)).bold(),
+    warning: None,
+    gutter: Style::new().fg(Color::Basic(5)),
+    primary: Style::new().fg(Color::Basic(1)).bold(),
+    secondary: Style::new().fg(Color::Basic(1)),
+    note: Style::new().fg(Color::Basic(5)),
+    marks: Marks::NATIVE,
+};
+
+pub fn render(
+    err: &Diagnostic,
+    theme: &Theme,
+    underline: Option<Underline>,
+    exception: &str,
+    highlighter: &Highlighter,
+    link: Option<&str>,
+) -> String {
+    let indent = err.line().len() - err.line().trim_start().len();
+    let code = err.line().trim();
+    let code_end = indent + code.len();
+    let spos = err.spos().clamp(indent, code_end) - indent;
+    let epos = err.epos().clamp(indent, code_end) - indent;
+    // A span past the end of the line gets one caret after the code
+    let past_end = err.spos() == err.line().len();
+    let style = match underline {
+        Some(Underline::Undercurl) => theme.primary.undercurl(),
+        _ => Style::new(),
+    };
+    let (before, span, after) = (
+        code[..spos].width(),
+        code[spos..epos].width().max(usize::from(past_end)),
+        code[epos..].width(),
+    );
+
+    let mut string = String::from("Traceback (most recent call last):\n");
+    let path = format!("\"{}\"", linked(link, err.path().to_owned()));
+    string.push_str("  File ");
+    theme.gutter.paint_into(&mut string, &path);
+    string.push_str(", line ");
+    theme
+        .gutter
+        .paint_into(&mut string, &err.lineno().to_string());
+    string.push_str(", in ");
+    theme.gutter.paint_into(&mut string, "<module>");
+    string.push('\n');
+    string.push_str(&format!(
+        "    {}\n",
+        highlighter.paint(code, spos..epos, style)
+    ));
+
+    match underline {
+        _ if span == 0 || underline == Some(Underline::Undercurl) => {}
+        // A chosen mark goes under the span alone, as Python marks a whole
+        // expression
+        Some(underline) => {
+            let marks = primary_marks(Some(underline), theme, span, |width| "^".repeat(width));
+            string.push_str(&format!("    {}", " ".repeat(before)));
+            theme.primary.paint_into(&mut string, &marks);
+            string.push('\n');
+        }
+        None => {
+            string.push_str("    ");
+            let primary = theme.marks.primary.unwrap_or('^').to_string();
+            let secondary = theme.marks.secondary.unwrap_or('~').to_string();
+            theme
+                .secondary
+                .paint_into(&mut string, &secondary.repeat(before));
+            theme.primary.paint_into(&mut string, &primary.repeat(span));
+            theme
+                .secondary
+                .paint_into(&mut string, &secondary.repeat(after));
+            string.push('\n');
+        }
+    }
+
+    theme.header.paint_into(&mut string, exception);
+    let indent = format!("\n{}", " ".repeat(exception.width() + 2));
+    string.push_str(": ");
+    theme.note.paint_into(
+        &mut string,
+        &err.message.lines().collect::<Vec<_>>().join(&indent),
+    );
+    string.push('\n');
+    // After the exception, as Python prints the notes added to it
+    for text in err.notes.iter().chain(&err.helps) {
+        string.push_str(text);
+        string.push('\n');
+    }
+
+    string
+}
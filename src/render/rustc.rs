@@ -0,0 +1,211 @@
+use super::{
+    annotations::{self, Annotation},
+    fixit_diff,
+    highlight::Highlighter,
+    label_annotations, lineno_width, linked,
+    locale::Words,
+    primary_marks, span_style,
+    theme::{Marks, Theme},
+    Charset, Labels, Underline,
+};
+use unicode_width::UnicodeWidthStr;
+
+use crate::{ansi::Color, bold, style::Style, Diagnostic};
+
+pub const THEME: Theme = Theme {
+    header: Style::new().fg(Color::Basic(1)).bold(),
+    warning: Some(Style::new().fg(Color::Basic(3)).bold()),
+    gutter: Style::new().fg(Color::Basic(4)),
+    primary: Style::new().fg(Color::Basic(1)).bold(),
+    secondary: Style::new().fg(Color::Basic(4)).bold(),
+    note: Style::new().bold(),
+    marks: Marks::NATIVE,
+};
+
+/// Renders `err`, its location naming its file when `location` is set.
+#[allow(clippy::too_many_arguments)]
+pub fn render(
+    err: &Diagnostic,
+    theme: &Theme,
+    underline: Option<Underline>,
+    labels: Labels,
+    charset: Charset,
+    highlighter: &Highlighter,
+    link: Option<&str>,
+    location: bool,
+    explain: Option<&str>,
+    words: Words,
+) -> String {
+    let mut string = theme.header.paint(format!(
+        "{}[{}]",
+        words.severity(err.severity),
+        err.get_errid()
+    ));
+    Style::new()
+        .bold()
+        .paint_into(&mut string, &format!(": {}\n", err.summary));
+    let lineno_len = lineno_width(err);
+    string.push_str(&snippet(
+        err,
+        theme,
+        underline,
+        labels,
+        charset,
+        highlighter,
+        link,
+        location,
+        lineno_len,
+    ));
+
+    string.push_str(&footers(err, theme, words, lineno_len));
+
+    let note_theme = Theme {
+        primary: theme.note,
+        ..*theme
+    };
+    for note in &err.related {
+        string.push_str(&theme.note.paint(words.note()));
+        string.push_str(&bold(format!(": {}\n", note.summary)));
+        string.push_str(&snippet(
+            note,
+            &note_theme,
+            underline,
+            labels,
+            charset,
+            highlighter,
+            None,
+            true,
+            lineno_len,
+        ));
+    }
+
+    if let Some(diff) = fixit_diff(err, theme, lineno_len) {
+        let empty_line = theme
+            .gutter
+            .paint(format!("{}| ", " ".repeat(lineno_len + 1)));
+        string.push_str(&theme.note.paint(words.help()));
+        string.push_str(&bold(format!(
+            ": replace with `{}`\n",
+            err.fixit.as_deref().unwrap_or_default()
+        )));
+        string.push_str(&format!("{}\n{}{}\n", empty_line, diff, empty_line));
+    }
+
+    if let Some(explain) = explain {
+        string.push_str(&bold(format!(
+            "\nFor more information about this error, try `{} {}`.\n",
+            explain,
+            err.get_errid()
+        )));
+    }
+
+    string
+}
+
+/// The `= note:` then `= help:` lines of `err`, under the gutter, the lines
+/// after the first of each aligned with its text.
+fn footers(err: &Diagnostic, theme: &Theme, words: Words, lineno_len: usize) -> String {
+    let notes = err.notes.iter().map(|note| (words.note(), note));
+    let helps = err.helps.iter().map(|help| (words.help(), help));
+
+    let mut string = String::new();
+    for (word, text) in notes.chain(helps) {
+        // Under the text, after `= `, the word and `: `
+        let indent = format!("\n{}", " ".repeat(lineno_len + word.width() + 5));
+        string.push_str(&" ".repeat(lineno_len + 1));
+        theme.gutter.paint_into(&mut string, "= ");
+        theme.note.paint_into(&mut string, &word);
+        string.push_str(&format!(
+            ": {}\n",
+            text.lines().collect::<Vec<_>>().join(&indent)
+        ));
+    }
+
+    string
+}
+
+/// The quoted lines of `err` and the marks under its span, below its
+/// location, which names its file when `location` is set, their numbers
+/// right-aligned in the `lineno_len` columns shared by the whole
+/// diagnostic.
+#[allow(clippy::too_many_arguments)]
+fn snippet(
+    err: &Diagnostic,
+    theme: &Theme,
+    underline: Option<Underline>,
+    labels: Labels,
+    charset: Charset,
+    highlighter: &Highlighter,
+    link: Option<&str>,
+    location: bool,
+    lineno_len: usize,
+) -> String {
+    let first_lineno = err.lineno().saturating_sub(err.context_before().len());
+    let empty_line = theme
+        .gutter
+        .paint(format!("{}| ", " ".repeat(lineno_len + 1)));
+    let source_line = |string: &mut String, lineno: usize, line: &str| {
+        let gutter = format!("{:>width$} | ", lineno, width = lineno_len);
+        theme.gutter.paint_into(string, &gutter);
+        string.push_str(line);
+        string.push('\n');
+    };
+
+    // A diagnostic following another one of its file leaves its path out
+    let path = match location {
+        true => err.path(),
+        false => "",
+    };
+    let mut string = String::new();
+    string.push_str(&format!(
+        "{}{}{}\n",
+        " ".repeat(lineno_len),
+        theme.gutter.paint("--> ".to_owned()),
+        linked(link, format!("{}:{}:{}", path, err.lineno(), err.column())),
+    ));
+    string.push_str(&empty_line);
+    string.push('\n');
+    for (offset, line) in err.context_before().iter().enumerate() {
+        source_line(
+            &mut string,
+            first_lineno + offset,
+            &highlighter.paint(line, 0..0, Style::new()),
+        );
+    }
+    source_line(
+        &mut string,
+        err.lineno(),
+        &highlighter.paint(
+            err.line(),
+            err.spos()..err.epos(),
+            span_style(theme.primary, underline),
+        ),
+    );
+    let mut annotations = vec![Annotation {
+        indent: err.span_indent(),
+        marks: primary_marks(underline, theme, err.span_width(), |width| {
+            "^".repeat(width)
+        }),
+        label: &err.message,
+        style: theme.primary,
+    }];
+    annotations.extend(label_annotations(err, theme, '-'));
+    string.push_str(&annotations::draw(
+        &annotations,
+        labels,
+        &empty_line,
+        underline != Some(Underline::Undercurl),
+        charset,
+    ));
+    for (offset, line) in err.context_after().iter().enumerate() {
+        source_line(
+            &mut string,
+            err.lineno() + 1 + offset,
+            &highlighter.paint(line, 0..0, Style::new()),
+        );
+    }
+    string.push_str(&empty_line);
+    string.push('\n');
+
+    string
+}
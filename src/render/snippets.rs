@@ -0,0 +1,67 @@
+use annotate_snippets::{Level, Renderer, Snippet};
+
+use crate::{ansi, Diagnostic, Severity};
+
+/// Renders the diagnostic with the `annotate-snippets` crate, which rustc
+/// itself uses.
+pub fn render(err: &Diagnostic, max_width: Option<usize>) -> String {
+    let errid = err.get_errid();
+    let level = match err.severity {
+        Severity::Error => Level::Error,
+        Severity::Warning => Level::Warning,
+    };
+    let file = err.file();
+    let help = err
+        .fixit
+        .as_ref()
+        .map(|fixit| format!("replace with `{}`", fixit));
+
+    let mut snippet = Snippet::source(&file.contents)
+        .line_start(file.first_lineno)
+        .origin(&file.path)
+        .annotation(level.span(err.span.range.clone()).label(&err.message));
+    for label in &err.labels {
+        snippet = snippet.annotation(
+            Level::Info
+                .span(err.label_range(label))
+                .label(&label.message),
+        );
+    }
+
+    let mut message = level.title(&err.summary).id(&errid).snippet(snippet);
+    for note in &err.notes {
+        message = message.footer(Level::Note.title(note));
+    }
+    for help in &err.helps {
+        message = message.footer(Level::Help.title(help));
+    }
+    for note in &err.related {
+        let file = note.file();
+        message = message.footer(
+            Level::Note.title(&note.summary).snippet(
+                Snippet::source(&file.contents)
+                    .line_start(file.first_lineno)
+                    .origin(&file.path)
+                    .annotation(
+                        Level::Note
+                            .span(note.span.range.clone())
+                            .label(&note.message),
+                    ),
+            ),
+        );
+    }
+    if let Some(help) = &help {
+        message = message.footer(Level::Help.title(help));
+    }
+
+    let renderer = match ansi::enabled() {
+        true => Renderer::styled(),
+        false => Renderer::plain(),
+    };
+    let renderer = match max_width {
+        Some(width) => renderer.term_width(width),
+        None => renderer,
+    };
+
+    format!("{}\n", renderer.render(message))
+}
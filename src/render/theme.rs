@@ -0,0 +1,161 @@
+use std::{fs, path::Path};
+
+use serde::Deserialize;
+
+use super::{clang, gcc, python, rustc, tsc};
+use crate::{ansi::Color, style::Style, RoostError, Severity};
+
+/// Characters underlining the spans, instead of the ones of the style.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Marks {
+    pub primary: Option<char>,
+    pub secondary: Option<char>,
+}
+
+impl Marks {
+    pub const NATIVE: Marks = Marks {
+        primary: None,
+        secondary: None,
+    };
+}
+
+/// The styles of the semantic parts of a diagnostic.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Theme {
+    /// `error[E0308]`, `error:`, or the exception name
+    pub header: Style,
+    /// The header of warnings, and their span if it shares the style of the
+    /// header; the ones of errors when unset
+    pub warning: Option<Style>,
+    /// Line numbers, bars and location
+    pub gutter: Style,
+    /// The span and its underline
+    pub primary: Style,
+    /// Secondary underlines and suggestions
+    pub secondary: Style,
+    /// Notes attached to the error
+    pub note: Style,
+    pub marks: Marks,
+}
+
+impl Theme {
+    /// The theme a diagnostic of `severity` is painted with.
+    pub fn for_severity(self, severity: Severity) -> Theme {
+        match (severity, self.warning) {
+            (Severity::Warning, Some(warning)) => Theme {
+                header: warning,
+                primary: match self.primary == self.header {
+                    true => warning,
+                    false => self.primary,
+                },
+                ..self
+            },
+            _ => self,
+        }
+    }
+}
+
+const DRACULA: Theme = Theme {
+    header: Style::new().fg(Color::Rgb([0xff, 0x55, 0x55])).bold(),
+    warning: Some(Style::new().fg(Color::Rgb([0xf1, 0xfa, 0x8c])).bold()),
+    gutter: Style::new().fg(Color::Rgb([0x62, 0x72, 0xa4])),
+    primary: Style::new().fg(Color::Rgb([0xff, 0x79, 0xc6])).bold(),
+    secondary: Style::new().fg(Color::Rgb([0x8b, 0xe9, 0xfd])),
+    note: Style::new().fg(Color::Rgb([0x50, 0xfa, 0x7b])).bold(),
+    marks: Marks::NATIVE,
+};
+
+const SOLARIZED: Theme = Theme {
+    header: Style::new().fg(Color::Rgb([0xdc, 0x32, 0x2f])).bold(),
+    warning: Some(Style::new().fg(Color::Rgb([0xb5, 0x89, 0x00])).bold()),
+    gutter: Style::new().fg(Color::Rgb([0x26, 0x8b, 0xd2])),
+    primary: Style::new().fg(Color::Rgb([0xcb, 0x4b, 0x16])).bold(),
+    secondary: Style::new().fg(Color::Rgb([0x2a, 0xa1, 0x98])),
+    note: Style::new().fg(Color::Rgb([0x85, 0x99, 0x00])).bold(),
+    marks: Marks::NATIVE,
+};
+
+// The color-blind themes keep errors and warnings apart with hues their
+// readers tell apart, and with the bold of errors alone, so that neither
+// rests on red against green.
+
+/// Okabe and Ito's palette, for the lack of green cones.
+const DEUTERANOPIA: Theme = Theme {
+    header: Style::new().fg(Color::Rgb([0xe6, 0x9f, 0x00])).bold(),
+    warning: Some(Style::new().fg(Color::Rgb([0x56, 0xb4, 0xe9]))),
+    gutter: Style::new().fg(Color::Rgb([0x00, 0x72, 0xb2])),
+    primary: Style::new().fg(Color::Rgb([0xe6, 0x9f, 0x00])).bold(),
+    secondary: Style::new().fg(Color::Rgb([0x56, 0xb4, 0xe9])),
+    note: Style::new().fg(Color::Rgb([0xcc, 0x79, 0xa7])).bold(),
+    marks: Marks::NATIVE,
+};
+
+/// IBM's palette, for the lack of red cones, to which red looks dark.
+const PROTANOPIA: Theme = Theme {
+    header: Style::new().fg(Color::Rgb([0xff, 0xb0, 0x00])).bold(),
+    warning: Some(Style::new().fg(Color::Rgb([0x64, 0x8f, 0xff]))),
+    gutter: Style::new().fg(Color::Rgb([0x78, 0x5e, 0xf0])),
+    primary: Style::new().fg(Color::Rgb([0xff, 0xb0, 0x00])).bold(),
+    secondary: Style::new().fg(Color::Rgb([0x64, 0x8f, 0xff])),
+    note: Style::new().fg(Color::Rgb([0xdc, 0x26, 0x7f])).bold(),
+    marks: Marks::NATIVE,
+};
+
+/// Red against teal, for the lack of blue cones, to which blue and yellow
+/// look alike.
+const TRITANOPIA: Theme = Theme {
+    header: Style::new().fg(Color::Rgb([0xdc, 0x32, 0x20])).bold(),
+    warning: Some(Style::new().fg(Color::Rgb([0x40, 0xb0, 0xa6]))),
+    gutter: Style::new().fg(Color::Rgb([0x88, 0x88, 0x88])),
+    primary: Style::new().fg(Color::Rgb([0xdc, 0x32, 0x20])).bold(),
+    secondary: Style::new().fg(Color::Rgb([0x40, 0xb0, 0xa6])),
+    note: Style::new().fg(Color::Rgb([0xaa, 0x44, 0x99])).bold(),
+    marks: Marks::NATIVE,
+};
+
+/// The bright colors of the terminal's own palette, all in bold.
+const HIGH_CONTRAST: Theme = Theme {
+    header: Style::new().fg(Color::Bright(1)).bold(),
+    warning: Some(Style::new().fg(Color::Bright(3)).bold().undercurl()),
+    gutter: Style::new().fg(Color::Bright(7)).bold(),
+    primary: Style::new().fg(Color::Bright(1)).bold(),
+    secondary: Style::new().fg(Color::Bright(6)).bold(),
+    note: Style::new().fg(Color::Bright(7)).bold(),
+    marks: Marks::NATIVE,
+};
+
+pub const BUILTIN: [(&str, Theme); 11] = [
+    ("rustc", rustc::THEME),
+    ("gcc", gcc::THEME),
+    ("clang", clang::THEME),
+    ("python", python::THEME),
+    ("tsc", tsc::THEME),
+    ("dracula", DRACULA),
+    ("solarized", SOLARIZED),
+    ("deuteranopia", DEUTERANOPIA),
+    ("protanopia", PROTANOPIA),
+    ("tritanopia", TRITANOPIA),
+    ("high-contrast", HIGH_CONTRAST),
+];
+
+/// Parses the name of a built-in theme, or the path to a TOML theme file.
+pub fn parse(value: &str) -> Result<Theme, RoostError> {
+    if let Some((_, theme)) = BUILTIN.iter().find(|(name, _)| *name == value) {
+        return Ok(*theme);
+    }
+
+    let path = Path::new(value);
+    let contents = fs::read_to_string(path).map_err(|_| RoostError::ValueError {
+        details: format!(
+            "'{}' is neither a built-in theme ({}) nor a theme file",
+            value,
+            BUILTIN.map(|(name, _)| name).join(", ")
+        ),
+    })?;
+
+    toml::from_str(&contents).map_err(|err| RoostError::ValueError {
+        details: format!("invalid theme {}: {}", path.display(), err.message()),
+    })
+}
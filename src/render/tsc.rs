@@ -0,0 +1,108 @@
+use super::{
+    highlight::Highlighter,
+    linked,
+    locale::Words,
+    primary_marks,
+    theme::{Marks, Theme},
+    Underline,
+};
+use crate::{ansi::Color, style::Style, Diagnostic};
+
+pub const THEME: Theme = Theme {
+    header: Style::new().fg(Color::Basic(1)),
+    warning: Some(Style::new().fg(Color::Basic(3))),
+    gutter: Style::new().fg(Color::Basic(6)),
+    primary: Style::new().fg(Color::Basic(1)),
+    secondary: Style::new().fg(Color::Basic(3)),
+    note: Style::new().fg(Color::Basic(0)),
+    marks: Marks::NATIVE,
+};
+
+pub fn render(
+    err: &Diagnostic,
+    theme: &Theme,
+    underline: Option<Underline>,
+    highlighter: &Highlighter,
+    link: Option<&str>,
+    words: Words,
+) -> String {
+    let span = match underline {
+        Some(Underline::Undercurl) => theme.primary.undercurl(),
+        _ => Style::new(),
+    };
+    let gutter = " ".repeat(err.lineno().to_string().len());
+
+    let mut location = String::new();
+    location_into(&mut location, err, theme);
+    let mut string = linked(link, location);
+    string.push_str(" - ");
+    theme
+        .header
+        .paint_into(&mut string, &words.severity(err.severity));
+    string.push(' ');
+    let code = match &err.code {
+        Some(code) => code.clone(),
+        None => format!("TS{}", err.errnum),
+    };
+    theme.header.paint_into(&mut string, &code);
+    string.push_str(&format!(": {}\n", err.summary));
+    for line in err.message.lines() {
+        string.push_str(&format!("  {}\n", line));
+    }
+    // Chained after the message, as tsc chains the ones it elaborates on
+    let notes = err.notes.iter().map(|note| (words.note(), note));
+    let helps = err.helps.iter().map(|help| (words.help(), help));
+    for (word, text) in notes.chain(helps) {
+        string.push_str("  ");
+        theme.note.paint_into(&mut string, &word);
+        string.push_str(&format!(": {}\n", text));
+    }
+    string.push('\n');
+    string.push_str(&format!(
+        "{} {}\n",
+        err.lineno(),
+        highlighter.paint(err.line(), err.spos()..err.epos(), span),
+    ));
+    if underline != Some(Underline::Undercurl) {
+        let marks = primary_marks(underline, theme, err.span_width(), |width| {
+            "~".repeat(width)
+        });
+        string.push_str(&format!("{} {}", gutter, " ".repeat(err.span_indent())));
+        theme.primary.paint_into(&mut string, &marks);
+        string.push('\n');
+    }
+    string.push('\n');
+
+    for note in &err.related {
+        let gutter = " ".repeat(note.lineno().to_string().len());
+        string.push_str("  ");
+        location_into(&mut string, note, theme);
+        string.push('\n');
+        string.push_str(&format!(
+            "    {} {}\n",
+            note.lineno(),
+            highlighter.paint(note.line(), note.spos()..note.epos(), Style::new()),
+        ));
+        string.push_str(&format!(
+            "    {} {}",
+            gutter,
+            " ".repeat(note.span_indent())
+        ));
+        theme
+            .secondary
+            .paint_into(&mut string, &"~".repeat(note.span_width()));
+        string.push('\n');
+        string.push_str(&format!("    {}\n\n", note.summary));
+    }
+
+    string
+}
+
+/// Writes where `err` is to `string`, as `src/main.ts:3:5`.
+fn location_into(string: &mut String, err: &Diagnostic, theme: &Theme) {
+    theme.gutter.paint_into(string, err.path());
+    string.push(':');
+    theme.gutter.paint_into(string, &err.lineno().to_string());
+    string.push(':');
+    theme.gutter.paint_into(string, &err.column().to_string());
+}
@@ -0,0 +1,267 @@
+//! The menu offered once the diagnostic of the prompts is printed, to
+//! change one of its fields or how it is presented and print it again,
+//! instead of answering every prompt anew.
+
+use std::ops::Range;
+
+use clap::ValueEnum;
+
+use roost::{
+    bold, color,
+    export::Format,
+    position::{self, PositionRange},
+    prompt::{
+        field, field_with, int_factory, optional_field, range_factory, read_answer, string, text,
+    },
+    render::{
+        self,
+        theme::{self, Theme},
+    },
+    source::SourceMap,
+    Diagnostic, RoostError, Severity,
+};
+
+use crate::{config::Config, paths, print_line_helper, printed_code, rules, span_positions, Args};
+
+/// What to do with the printed diagnostic.
+pub enum Choice {
+    Accept,
+    Edit,
+    Theme,
+    Format,
+}
+
+#[derive(Clone, Copy)]
+enum Field {
+    Summary,
+    Message,
+    Line,
+    Span,
+    Lineno,
+    Path,
+    Errnum,
+    Severity,
+    Notes,
+    Helps,
+}
+
+/// The fields that can be edited, in the order of the prompts.
+const FIELDS: [(&str, Field); 10] = [
+    ("summary", Field::Summary),
+    ("message", Field::Message),
+    ("line", Field::Line),
+    ("error span", Field::Span),
+    ("line number", Field::Lineno),
+    ("path", Field::Path),
+    ("error number", Field::Errnum),
+    ("severity", Field::Severity),
+    ("notes", Field::Notes),
+    ("helps", Field::Helps),
+];
+
+/// Asks what to do with the diagnostic just printed, an empty answer
+/// accepting it.
+pub fn choose() -> Choice {
+    let prompt = bold(format!(
+        "{} ",
+        text(
+            "looks good? [enter] accept, [e]dit field, [t]heme, [f]ormat",
+            &[]
+        )
+    ));
+
+    loop {
+        let answer = read_answer("choice", &prompt);
+        match answer.trim().to_lowercase().as_str() {
+            "" => return Choice::Accept,
+            "e" => return Choice::Edit,
+            "t" => return Choice::Theme,
+            "f" => return Choice::Format,
+            _ => eprintln!(
+                "{}",
+                bold(color(
+                    format!(
+                        "ERR: {}",
+                        text("'{}' is not one of the choices", &[answer.trim()])
+                    ),
+                    3
+                ))
+            ),
+        }
+    }
+}
+
+/// Prompts for the span in `line`, as the interview does, `current` being
+/// the default unless it no longer fits in the line.
+fn span(args: &Args, config: &Config, line: &str, current: Range<usize>) -> Range<usize> {
+    let (unit, base) = span_positions(args, config);
+    let current = match current.end <= line.len() {
+        true => current,
+        false => 0..line.len(),
+    };
+    if !args.render.quiet {
+        print_line_helper(
+            line,
+            unit,
+            base,
+            args.render.charset,
+            args.render
+                .max_width
+                .map(usize::from)
+                .or_else(render::layout::terminal_width),
+            Some(current.clone()),
+        );
+    }
+
+    let from_byte =
+        |offset: usize| unit.from_byte(line, position::floor_char_boundary(line, offset));
+    let (spos, epos) = (from_byte(current.start), from_byte(current.end));
+    let PositionRange(range) = field(
+        "error span",
+        &range_factory(base, unit.from_byte(line, line.len())),
+        Some(PositionRange(spos + base..epos + base)),
+    );
+    unit.to_bytes(line, range.start - base..range.end - base)
+}
+
+/// Prompts for the path, prefixed as set by the configuration unless it is
+/// `current` or a recent path.
+fn path(config: &Config, current: String) -> String {
+    let recent = paths::recent();
+    let answer = field_with(
+        "path",
+        &rules::enforce(config.rules.path.as_ref(), string),
+        Some(current.clone()),
+        |name, prompt| paths::read_answer(name, prompt, &recent),
+    );
+
+    match answer == current || recent.contains(&answer) {
+        true => answer,
+        false => config.prefixed_path(answer),
+    }
+}
+
+/// Lists the fields of the diagnostic and prompts for a new value of the
+/// chosen one, the current value being the default. Notes and helps are
+/// all prompted for again.
+pub fn edit(args: &Args, config: &Config, err: &mut Diagnostic<'static>) {
+    for (index, (name, _)) in FIELDS.iter().enumerate() {
+        println!("{:>2}. {}", index + 1, text(name, &[]));
+    }
+    let Some(index) = optional_field(
+        "field to edit (empty to cancel)",
+        &int_factory(1, FIELDS.len()),
+    ) else {
+        return;
+    };
+
+    let rules = &config.rules;
+    let mut path = err.path().to_owned();
+    let mut lineno = err.lineno();
+    let mut line = err.line().to_owned();
+    let mut range = err.spos()..err.epos();
+    match FIELDS[index - 1].1 {
+        Field::Summary => {
+            err.summary = field(
+                "summary",
+                &rules::enforce(rules.summary.as_ref(), string),
+                Some(err.summary.to_string()),
+            )
+            .into()
+        }
+        Field::Message => {
+            err.message = field(
+                "message",
+                &rules::enforce(rules.message.as_ref(), string),
+                Some(err.message.to_string()),
+            )
+            .into()
+        }
+        Field::Line => {
+            // The span is asked for again, as it may no longer fit
+            line = field(
+                "line",
+                &rules::enforce(rules.line.as_ref(), string),
+                Some(line),
+            );
+            range = span(args, config, &line, range);
+        }
+        Field::Span => range = span(args, config, &line, range),
+        Field::Lineno => {
+            lineno = field(
+                "line number",
+                &rules::enforce(rules.lineno.as_ref(), int_factory(usize::MIN, usize::MAX)),
+                Some(lineno),
+            )
+        }
+        Field::Path => {
+            path = self::path(config, path);
+            paths::remember(&path);
+        }
+        Field::Errnum => {
+            err.errnum = field(
+                "error number",
+                &rules::enforce_errnum(
+                    rules.errnum.as_ref(),
+                    int_factory(usize::MIN, usize::MAX),
+                    |errnum| printed_code(args, errnum),
+                ),
+                Some(err.errnum),
+            )
+        }
+        Field::Severity => {
+            err.severity = field(
+                "severity",
+                &|raw: &str| {
+                    Severity::from_str(raw, true).map_err(|_| RoostError::ValueError {
+                        details: text("expected one of {}", &["error, warning"]),
+                    })
+                },
+                Some(err.severity),
+            )
+        }
+        Field::Notes => {
+            err.notes =
+                std::iter::from_fn(|| optional_field("note (empty to finish)", &string)).collect()
+        }
+        Field::Helps => {
+            err.helps =
+                std::iter::from_fn(|| optional_field("help (empty to finish)", &string)).collect()
+        }
+    }
+    println!();
+
+    let (sources, span) = SourceMap::quote(path, lineno, &line, range, &[], &[]);
+    err.sources = sources;
+    err.span = span;
+}
+
+/// Prompts for a built-in theme or a theme file, `None` keeping the
+/// current one.
+pub fn theme() -> Option<Theme> {
+    optional_field("theme (empty to cancel)", &theme::parse)
+}
+
+/// Parses the name of a format that can be printed in the terminal, which
+/// leaves out PNG images and plugins.
+fn printable_format(name: &str) -> Result<Format, RoostError> {
+    match Format::from_str(name, true) {
+        Ok(Format::Png) | Err(_) => {
+            let names: Vec<String> = Format::value_variants()
+                .iter()
+                .filter(|format| **format != Format::Png)
+                .filter_map(Format::to_possible_value)
+                .map(|value| value.get_name().to_owned())
+                .collect();
+            Err(RoostError::ValueError {
+                details: text("expected one of {}", &[&names.join(", ")]),
+            })
+        }
+        Ok(format) => Ok(format),
+    }
+}
+
+/// Prompts for a format, `None` keeping the current one.
+pub fn format() -> Option<Format> {
+    optional_field("format (empty to cancel)", &printable_format)
+}
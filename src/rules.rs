@@ -0,0 +1,203 @@
+//! Conventions on the fields entered at the prompts, set in the `[rules]`
+//! table of the configuration:
+//!
+//! ```toml
+//! [rules.path]
+//! pattern = '^src/.*\.rs$'
+//! [rules.errnum]
+//! pattern = '^E0\d{3}$'
+//! max = 999
+//! ```
+
+use regex::Regex;
+use serde::Deserialize;
+
+use roost::{prompt::text, RoostError};
+
+/// The rules of each field, by the name it has in specs.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Rules {
+    pub summary: Option<Rule>,
+    pub line: Option<Rule>,
+    pub message: Option<Rule>,
+    pub lineno: Option<Rule>,
+    pub path: Option<Rule>,
+    pub errnum: Option<Rule>,
+}
+
+impl Rules {
+    /// Keeps the rules of `self`, falling back to `lower` for the fields
+    /// without one.
+    pub fn merge(self, lower: Rules) -> Rules {
+        Rules {
+            summary: self.summary.or(lower.summary),
+            line: self.line.or(lower.line),
+            message: self.message.or(lower.message),
+            lineno: self.lineno.or(lower.lineno),
+            path: self.path.or(lower.path),
+            errnum: self.errnum.or(lower.errnum),
+        }
+    }
+}
+
+/// What the value of a field must look like. The pattern of `errnum` is
+/// matched against the code as printed, such as `E0308`, or `W0308` with
+/// `--code 'W####'`.
+#[derive(Debug, Deserialize)]
+#[serde(try_from = "RuleSpec")]
+pub struct Rule {
+    pattern: Option<Regex>,
+    min: Option<usize>,
+    max: Option<usize>,
+    max_length: Option<usize>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RuleSpec {
+    pattern: Option<String>,
+    min: Option<usize>,
+    max: Option<usize>,
+    max_length: Option<usize>,
+}
+
+impl TryFrom<RuleSpec> for Rule {
+    type Error = String;
+
+    fn try_from(spec: RuleSpec) -> Result<Self, Self::Error> {
+        let pattern = match spec.pattern {
+            Some(pattern) => Some(
+                Regex::new(&pattern)
+                    .map_err(|err| format!("invalid pattern '{}': {}", pattern, err))?,
+            ),
+            None => None,
+        };
+
+        Ok(Rule {
+            pattern,
+            min: spec.min,
+            max: spec.max,
+            max_length: spec.max_length,
+        })
+    }
+}
+
+impl Rule {
+    /// Checks the text `raw` entered for a field, `shown` being how it is
+    /// printed in the diagnostic.
+    fn check(&self, raw: &str, shown: &str) -> Result<(), RoostError> {
+        let invalid = |details: String| RoostError::ValueError { details };
+
+        if let Some(max_length) = self.max_length {
+            if raw.chars().count() > max_length {
+                return Err(invalid(text(
+                    "must be at most {} characters long",
+                    &[&max_length.to_string()],
+                )));
+            }
+        }
+        if let Some(pattern) = &self.pattern {
+            if !pattern.is_match(shown) {
+                return Err(invalid(text("must match '{}'", &[pattern.as_str()])));
+            }
+        }
+        if self.min.is_some() || self.max.is_some() {
+            let value: usize = raw
+                .parse()
+                .map_err(|_| invalid(text("must be a number", &[])))?;
+            if let Some(min) = self.min.filter(|&min| value < min) {
+                return Err(invalid(text("must be at least {}", &[&min.to_string()])));
+            }
+            if let Some(max) = self.max.filter(|&max| value > max) {
+                return Err(invalid(text("must be at most {}", &[&max.to_string()])));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Wraps the parser of a prompt so that it also enforces `rule`.
+pub fn enforce<'a, T>(
+    rule: Option<&'a Rule>,
+    parse: impl Fn(&str) -> Result<T, RoostError> + 'a,
+) -> impl Fn(&str) -> Result<T, RoostError> + 'a {
+    move |raw: &str| {
+        if let Some(rule) = rule {
+            rule.check(raw, raw)?;
+        }
+        parse(raw)
+    }
+}
+
+/// Like [`enforce`] for the error number, whose pattern is matched against
+/// the code `code` prints it as, with `--code` and `--code-width`.
+pub fn enforce_errnum<'a>(
+    rule: Option<&'a Rule>,
+    parse: impl Fn(&str) -> Result<usize, RoostError> + 'a,
+    code: impl Fn(usize) -> String + 'a,
+) -> impl Fn(&str) -> Result<usize, RoostError> + 'a {
+    move |raw: &str| {
+        let errnum = parse(raw)?;
+        if let Some(rule) = rule {
+            rule.check(raw, &code(errnum))?;
+        }
+        Ok(errnum)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use roost::prompt::{int_factory, string};
+
+    use super::*;
+
+    fn rules(toml: &str) -> Rules {
+        toml::from_str(toml).unwrap()
+    }
+
+    #[test]
+    fn enforce_without_a_rule_only_parses() {
+        assert_eq!(enforce(None, string)("anything").unwrap(), "anything");
+        assert!(enforce(None, int_factory(1, 9))("10").is_err());
+    }
+
+    #[test]
+    fn enforce_checks_each_constraint() {
+        let rules = rules(
+            "[path]\npattern = '^src/.*\\.rs$'\n[summary]\nmax_length = 5\n[lineno]\nmin = 2\nmax = 9\n",
+        );
+        let path = enforce(rules.path.as_ref(), string);
+        let summary = enforce(rules.summary.as_ref(), string);
+        let lineno = enforce(rules.lineno.as_ref(), int_factory(usize::MIN, usize::MAX));
+
+        assert!(path("src/main.rs").is_ok());
+        assert!(path("main.rs").is_err());
+        // Characters are counted, not bytes
+        assert!(summary("日本語です").is_ok());
+        assert!(summary("mismatched").is_err());
+        assert_eq!(lineno("9").unwrap(), 9);
+        assert!(lineno("1").is_err());
+        assert!(lineno("10").is_err());
+    }
+
+    #[test]
+    fn enforce_errnum_matches_the_printed_code() {
+        let rules = rules("[errnum]\npattern = '^E0\\d{3}$'\n");
+        let errnum = enforce_errnum(
+            rules.errnum.as_ref(),
+            int_factory(usize::MIN, usize::MAX),
+            |errnum| format!("E{:04}", errnum),
+        );
+
+        assert_eq!(errnum("308").unwrap(), 308);
+        assert!(errnum("1308").is_err());
+    }
+
+    #[test]
+    fn rules_refuse_invalid_patterns() {
+        assert!(toml::from_str::<Rules>("[path]\npattern = '('\n").is_err());
+        assert!(toml::from_str::<Rules>("[path]\ntypo = 1\n").is_err());
+    }
+}
@@ -0,0 +1,120 @@
+use std::fs;
+
+use crate::{Diagnostic, RoostError};
+
+/// A span selected in an editor, given as `file:line:col-col` with 1-based,
+/// inclusive line and columns.
+#[derive(Clone, Debug)]
+pub struct Selection {
+    pub path: String,
+    pub lineno: usize,
+    pub line: String,
+    pub spos: usize,
+    pub epos: usize,
+}
+
+fn invalid(details: String) -> RoostError {
+    RoostError::ValueError { details }
+}
+
+fn parse_position(raw: &str, what: &str) -> Result<usize, RoostError> {
+    raw.parse::<usize>()
+        .ok()
+        .filter(|&value| value > 0)
+        .ok_or_else(|| invalid(format!("invalid {} '{}'", what, raw)))
+}
+
+pub fn parse(spec: &str) -> Result<Selection, RoostError> {
+    let mut parts = spec.rsplitn(3, ':');
+    let (Some(columns), Some(lineno), Some(path)) = (parts.next(), parts.next(), parts.next())
+    else {
+        return Err(invalid(format!(
+            "expected 'file:line:col-col', found '{}'",
+            spec
+        )));
+    };
+
+    let lineno = parse_position(lineno, "line")?;
+    let (start, end) = match columns.split_once('-') {
+        Some((start, end)) => (
+            parse_position(start, "column")?,
+            parse_position(end, "column")?,
+        ),
+        None => {
+            let column = parse_position(columns, "column")?;
+            (column, column)
+        }
+    };
+
+    if end < start {
+        return Err(invalid(format!(
+            "the selection ends before it starts ({}-{})",
+            start, end
+        )));
+    }
+
+    let contents = fs::read_to_string(path)
+        .map_err(|err| invalid(format!("could not read {}: {}", path, err)))?;
+    let line = contents
+        .lines()
+        .nth(lineno - 1)
+        .ok_or_else(|| invalid(format!("{} has no line {}", path, lineno)))?;
+
+    if line.is_empty() {
+        return Err(invalid(format!("line {} of {} is empty", lineno, path)));
+    }
+    if end > line.len() {
+        return Err(invalid(format!(
+            "line {} of {} has only {} columns",
+            lineno,
+            path,
+            line.len()
+        )));
+    }
+
+    Ok(Selection {
+        path: path.to_owned(),
+        lineno,
+        line: line.to_owned(),
+        spos: start - 1,
+        epos: end,
+    })
+}
+
+/// Defaults proposed by the interactive prompts.
+#[derive(Clone, Debug, Default)]
+pub struct Prefill {
+    pub path: Option<String>,
+    pub lineno: Option<usize>,
+    pub line: Option<String>,
+    pub span: Option<(usize, usize)>,
+    pub summary: Option<String>,
+    pub message: Option<String>,
+    pub errnum: Option<usize>,
+}
+
+impl From<&Selection> for Prefill {
+    fn from(selection: &Selection) -> Self {
+        Prefill {
+            path: Some(selection.path.clone()),
+            lineno: Some(selection.lineno),
+            line: Some(selection.line.clone()),
+            span: Some((selection.spos, selection.epos)),
+            ..Default::default()
+        }
+    }
+}
+
+impl From<&Diagnostic<'_>> for Prefill {
+    fn from(err: &Diagnostic) -> Self {
+        Prefill {
+            path: Some(err.path().to_owned()),
+            lineno: Some(err.lineno()),
+            line: Some(err.line().to_owned()),
+            span: Some((err.spos(), err.epos())),
+            summary: Some(err.summary.to_string()),
+            message: Some(err.message.to_string()).filter(|message| !message.is_empty()),
+            errnum: Some(err.errnum),
+        }
+    }
+}
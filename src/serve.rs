@@ -0,0 +1,139 @@
+use clap::Parser;
+use tiny_http::{Header, Method, Request, Response, Server};
+
+use roost::{
+    export::{self, ExportOptions, Format},
+    render::RenderOptions,
+    spec, RoostError,
+};
+
+const USAGE: &str = "POST a JSON spec to /render?format=<format>&style=<style>\n";
+
+#[derive(clap::Args, Debug)]
+pub struct ServeArgs {
+    #[arg(long, default_value = "127.0.0.1")]
+    pub host: String,
+    #[arg(long, default_value_t = 8080)]
+    pub port: u16,
+}
+
+/// The render and export options of a request, given as query parameters
+/// named like the command line flags.
+#[derive(Parser, Debug)]
+#[command(no_binary_name = true)]
+struct RequestOptions {
+    #[command(flatten)]
+    render: RenderOptions,
+    #[command(flatten)]
+    export: ExportOptions,
+}
+
+fn percent_decode(raw: &str) -> String {
+    let bytes = raw.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+
+    while index < bytes.len() {
+        match bytes[index] {
+            b'+' => decoded.push(b' '),
+            b'%' if index + 2 < bytes.len() => {
+                let hex = std::str::from_utf8(&bytes[index + 1..index + 3]).unwrap_or("");
+                match u8::from_str_radix(hex, 16) {
+                    Ok(byte) => {
+                        decoded.push(byte);
+                        index += 2;
+                    }
+                    Err(_) => decoded.push(b'%'),
+                }
+            }
+            byte => decoded.push(byte),
+        }
+        index += 1;
+    }
+
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+fn parse_options(query: &str) -> Result<RequestOptions, RoostError> {
+    let mut args = Vec::new();
+
+    for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+        args.push(format!("--{}", percent_decode(key)));
+        if !value.is_empty() {
+            args.push(percent_decode(value));
+        }
+    }
+
+    RequestOptions::try_parse_from(args).map_err(|err| RoostError::ValueError {
+        details: err.to_string(),
+    })
+}
+
+fn render(request: &mut Request) -> Result<(Vec<u8>, Format), RoostError> {
+    let query = request.url().split_once('?').map_or("", |(_, query)| query);
+    let mut options = parse_options(query)?;
+    options.render.resolve(false);
+
+    let mut body = String::new();
+    request
+        .as_reader()
+        .read_to_string(&mut body)
+        .map_err(|err| RoostError::ValueError {
+            details: format!("could not read the request: {}", err),
+        })?;
+    let errs = spec::from_json(&body)?;
+
+    let mut output = Vec::new();
+    // Writing to a buffer fails only when a plugin does
+    export::write(&options.export, &options.render, &errs, &mut output).map_err(|err| {
+        RoostError::OutputError {
+            details: format!("could not render the diagnostic: {}", err),
+        }
+    })?;
+
+    Ok((output, options.export.format))
+}
+
+fn respond(mut request: Request) {
+    let path = request.url().split('?').next().unwrap_or("").to_owned();
+    let text = |status: u16, body: String| {
+        Response::from_string(body)
+            .with_status_code(status)
+            .with_header(
+                Header::from_bytes("Content-Type", "text/plain; charset=utf-8")
+                    .expect("static header"),
+            )
+    };
+
+    let response = match (request.method(), path.as_str()) {
+        (Method::Post, "/render") => match render(&mut request) {
+            Ok((body, format)) => Response::from_data(body).with_header(
+                Header::from_bytes("Content-Type", format.content_type()).expect("static header"),
+            ),
+            Err(err @ RoostError::OutputError { .. }) => text(500, format!("{}\n", err)),
+            Err(err) => text(400, format!("{}\n", err)),
+        },
+        (Method::Get, "/") => text(200, USAGE.to_owned()),
+        _ => text(404, USAGE.to_owned()),
+    };
+
+    if let Err(err) = request.respond(response) {
+        eprintln!("could not respond: {}", err);
+    }
+}
+
+pub fn run(args: &ServeArgs) -> Result<(), RoostError> {
+    let address = format!("{}:{}", args.host, args.port);
+    let server = Server::http(&address).map_err(|err| RoostError::ValueError {
+        details: format!("could not listen on {}: {}", address, err),
+    })?;
+
+    eprintln!("roost is listening on http://{}", address);
+
+    for request in server.incoming_requests() {
+        respond(request);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,127 @@
+//! The `share` subcommand: the rendered diagnostic uploaded to a paste
+//! service or a GitHub gist, through `curl`.
+
+use std::{
+    env,
+    io::Write,
+    path::PathBuf,
+    process::{Command, Stdio},
+};
+
+use serde_json::{json, Value};
+
+use roost::RoostError;
+
+use crate::config::Config;
+
+/// Where the diagnostic is posted when the configuration does not say.
+const DEFAULT_ENDPOINT: &str = "https://paste.rs/";
+const GISTS_URL: &str = "https://api.github.com/gists";
+/// The environment variable holding the token of `--gist`.
+const TOKEN_VAR: &str = "GITHUB_TOKEN";
+
+#[derive(clap::Args, Debug)]
+pub struct ShareArgs {
+    /// TOML or JSON spec file, prompting for the diagnostic if omitted
+    #[arg(long)]
+    pub spec: Option<PathBuf>,
+    /// Upload the HTML rendering instead of the text
+    #[arg(long)]
+    pub html: bool,
+    /// Create a secret GitHub gist instead, with the token of
+    /// `GITHUB_TOKEN`
+    #[arg(long)]
+    pub gist: bool,
+}
+
+fn failed(details: String) -> RoostError {
+    RoostError::OutputError {
+        details: format!("could not share the diagnostic: {}", details),
+    }
+}
+
+/// `value` as a quoted string of a curl config file.
+fn quoted(value: &str) -> String {
+    let escaped = value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+        .replace('\t', "\\t");
+    format!("\"{}\"", escaped)
+}
+
+/// Runs curl with the options of `config`, given on its standard input so
+/// that neither the token nor the diagnostic show in the process list, and
+/// gives the body of the response.
+fn curl(config: &str) -> Result<String, RoostError> {
+    let mut child = Command::new("curl")
+        .args(["--silent", "--show-error", "--fail", "--config", "-"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|err| failed(format!("could not run curl: {}", err)))?;
+    child
+        .stdin
+        .take()
+        .expect("stdin is piped")
+        .write_all(config.as_bytes())
+        .map_err(|err| failed(format!("could not write to curl: {}", err)))?;
+
+    let output = child
+        .wait_with_output()
+        .map_err(|err| failed(format!("curl failed: {}", err)))?;
+    match output.status.success() {
+        true => Ok(String::from_utf8_lossy(&output.stdout).into_owned()),
+        false => Err(failed(
+            String::from_utf8_lossy(&output.stderr).trim().to_owned(),
+        )),
+    }
+}
+
+/// Uploads `rendered` and gives its URL: posted as is to the `share_endpoint`
+/// of the configuration, which answers with the URL, or as the only file of
+/// a gist with `--gist`.
+pub fn upload(args: &ShareArgs, config: &Config, rendered: &str) -> Result<String, RoostError> {
+    let content_type = match args.html {
+        true => "text/html; charset=utf-8",
+        false => "text/plain; charset=utf-8",
+    };
+    if !args.gist {
+        let endpoint = config.share_endpoint.as_deref().unwrap_or(DEFAULT_ENDPOINT);
+        let response = curl(&format!(
+            "url = {}\nheader = {}\ndata-raw = {}\n",
+            quoted(endpoint),
+            quoted(&format!("Content-Type: {}", content_type)),
+            quoted(rendered)
+        ))?;
+        return Ok(response.trim().to_owned());
+    }
+
+    let token = env::var(TOKEN_VAR)
+        .ok()
+        .filter(|token| !token.is_empty())
+        .ok_or_else(|| failed(format!("`--gist` needs a token in {}", TOKEN_VAR)))?;
+    let name = match args.html {
+        true => "diagnostic.html",
+        false => "diagnostic.txt",
+    };
+    let gist = json!({
+        "description": "Diagnostic rendered by roost",
+        "public": false,
+        "files": { name: { "content": rendered } },
+    });
+    let response = curl(&format!(
+        "url = {}\nheader = {}\nheader = {}\ndata-raw = {}\n",
+        quoted(GISTS_URL),
+        quoted(&format!("Authorization: Bearer {}", token)),
+        quoted("Accept: application/vnd.github+json"),
+        quoted(&gist.to_string())
+    ))?;
+
+    serde_json::from_str::<Value>(&response)
+        .ok()
+        .and_then(|gist| Some(gist.get("html_url")?.as_str()?.to_owned()))
+        .ok_or_else(|| failed("the gist has no URL in GitHub's answer".to_owned()))
+}
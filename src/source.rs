@@ -0,0 +1,139 @@
+use std::{borrow::Cow, ops::Range};
+
+use crate::position::{ceil_char_boundary, floor_char_boundary};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Identifies a file of a [`SourceMap`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SourceId(usize);
+
+/// The part of a file quoted by a diagnostic, owned or borrowed from the
+/// caller.
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SourceFile<'a> {
+    pub path: Cow<'a, str>,
+    /// Number of the first line of `contents` in the file
+    pub first_lineno: usize,
+    pub contents: Cow<'a, str>,
+}
+
+impl SourceFile<'_> {
+    /// The index of the line holding the byte at `offset`, and the byte range
+    /// of that line in the contents.
+    pub fn line_at(&self, offset: usize) -> (usize, Range<usize>) {
+        let before = &self.contents[..offset];
+        let index = before.matches('\n').count();
+        let start = before.rfind('\n').map_or(0, |newline| newline + 1);
+        let end = self.contents[offset..]
+            .find('\n')
+            .map_or(self.contents.len(), |newline| offset + newline);
+
+        (index, start..end)
+    }
+
+    pub fn lines(&self) -> impl Iterator<Item = &str> {
+        self.contents.split('\n')
+    }
+}
+
+/// Holds the contents of the files diagnostics point into. Contents
+/// borrowed from the caller are shared by the clones of the map, so that
+/// the diagnostics of a same file do not copy it.
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SourceMap<'a> {
+    files: Vec<SourceFile<'a>>,
+}
+
+impl<'a> SourceMap<'a> {
+    pub fn add(
+        &mut self,
+        path: impl Into<Cow<'a, str>>,
+        first_lineno: usize,
+        contents: impl Into<Cow<'a, str>>,
+    ) -> SourceId {
+        self.files.push(SourceFile {
+            path: path.into(),
+            first_lineno,
+            contents: contents.into(),
+        });
+        SourceId(self.files.len() - 1)
+    }
+
+    /// Panics if `id` comes from another map.
+    pub fn get(&self, id: SourceId) -> &SourceFile<'a> {
+        &self.files[id.0]
+    }
+
+    /// Whether `span` points into one of the files of the map, between
+    /// characters.
+    pub fn contains(&self, span: &Span) -> bool {
+        self.files.get(span.file.0).is_some_and(|file| {
+            span.range.start <= span.range.end && file.contents.get(span.range.clone()).is_some()
+        })
+    }
+
+    /// The map with copies of the contents it borrows.
+    pub fn into_owned(self) -> SourceMap<'static> {
+        SourceMap {
+            files: self
+                .files
+                .into_iter()
+                .map(|file| SourceFile {
+                    path: Cow::Owned(file.path.into_owned()),
+                    first_lineno: file.first_lineno,
+                    contents: Cow::Owned(file.contents.into_owned()),
+                })
+                .collect(),
+        }
+    }
+
+    /// A map holding `line` between its context lines, and the span of
+    /// `range` in `line`, widened to whole characters. There must be fewer
+    /// lines of `context_before` than `lineno`, for the first one to be
+    /// numbered from 1.
+    pub fn quote(
+        path: String,
+        lineno: usize,
+        line: &str,
+        range: Range<usize>,
+        context_before: &[String],
+        context_after: &[String],
+    ) -> (SourceMap<'static>, Span) {
+        let mut contents = String::new();
+        for before in context_before {
+            contents.push_str(before);
+            contents.push('\n');
+        }
+        let line_start = contents.len();
+        contents.push_str(line);
+        let range = floor_char_boundary(line, range.start)..ceil_char_boundary(line, range.end);
+        for after in context_after {
+            contents.push('\n');
+            contents.push_str(after);
+        }
+
+        let mut sources = SourceMap::default();
+        let file = sources.add(path, lineno - context_before.len(), contents);
+
+        (
+            sources,
+            Span {
+                file,
+                range: line_start + range.start..line_start + range.end,
+            },
+        )
+    }
+}
+
+/// A byte range in one of the files of a [`SourceMap`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Span {
+    pub file: SourceId,
+    pub range: Range<usize>,
+}
@@ -0,0 +1,723 @@
+use std::{
+    fs,
+    ops::Range,
+    path::{Path, PathBuf},
+};
+
+use clap::ValueEnum;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use unicode_width::UnicodeWidthStr;
+
+use crate::{
+    position::{ceil_char_boundary, floor_char_boundary, PositionUnit},
+    style, template, Diagnostic, Label, RoostError, Severity, DEFAULT_ERRNUM, DEFAULT_LINENO,
+    DEFAULT_PATH,
+};
+
+fn default_lineno() -> usize {
+    DEFAULT_LINENO
+}
+
+fn default_path() -> String {
+    DEFAULT_PATH.to_owned()
+}
+
+fn default_errnum() -> usize {
+    DEFAULT_ERRNUM
+}
+
+/// Serialized form of a diagnostic, with the same fields as the prompts.
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct Spec {
+    #[serde(default, skip_serializing_if = "Severity::is_error")]
+    pub severity: Severity,
+    pub summary: String,
+    pub line: String,
+    #[serde(default)]
+    pub message: String,
+    pub spos: usize,
+    pub epos: usize,
+    /// Other spans of the line, with their own labels
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub labels: Vec<LabelSpec>,
+    #[serde(default = "default_lineno")]
+    pub lineno: usize,
+    #[serde(default = "default_path")]
+    pub path: String,
+    #[serde(default = "default_errnum")]
+    pub errnum: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fixit: Option<String>,
+    /// Show the fix-it as a diff of the line
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub fixit_diff: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub context_before: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub context_after: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub related: Vec<RelatedSpec>,
+    /// Remarks after the quoted code, `= note:` in rustc's style
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub notes: Vec<String>,
+    /// Advice after the notes, `= help:` in rustc's style
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub helps: Vec<String>,
+}
+
+/// A secondary span of the line of a spec.
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct LabelSpec {
+    pub spos: usize,
+    pub epos: usize,
+    #[serde(default)]
+    pub message: String,
+    /// A color name, 256-color index or `#rrggbb` code
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+}
+
+impl From<&Label> for LabelSpec {
+    fn from(label: &Label) -> Self {
+        LabelSpec {
+            spos: label.range.start,
+            epos: label.range.end,
+            message: label.message.clone(),
+            color: label.color.map(style::format_color),
+        }
+    }
+}
+
+impl TryFrom<LabelSpec> for Label {
+    type Error = RoostError;
+
+    fn try_from(spec: LabelSpec) -> Result<Self, Self::Error> {
+        let color = spec
+            .color
+            .map(|color| style::parse_color(&color))
+            .transpose()
+            .map_err(|details| RoostError::ValueError { details })?;
+
+        Ok(Label {
+            range: spec.spos..spec.epos,
+            message: spec.message,
+            color,
+        })
+    }
+}
+
+/// Another location quoted by a spec, under a `note:`.
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct RelatedSpec {
+    pub note: String,
+    pub line: String,
+    #[serde(default)]
+    pub message: String,
+    pub spos: usize,
+    pub epos: usize,
+    #[serde(default = "default_lineno")]
+    pub lineno: usize,
+    /// The path of the diagnostic when not given
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+}
+
+/// Several specs, as `[[diagnostics]]` tables or a JSON array.
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct Specs {
+    pub diagnostics: Vec<Spec>,
+}
+
+fn to_owned(lines: Vec<&str>) -> Vec<String> {
+    lines.into_iter().map(str::to_owned).collect()
+}
+
+impl From<&Diagnostic<'_>> for Spec {
+    fn from(err: &Diagnostic) -> Self {
+        Spec {
+            severity: err.severity,
+            summary: err.summary.to_string(),
+            line: err.line().to_owned(),
+            message: err.message.to_string(),
+            spos: err.spos(),
+            epos: err.epos(),
+            labels: err.labels.iter().map(LabelSpec::from).collect(),
+            lineno: err.lineno(),
+            path: err.path().to_owned(),
+            errnum: err.errnum,
+            fixit: err.fixit.clone(),
+            fixit_diff: err.fixit_diff,
+            context_before: to_owned(err.context_before()),
+            context_after: to_owned(err.context_after()),
+            related: err
+                .related
+                .iter()
+                .map(|related| RelatedSpec {
+                    note: related.summary.to_string(),
+                    line: related.line().to_owned(),
+                    message: related.message.to_string(),
+                    spos: related.spos(),
+                    epos: related.epos(),
+                    lineno: related.lineno(),
+                    path: Some(related.path())
+                        .filter(|path| *path != err.path())
+                        .map(str::to_owned),
+                })
+                .collect(),
+            notes: err.notes.clone(),
+            helps: err.helps.clone(),
+        }
+    }
+}
+
+impl TryFrom<Spec> for Diagnostic<'static> {
+    type Error = RoostError;
+
+    fn try_from(spec: Spec) -> Result<Self, Self::Error> {
+        let mut builder = Diagnostic::builder();
+        for note in spec.notes {
+            builder = builder.note(note);
+        }
+        for help in spec.helps {
+            builder = builder.help(help);
+        }
+        for label in spec.labels {
+            builder = builder.label(label.try_into()?);
+        }
+        for related in spec.related {
+            builder = builder.related(
+                Diagnostic::builder()
+                    .summary(related.note)
+                    .line(related.line)
+                    .span(related.spos..related.epos)
+                    .message(related.message)
+                    .lineno(related.lineno)
+                    .path(related.path.unwrap_or_else(|| spec.path.clone()))
+                    .build()?,
+            );
+        }
+
+        let builder = builder
+            .severity(spec.severity)
+            .summary(spec.summary)
+            .line(spec.line)
+            .span(spec.spos..spec.epos)
+            .message(spec.message)
+            .lineno(spec.lineno)
+            .path(spec.path)
+            .code(spec.errnum)
+            .fixit_diff(spec.fixit_diff)
+            .context_before(spec.context_before)
+            .context_after(spec.context_after);
+
+        match spec.fixit {
+            Some(fixit) => builder.fixit(fixit),
+            None => builder,
+        }
+        .build()
+    }
+}
+
+/// Any of the accepted spec layouts: one spec, an array, or a
+/// `diagnostics` table.
+#[derive(Deserialize, JsonSchema)]
+#[serde(untagged)]
+#[schemars(title = "roost spec")]
+pub enum SpecInput {
+    One(Box<Spec>),
+    Many(Vec<Spec>),
+    Specs(Specs),
+}
+
+impl SpecInput {
+    pub fn into_specs(self) -> Vec<Spec> {
+        match self {
+            SpecInput::One(spec) => vec![*spec],
+            SpecInput::Many(specs) | SpecInput::Specs(Specs { diagnostics: specs }) => specs,
+        }
+    }
+
+    pub fn into_errors(self) -> Result<Vec<Diagnostic<'static>>, RoostError> {
+        self.into_specs()
+            .into_iter()
+            .map(Diagnostic::try_from)
+            .collect()
+    }
+}
+
+/// What the errors of a spec not read from a file call it.
+const UNNAMED: &str = "<spec>";
+
+/// The error of a spec that could not be read: `details` about `field`,
+/// such as `diagnostics[1].spos`, and the line of `text` at `range` quoted
+/// under its location in `name`, as rustc would.
+/// The error for a mistake at `range` in `text`, whose first line is the
+/// line `first_lineno` of the spec named `name`.
+fn invalid_spec(
+    text: &str,
+    name: &str,
+    first_lineno: usize,
+    field: Option<String>,
+    details: &str,
+    range: Option<Range<usize>>,
+) -> RoostError {
+    let mut message = match field.filter(|field| field != ".") {
+        Some(field) => format!("invalid spec: `{}`: {}", field, details),
+        None => format!("invalid spec: {}", details),
+    };
+    if let Some(range) = range.filter(|range| range.start <= text.len()) {
+        let start = text[..range.start].rfind('\n').map_or(0, |index| index + 1);
+        let line = text[start..].lines().next().unwrap_or_default();
+        let lineno = first_lineno + text[..start].matches('\n').count();
+        // The span may start past the text `lines` keeps, such as on the
+        // `\r` of a CRLF line ending
+        let column = floor_char_boundary(line, range.start - start);
+        let end = ceil_char_boundary(
+            line,
+            range.end.saturating_sub(start).clamp(column, line.len()),
+        );
+        let gutter = " ".repeat(lineno.to_string().len());
+        message.push_str(&format!(
+            "\n{}--> {}:{}:{}\n{} |\n{} | {}\n{} | {}{}",
+            gutter,
+            name,
+            lineno,
+            line[..column].chars().count() + 1,
+            gutter,
+            lineno,
+            line,
+            gutter,
+            " ".repeat(line[..column].width()),
+            "^".repeat(line[column..end].width().max(1)),
+        ));
+    }
+
+    RoostError::ValueError { details: message }
+}
+
+/// Reads the spec of `text`, a TOML document or a JSON value, named `name`
+/// in the errors. The layout is told by the top of the document, so that a
+/// mistake in a spec is reported at the field it is in.
+pub fn parse(text: &str, format: SpecFormat, name: &str) -> Result<SpecInput, RoostError> {
+    parse_from(text, format, name, 1)
+}
+
+/// Reads the spec of `text` as `parse` does, `text` starting at the line
+/// `first_lineno` of the spec.
+fn parse_from(
+    text: &str,
+    format: SpecFormat,
+    name: &str,
+    first_lineno: usize,
+) -> Result<SpecInput, RoostError> {
+    match format {
+        SpecFormat::Toml => {
+            let table = toml::from_str::<toml::Table>(text).map_err(|err| {
+                invalid_spec(text, name, first_lineno, None, err.message(), err.span())
+            })?;
+            let failed = |err: serde_path_to_error::Error<toml::de::Error>| {
+                let field = err.path().to_string();
+                let err = err.into_inner();
+                invalid_spec(
+                    text,
+                    name,
+                    first_lineno,
+                    Some(field),
+                    err.message(),
+                    err.span(),
+                )
+            };
+            let deserializer = || {
+                toml::Deserializer::parse(text).map_err(|err| {
+                    invalid_spec(text, name, first_lineno, None, err.message(), err.span())
+                })
+            };
+            match table.contains_key("diagnostics") {
+                true => serde_path_to_error::deserialize(deserializer()?)
+                    .map(SpecInput::Specs)
+                    .map_err(failed),
+                false => serde_path_to_error::deserialize(deserializer()?)
+                    .map(SpecInput::One)
+                    .map_err(failed),
+            }
+        }
+        SpecFormat::Json | SpecFormat::NdjsonIn => {
+            let at = |err: &serde_json::Error| {
+                let start: usize = (text.split_inclusive('\n'))
+                    .take(err.line().saturating_sub(1))
+                    .map(str::len)
+                    .sum();
+                let offset = (start + err.column().saturating_sub(1)).min(text.len());
+                Some(offset..offset + 1)
+            };
+            let value = serde_json::from_str::<serde_json::Value>(text).map_err(|err| {
+                invalid_spec(
+                    text,
+                    name,
+                    first_lineno,
+                    None,
+                    &json_message(&err),
+                    at(&err),
+                )
+            })?;
+            let failed = |err: serde_path_to_error::Error<serde_json::Error>| {
+                let field = err.path().to_string();
+                let err = err.into_inner();
+                invalid_spec(
+                    text,
+                    name,
+                    first_lineno,
+                    Some(field),
+                    &json_message(&err),
+                    at(&err),
+                )
+            };
+            let deserializer = &mut serde_json::Deserializer::from_str(text);
+            match &value {
+                serde_json::Value::Array(_) => serde_path_to_error::deserialize(deserializer)
+                    .map(SpecInput::Many)
+                    .map_err(failed),
+                serde_json::Value::Object(object) if object.contains_key("diagnostics") => {
+                    serde_path_to_error::deserialize(deserializer)
+                        .map(SpecInput::Specs)
+                        .map_err(failed)
+                }
+                _ => serde_path_to_error::deserialize(deserializer)
+                    .map(SpecInput::One)
+                    .map_err(failed),
+            }
+        }
+    }
+}
+
+/// The message of `err` without the location serde_json ends it with, which
+/// the quoted line gives.
+fn json_message(err: &serde_json::Error) -> String {
+    let message = err.to_string();
+    let suffix = format!(" at line {} column {}", err.line(), err.column());
+    message
+        .strip_suffix(&suffix)
+        .map_or(message.clone(), str::to_owned)
+}
+
+/// The JSON Schema of the spec files, TOML ones being read as the same
+/// value.
+pub fn json_schema() -> String {
+    let schema = schemars::schema_for!(SpecInput);
+    serde_json::to_string_pretty(&schema).unwrap_or_default()
+}
+
+pub fn from_json(text: &str) -> Result<Vec<Diagnostic<'static>>, RoostError> {
+    parse(text, SpecFormat::Json, UNNAMED)?.into_errors()
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum SpecFormat {
+    Toml,
+    Json,
+    /// One JSON spec per line of stdin, each rendered as it arrives
+    NdjsonIn,
+}
+
+impl SpecFormat {
+    /// JSON for `.json` files, TOML otherwise.
+    pub fn guess(path: &Path) -> SpecFormat {
+        match path.extension().is_some_and(|ext| ext == "json") {
+            true => SpecFormat::Json,
+            false => SpecFormat::Toml,
+        }
+    }
+}
+
+/// The fields of a spec given on the command line, as written by
+/// [`Spec::to_command`].
+#[derive(clap::Args, Debug)]
+pub struct InlineSpec {
+    /// Render a warning rather than an error
+    #[arg(long, value_enum, default_value_t = Severity::Error, requires = "summary")]
+    pub severity: Severity,
+    /// Headline of the error, to render it without a spec file
+    #[arg(long, conflicts_with_all = ["spec", "template"])]
+    pub summary: Option<String>,
+    /// Source line the error points at
+    #[arg(long, requires = "summary")]
+    pub line: Option<String>,
+    /// Label of the span
+    #[arg(long, default_value = "", requires = "summary")]
+    pub message: String,
+    /// Byte offset of the start of the span in the line
+    #[arg(long, requires = "summary")]
+    pub spos: Option<usize>,
+    /// Byte offset of the end of the span in the line
+    #[arg(long, requires = "summary")]
+    pub epos: Option<usize>,
+    #[arg(long, default_value_t = DEFAULT_LINENO, requires = "summary")]
+    pub lineno: usize,
+    #[arg(long, default_value = DEFAULT_PATH, requires = "summary")]
+    pub path: String,
+    #[arg(long, default_value_t = DEFAULT_ERRNUM, requires = "summary")]
+    pub errnum: usize,
+    /// Replacement text suggested for the span
+    #[arg(long, requires = "summary")]
+    pub fixit: Option<String>,
+    /// Show the fix-it as the line before and after it, in a diff
+    #[arg(long, requires = "fixit")]
+    pub fixit_diff: bool,
+    /// Remark after the quoted code; can be given several times
+    #[arg(long = "note", value_name = "NOTE", requires = "summary")]
+    pub notes: Vec<String>,
+    /// Advice after the notes; can be given several times
+    #[arg(long = "help-note", value_name = "HELP", requires = "summary")]
+    pub helps: Vec<String>,
+}
+
+impl InlineSpec {
+    /// The diagnostic given by the flags, if `--summary` is, with `--spos`
+    /// and `--epos` counting `unit`.
+    pub fn to_diagnostic(
+        &self,
+        unit: PositionUnit,
+    ) -> Option<Result<Diagnostic<'static>, RoostError>> {
+        let summary = self.summary.as_ref()?;
+
+        let mut builder = Diagnostic::builder()
+            .severity(self.severity)
+            .summary(summary)
+            .message(&self.message)
+            .lineno(self.lineno)
+            .path(&self.path)
+            .code(self.errnum);
+        if let Some(line) = &self.line {
+            builder = builder.line(line);
+        }
+        if let (Some(spos), Some(epos)) = (self.spos, self.epos) {
+            let line = self.line.as_deref().unwrap_or_default();
+            builder = match unit {
+                PositionUnit::Bytes => builder.span(spos..epos),
+                _ => builder.span(unit.to_bytes(line, spos..epos)),
+            };
+        }
+        if let Some(fixit) = &self.fixit {
+            builder = builder.fixit(fixit).fixit_diff(self.fixit_diff);
+        }
+        for note in &self.notes {
+            builder = builder.note(note);
+        }
+        for help in &self.helps {
+            builder = builder.help(help);
+        }
+
+        Some(builder.build())
+    }
+}
+
+/// Quotes `arg` for POSIX shells, if it needs to.
+fn shell_quote(arg: &str) -> String {
+    let plain = |c: char| c.is_ascii_alphanumeric() || "-_./:@%+=,".contains(c);
+    match !arg.is_empty() && arg.chars().all(plain) {
+        true => arg.to_owned(),
+        false => format!("'{}'", arg.replace('\'', "'\\''")),
+    }
+}
+
+impl Spec {
+    /// A `roost render` command rendering this spec, leaving out the fields
+    /// that have their default value.
+    pub fn to_command(&self) -> String {
+        let mut args = vec![
+            ("summary", self.summary.clone()),
+            ("line", self.line.clone()),
+            ("spos", self.spos.to_string()),
+            ("epos", self.epos.to_string()),
+        ];
+        if !self.severity.is_error() {
+            args.push(("severity", self.severity.to_string()));
+        }
+        if !self.message.is_empty() {
+            args.push(("message", self.message.clone()));
+        }
+        if self.lineno != DEFAULT_LINENO {
+            args.push(("lineno", self.lineno.to_string()));
+        }
+        if self.path != DEFAULT_PATH {
+            args.push(("path", self.path.clone()));
+        }
+        if self.errnum != DEFAULT_ERRNUM {
+            args.push(("errnum", self.errnum.to_string()));
+        }
+        if let Some(fixit) = &self.fixit {
+            args.push(("fixit", fixit.clone()));
+        }
+        for note in &self.notes {
+            args.push(("note", note.clone()));
+        }
+        for help in &self.helps {
+            args.push(("help-note", help.clone()));
+        }
+
+        let mut command = "roost render".to_owned();
+        for (flag, value) in args {
+            command.push_str(&format!(" --{} {}", flag, shell_quote(&value)));
+        }
+        if self.fixit_diff {
+            command.push_str(" --fixit-diff");
+        }
+        command
+    }
+}
+
+#[derive(clap::Args, Debug)]
+pub struct RenderArgs {
+    /// TOML or JSON spec file
+    #[arg(long, required_unless_present_any = ["from", "template", "summary"])]
+    pub spec: Option<PathBuf>,
+    /// Spec file with `{name}` placeholders, filled with `--var`
+    #[arg(long, conflicts_with_all = ["spec", "watch"])]
+    pub template: Option<PathBuf>,
+    /// Value of a placeholder of the template
+    #[arg(long = "var", value_name = "NAME=VALUE", value_parser = template::parse_var)]
+    pub vars: Vec<(String, String)>,
+    /// Format of the spec, guessed from its extension by default
+    #[arg(long, value_enum)]
+    pub from: Option<SpecFormat>,
+    /// Render again whenever the spec file changes
+    #[arg(long)]
+    pub watch: bool,
+    #[command(flatten)]
+    pub inline: InlineSpec,
+}
+
+pub fn from_toml(text: &str) -> Result<Vec<Diagnostic<'static>>, RoostError> {
+    parse(text, SpecFormat::Toml, UNNAMED)?.into_errors()
+}
+
+pub fn from_file(
+    path: &Path,
+    format: Option<SpecFormat>,
+) -> Result<Vec<Diagnostic<'static>>, RoostError> {
+    let text = fs::read_to_string(path).map_err(|err| RoostError::ValueError {
+        details: format!("could not read {}: {}", path.display(), err),
+    })?;
+
+    from_text(
+        &text,
+        format.unwrap_or(SpecFormat::guess(path)),
+        &path.display().to_string(),
+    )
+}
+
+/// The diagnostics of the specs of `text`, in `format` and named `name` in
+/// the errors.
+pub fn from_text(
+    text: &str,
+    format: SpecFormat,
+    name: &str,
+) -> Result<Vec<Diagnostic<'static>>, RoostError> {
+    match format {
+        // Each line on its own, the errors giving its line number
+        SpecFormat::NdjsonIn => (text.lines().enumerate())
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(index, line)| parse_from(line, SpecFormat::Json, name, index + 1)?.into_errors())
+            .collect::<Result<Vec<_>, _>>()
+            .map(|errs| errs.into_iter().flatten().collect()),
+        format => parse(text, format, name)?.into_errors(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn details(result: Result<SpecInput, RoostError>) -> String {
+        match result {
+            Err(RoostError::ValueError { details }) => details,
+            Err(err) => panic!("unexpected error {:?}", err),
+            Ok(_) => panic!("the spec was read"),
+        }
+    }
+
+    #[test]
+    fn parse_toml_spec() {
+        let text = "summary = \"mismatched types\"\nline = \"let x: u8 = 'a';\"\nspos = 12\nepos = 15\nlineno = 4\nnotes = [\"a note\"]\n";
+        let specs = parse(text, SpecFormat::Toml, "diag.toml")
+            .unwrap()
+            .into_specs();
+
+        assert_eq!(specs.len(), 1);
+        assert_eq!(specs[0].summary, "mismatched types");
+        assert_eq!((specs[0].spos, specs[0].epos), (12, 15));
+        assert_eq!(specs[0].lineno, 4);
+        assert_eq!(specs[0].path, DEFAULT_PATH);
+        assert_eq!(specs[0].errnum, DEFAULT_ERRNUM);
+        assert_eq!(specs[0].notes, ["a note"]);
+    }
+
+    #[test]
+    fn parse_toml_diagnostics() {
+        let text = "[[diagnostics]]\nsummary = \"a\"\nline = \"x\"\nspos = 0\nepos = 1\n\n[[diagnostics]]\nsummary = \"b\"\nline = \"y\"\nspos = 0\nepos = 1\nseverity = \"warning\"\n";
+        let errs = parse(text, SpecFormat::Toml, "diag.toml")
+            .unwrap()
+            .into_errors()
+            .unwrap();
+
+        assert_eq!(errs.len(), 2);
+        assert_eq!(errs[1].summary, "b");
+        assert_eq!(errs[1].severity, Severity::Warning);
+    }
+
+    #[test]
+    fn parse_json_specs() {
+        let text = r#"[{"summary": "a", "line": "x", "spos": 0, "epos": 1}, {"summary": "b", "line": "yz", "spos": 1, "epos": 2}]"#;
+        let errs = parse(text, SpecFormat::Json, "diag.json")
+            .unwrap()
+            .into_errors()
+            .unwrap();
+
+        assert_eq!(errs.len(), 2);
+        assert_eq!((errs[1].line(), errs[1].spos()), ("yz", 1));
+    }
+
+    #[test]
+    fn parse_reports_the_field_and_its_line() {
+        let text = "summary = \"a\"\nline = \"x\"\nspos = \"zero\"\nepos = 1\n";
+        let details = details(parse(text, SpecFormat::Toml, "diag.toml"));
+
+        assert!(details.starts_with("invalid spec: `spos`:"), "{}", details);
+        assert!(details.contains("--> diag.toml:3:8"), "{}", details);
+        assert!(details.contains("3 | spos = \"zero\""), "{}", details);
+    }
+
+    #[test]
+    fn parse_refuses_unknown_fields() {
+        let text = r#"{"summary": "a", "line": "x", "spos": 0, "epos": 1, "typo": 2}"#;
+        let details = details(parse(text, SpecFormat::Json, "diag.json"));
+
+        assert!(details.contains("typo"), "{}", details);
+    }
+
+    #[test]
+    fn parse_quotes_crlf_lines() {
+        // The error may point at the `\r` the quoted line leaves out
+        let text = "summary = \"a\"\r\nline = \"x\"\r\nspos = 0\r\nepos = \r\n";
+        let details = details(parse(text, SpecFormat::Toml, "diag.toml"));
+
+        assert!(details.contains("--> diag.toml:4:"), "{}", details);
+    }
+
+    #[test]
+    fn ndjson_errors_give_the_line_of_the_record() {
+        let text = "{\"summary\": \"a\", \"line\": \"x\", \"spos\": 0, \"epos\": 1}\n\n{\"summary\": \"b\", \"line\": \"y\", \"spos\": \"zero\", \"epos\": 1}\n";
+        let details = match from_text(text, SpecFormat::NdjsonIn, "diag.ndjson") {
+            Err(RoostError::ValueError { details }) => details,
+            result => panic!("unexpected result {:?}", result.map(|errs| errs.len())),
+        };
+
+        assert!(details.starts_with("invalid spec: `spos`:"), "{}", details);
+        assert!(details.contains("--> diag.ndjson:3:"), "{}", details);
+        assert!(details.contains("3 | {\"summary\": \"b\""), "{}", details);
+    }
+}
@@ -0,0 +1,251 @@
+use std::{
+    env,
+    fmt::Write,
+    sync::atomic::{AtomicU8, Ordering},
+};
+
+use serde::Deserialize;
+
+use crate::ansi::{self, Color, Rgb};
+
+/// How many colors the terminal can show.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ColorDepth {
+    Basic,
+    Ansi256,
+    TrueColor,
+}
+
+/// Kept at true color unless roost prints to a terminal, since the images
+/// and web pages draw every color.
+static DEPTH: AtomicU8 = AtomicU8::new(ColorDepth::TrueColor as u8);
+
+impl ColorDepth {
+    /// Guesses the depth of the terminal from `COLORTERM` and `TERM`.
+    pub fn detect() -> Self {
+        let var = |name: &str| env::var(name).unwrap_or_default();
+
+        match (var("COLORTERM").as_str(), var("TERM")) {
+            ("truecolor" | "24bit", _) => ColorDepth::TrueColor,
+            (_, term) if term.ends_with("-direct") => ColorDepth::TrueColor,
+            (_, term) if term.contains("256color") => ColorDepth::Ansi256,
+            _ => ColorDepth::Basic,
+        }
+    }
+}
+
+pub fn set_depth(depth: ColorDepth) {
+    DEPTH.store(depth as u8, Ordering::Relaxed);
+}
+
+fn depth() -> ColorDepth {
+    match DEPTH.load(Ordering::Relaxed) {
+        0 => ColorDepth::Basic,
+        1 => ColorDepth::Ansi256,
+        _ => ColorDepth::TrueColor,
+    }
+}
+
+fn distance(a: Rgb, b: Rgb) -> u32 {
+    a.iter()
+        .zip(b)
+        .map(|(&a, b)| (a as i32 - b as i32).pow(2) as u32)
+        .sum()
+}
+
+/// The closest color among `candidates` to `rgb`.
+fn nearest(rgb: Rgb, candidates: impl Iterator<Item = Color>) -> Color {
+    candidates
+        .min_by_key(|&candidate| distance(rgb, ansi::rgb(Some(candidate))))
+        .expect("there is always a candidate")
+}
+
+/// Approximates `color` with the colors available at `depth`.
+fn downgrade(color: Color, depth: ColorDepth) -> Color {
+    let rgb = ansi::rgb(Some(color));
+
+    match (color, depth) {
+        (Color::Basic(_) | Color::Bright(_), _) => color,
+        (Color::Fixed(index), ColorDepth::Basic) if index < 8 => Color::Basic(index),
+        (Color::Fixed(index), ColorDepth::Basic) if index < 16 => Color::Bright(index - 8),
+        (Color::Fixed(_) | Color::Rgb(_), ColorDepth::Basic) => nearest(
+            rgb,
+            (0..8).map(Color::Basic).chain((0..8).map(Color::Bright)),
+        ),
+        (Color::Rgb(_), ColorDepth::Ansi256) => nearest(rgb, (16..=255).map(Color::Fixed)),
+        (Color::Fixed(_), _) | (Color::Rgb(_), ColorDepth::TrueColor) => color,
+    }
+}
+
+/// Writes the SGR sequence setting the text (`base` 3) or the underline
+/// (`base` 5) color. Underline colors have no short form.
+fn write_sgr(out: &mut String, color: Color, base: u8) {
+    let _ = match (downgrade(color, depth()), base) {
+        (Color::Basic(code), 3) => write!(out, "\x1b[3{}m", code),
+        (Color::Bright(code), 3) => write!(out, "\x1b[9{}m", code),
+        (Color::Basic(code), _) => write!(out, "\x1b[{}8;5;{}m", base, code),
+        (Color::Bright(code), _) => write!(out, "\x1b[{}8;5;{}m", base, code + 8),
+        (Color::Fixed(index), _) => write!(out, "\x1b[{}8;5;{}m", base, index),
+        (Color::Rgb([r, g, b]), _) => write!(out, "\x1b[{}8;2;{};{};{}m", base, r, g, b),
+    };
+}
+
+const COLOR_NAMES: [&str; 8] = [
+    "black", "red", "green", "yellow", "blue", "magenta", "cyan", "white",
+];
+
+/// Parses a color name (`red`, `bright-red`), a 256-color index or a
+/// `#rrggbb` hex code.
+pub fn parse_color(value: &str) -> Result<Color, String> {
+    let name_index = |name: &str| COLOR_NAMES.iter().position(|&known| known == name);
+
+    if let Some(hex) = value.strip_prefix('#') {
+        let channel = |index: usize| {
+            hex.get(index..index + 2)
+                .and_then(|channel| u8::from_str_radix(channel, 16).ok())
+        };
+        return match (hex.len(), channel(0), channel(2), channel(4)) {
+            (6, Some(r), Some(g), Some(b)) => Ok(Color::Rgb([r, g, b])),
+            _ => Err(format!("invalid hex color '{}'", value)),
+        };
+    }
+    if let Ok(index) = value.parse() {
+        return Ok(Color::Fixed(index));
+    }
+    if let Some(index) = value.strip_prefix("bright-").and_then(name_index) {
+        return Ok(Color::Bright(index as u8));
+    }
+
+    name_index(value)
+        .map(|index| Color::Basic(index as u8))
+        .ok_or_else(|| format!("unknown color '{}'", value))
+}
+
+/// Writes `color` the way [`parse_color`] reads it.
+pub fn format_color(color: Color) -> String {
+    match color {
+        Color::Basic(index) => COLOR_NAMES[usize::from(index % 8)].to_owned(),
+        Color::Bright(index) => format!("bright-{}", COLOR_NAMES[usize::from(index % 8)]),
+        Color::Fixed(index) => index.to_string(),
+        Color::Rgb([r, g, b]) => format!("#{:02x}{:02x}{:02x}", r, g, b),
+    }
+}
+
+/// A style as written in a theme file: `{ fg = "red", bold = true }`.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct StyleSpec {
+    fg: Option<ColorSpec>,
+    #[serde(default)]
+    bold: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ColorSpec {
+    Index(u8),
+    Name(String),
+}
+
+impl TryFrom<StyleSpec> for Style {
+    type Error = String;
+
+    fn try_from(spec: StyleSpec) -> Result<Self, Self::Error> {
+        let fg = match spec.fg {
+            Some(ColorSpec::Index(index)) => Some(Color::Fixed(index)),
+            Some(ColorSpec::Name(name)) => Some(parse_color(&name)?),
+            None => None,
+        };
+
+        Ok(Style {
+            fg,
+            bold: spec.bold,
+            undercurl: false,
+        })
+    }
+}
+
+/// How a piece of text is printed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "StyleSpec")]
+pub struct Style {
+    pub fg: Option<Color>,
+    pub bold: bool,
+    pub undercurl: bool,
+}
+
+impl Style {
+    pub const fn new() -> Self {
+        Self {
+            fg: None,
+            bold: false,
+            undercurl: false,
+        }
+    }
+
+    pub const fn fg(self, color: Color) -> Self {
+        Self {
+            fg: Some(color),
+            ..self
+        }
+    }
+
+    pub const fn bold(self) -> Self {
+        Self { bold: true, ..self }
+    }
+
+    /// Adds a curly underline, of the color of the text.
+    pub const fn undercurl(self) -> Self {
+        Self {
+            undercurl: true,
+            ..self
+        }
+    }
+
+    /// Wraps `text` in the escape sequences of the style, downgrading its
+    /// color to what the terminal supports.
+    pub fn paint(&self, text: String) -> String {
+        if !ansi::enabled() || *self == Style::new() {
+            return text;
+        }
+
+        let mut painted = String::with_capacity(text.len() + 32);
+        self.paint_into(&mut painted, &text);
+        painted
+    }
+
+    /// Writes `text` to `out` in the escape sequences of the style, the
+    /// bold outside the underline and the underline outside the color,
+    /// without the intermediate strings of [`Style::paint`].
+    pub fn paint_into(&self, out: &mut String, text: &str) {
+        if !ansi::enabled() {
+            out.push_str(text);
+            return;
+        }
+
+        if self.bold {
+            out.push_str("\x1b[1m");
+        }
+        if self.undercurl {
+            out.push_str("\x1b[4:3m");
+            if let Some(color) = self.fg {
+                write_sgr(out, color, 5);
+            }
+        }
+        if let Some(color) = self.fg {
+            write_sgr(out, color, 3);
+        }
+        out.push_str(text);
+        if self.fg.is_some() {
+            out.push_str("\x1b[39m");
+        }
+        match (self.undercurl, self.fg) {
+            (true, Some(_)) => out.push_str("\x1b[59m\x1b[24m"),
+            (true, None) => out.push_str("\x1b[24m"),
+            (false, _) => {}
+        }
+        if self.bold {
+            out.push_str("\x1b[0m");
+        }
+    }
+}
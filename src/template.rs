@@ -0,0 +1,308 @@
+use std::{collections::BTreeMap, fs, path::Path};
+
+use crate::{
+    spec::{self, LabelSpec, RelatedSpec, Spec, SpecFormat},
+    Diagnostic, RoostError,
+};
+
+fn invalid(details: String) -> RoostError {
+    RoostError::ValueError { details }
+}
+
+/// Parses a `--var NAME=VALUE` argument.
+pub fn parse_var(raw: &str) -> Result<(String, String), RoostError> {
+    match raw.split_once('=') {
+        Some((name, value)) if is_identifier(name) => Ok((name.to_owned(), value.to_owned())),
+        _ => Err(invalid(format!("expected NAME=VALUE, got `{}`", raw))),
+    }
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Replaces the `{name}` placeholders of `text` with their value, and `{{`
+/// and `}}` with single braces. Braces around anything else than a name are
+/// kept, so that quoted code such as `{ x }` needs no escaping, but a name
+/// between braces is a placeholder even between backticks, as in the
+/// summary `` cannot find value `{ident}` ``.
+pub fn interpolate(text: &str, vars: &BTreeMap<String, String>) -> Result<String, RoostError> {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(index) = rest.find(['{', '}']) {
+        result.push_str(&rest[..index]);
+        rest = &rest[index..];
+
+        if rest.starts_with("{{") || rest.starts_with("}}") {
+            result.push_str(&rest[..1]);
+            rest = &rest[2..];
+            continue;
+        }
+
+        let placeholder = rest
+            .strip_prefix('{')
+            .and_then(|after| after.split_once('}'))
+            .map(|(name, _)| name)
+            .filter(|name| is_identifier(name));
+        match placeholder {
+            Some(name) => {
+                let value = vars
+                    .get(name)
+                    .ok_or_else(|| invalid(format!("missing --var {}=...", name)))?;
+                result.push_str(value);
+                rest = &rest[name.len() + 2..];
+            }
+            None => {
+                result.push_str(&rest[..1]);
+                rest = &rest[1..];
+            }
+        }
+    }
+    result.push_str(rest);
+
+    Ok(result)
+}
+
+fn interpolate_spec(spec: Spec, vars: &BTreeMap<String, String>) -> Result<Spec, RoostError> {
+    let lines = |lines: Vec<String>| -> Result<Vec<String>, RoostError> {
+        lines.iter().map(|line| interpolate(line, vars)).collect()
+    };
+
+    Ok(Spec {
+        summary: interpolate(&spec.summary, vars)?,
+        line: interpolate(&spec.line, vars)?,
+        message: interpolate(&spec.message, vars)?,
+        path: interpolate(&spec.path, vars)?,
+        fixit: spec
+            .fixit
+            .map(|fixit| interpolate(&fixit, vars))
+            .transpose()?,
+        context_before: lines(spec.context_before)?,
+        context_after: lines(spec.context_after)?,
+        notes: lines(spec.notes)?,
+        helps: lines(spec.helps)?,
+        labels: spec
+            .labels
+            .into_iter()
+            .map(|label| {
+                Ok(LabelSpec {
+                    message: interpolate(&label.message, vars)?,
+                    ..label
+                })
+            })
+            .collect::<Result<_, RoostError>>()?,
+        related: spec
+            .related
+            .into_iter()
+            .map(|related| {
+                Ok(RelatedSpec {
+                    note: interpolate(&related.note, vars)?,
+                    line: interpolate(&related.line, vars)?,
+                    message: interpolate(&related.message, vars)?,
+                    path: related
+                        .path
+                        .map(|path| interpolate(&path, vars))
+                        .transpose()?,
+                    ..related
+                })
+            })
+            .collect::<Result<_, RoostError>>()?,
+        ..spec
+    })
+}
+
+/// The end of the `{= expr}` script `text` starts with, past its closing
+/// brace, the braces of the expression being paired. The ones of its
+/// string and character literals are left out.
+#[cfg(feature = "scripting")]
+fn script_end(text: &str) -> Option<usize> {
+    let mut depth = 0;
+    let mut quote = None;
+    let mut escaped = false;
+    for (index, c) in text.char_indices() {
+        match (quote, c) {
+            (Some(_), _) if escaped => escaped = false,
+            // Backtick strings have no escapes
+            (Some('"' | '\''), '\\') => escaped = true,
+            (Some(open), _) if c == open => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'' | '`') => quote = Some(c),
+            (None, '{') => depth += 1,
+            (None, '}') if depth == 1 => return Some(index + 1),
+            (None, '}') => depth -= 1,
+            (None, _) => {}
+        }
+    }
+
+    None
+}
+
+#[cfg(feature = "scripting")]
+const MAX_SCRIPT_OPERATIONS: u64 = 1_000_000;
+#[cfg(feature = "scripting")]
+const MAX_SCRIPT_CALL_LEVELS: usize = 64;
+/// In bytes
+#[cfg(feature = "scripting")]
+const MAX_SCRIPT_STRING_SIZE: usize = 1 << 20;
+
+/// The Rhai engine of the scripts, with functions to generate values:
+/// `rand(min, max)` for a number of the range, `ident(len)` for an
+/// identifier, `today()` for the date and `now()` for the date and time.
+#[cfg(feature = "scripting")]
+fn script_engine() -> rhai::Engine {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    let now = || {
+        let millis = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_millis() as u64);
+        crate::export::ci_log::iso8601(millis)
+    };
+
+    // A template cannot hang roost or take all of its memory
+    let mut engine = rhai::Engine::new();
+    engine
+        .set_max_operations(MAX_SCRIPT_OPERATIONS)
+        .set_max_call_levels(MAX_SCRIPT_CALL_LEVELS)
+        .set_max_string_size(MAX_SCRIPT_STRING_SIZE)
+        .register_fn("rand", |min: i64, max: i64| {
+            fastrand::i64(min..=max.max(min))
+        })
+        .register_fn("ident", |len: i64| {
+            (0..len.max(1))
+                .map(|index| match index {
+                    0 => fastrand::lowercase(),
+                    _ => fastrand::alphanumeric().to_ascii_lowercase(),
+                })
+                .collect::<String>()
+        })
+        .register_fn("today", move || now()[..10].to_owned())
+        .register_fn("now", now);
+
+    engine
+}
+
+/// Replaces the `{= expr}` scripts of `text` with what they evaluate to,
+/// in order and with `vars` in scope as strings. The variables a script
+/// declares with `let` are in scope of the next ones. `{{=` is left as is
+/// for the placeholders to write a literal brace.
+#[cfg(feature = "scripting")]
+fn run_scripts(text: &str, vars: &BTreeMap<String, String>) -> Result<String, RoostError> {
+    let engine = script_engine();
+    let mut scope = rhai::Scope::new();
+    for (name, value) in vars {
+        scope.push(name.clone(), value.clone());
+    }
+
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(index) = rest.find("{=") {
+        if rest[..index].ends_with('{') {
+            result.push_str(&rest[..index + 2]);
+            rest = &rest[index + 2..];
+            continue;
+        }
+        result.push_str(&rest[..index]);
+        rest = &rest[index..];
+
+        let end = script_end(rest).ok_or_else(|| invalid("unclosed `{=` script".to_owned()))?;
+        let value = engine
+            .eval_with_scope::<rhai::Dynamic>(&mut scope, &rest[2..end - 1])
+            .map_err(|err| invalid(format!("script `{}` failed: {}", &rest[..end], err)))?;
+        result.push_str(&value.to_string());
+        rest = &rest[end..];
+    }
+    result.push_str(rest);
+
+    Ok(result)
+}
+
+/// Reads a spec file whose strings have placeholders, filling them with
+/// `vars`. The span is given in the line once filled. With the `scripting`
+/// feature, the `{= expr}` scripts of the file are evaluated first, so
+/// that they can compute the numbers of the spec as well as its strings.
+pub fn from_file(
+    path: &Path,
+    format: Option<SpecFormat>,
+    vars: &[(String, String)],
+) -> Result<Vec<Diagnostic<'static>>, RoostError> {
+    let text = fs::read_to_string(path)
+        .map_err(|err| invalid(format!("could not read {}: {}", path.display(), err)))?;
+    let vars: BTreeMap<String, String> = vars.iter().cloned().collect();
+    #[cfg(feature = "scripting")]
+    let text = run_scripts(&text, &vars)?;
+
+    let input = match format.unwrap_or(SpecFormat::guess(path)) {
+        SpecFormat::NdjsonIn => {
+            return Err(invalid("templates are TOML or JSON files".to_owned()));
+        }
+        format => spec::parse(&text, format, &path.display().to_string())?,
+    };
+
+    input
+        .into_specs()
+        .into_iter()
+        .map(|spec| interpolate_spec(spec, &vars).and_then(Diagnostic::try_from))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+        pairs
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn interpolate_fills_placeholders() {
+        let vars = vars(&[("ident", "foo"), ("n", "3")]);
+        assert_eq!(
+            interpolate("let {ident} = {n} + {n};", &vars).unwrap(),
+            "let foo = 3 + 3;"
+        );
+        // Even between backticks
+        assert_eq!(
+            interpolate("cannot find value `{ident}`", &vars).unwrap(),
+            "cannot find value `foo`"
+        );
+    }
+
+    #[test]
+    fn interpolate_keeps_other_braces() {
+        let vars = vars(&[("x", "1")]);
+        assert_eq!(
+            interpolate("fn f() { {x} }", &vars).unwrap(),
+            "fn f() { 1 }"
+        );
+        assert_eq!(interpolate("{{x}} {}", &vars).unwrap(), "{x} {}");
+        assert_eq!(interpolate("{ x } {1x} }", &vars).unwrap(), "{ x } {1x} }");
+    }
+
+    #[test]
+    fn interpolate_refuses_missing_vars() {
+        assert!(matches!(
+            interpolate("{missing}", &BTreeMap::new()),
+            Err(RoostError::ValueError { .. })
+        ));
+    }
+
+    #[cfg(feature = "scripting")]
+    #[test]
+    fn script_end_skips_braces_in_strings() {
+        assert_eq!(script_end("{= 1 + 2} rest"), Some(9));
+        assert_eq!(script_end("{= if x { 1 } else { 2 }}"), Some(25));
+        assert_eq!(script_end(r#"{= "}" } rest"#), Some(8));
+        assert_eq!(script_end(r#"{= "\"}" }"#), Some(10));
+        assert_eq!(script_end("{= `}` + '}' }"), Some(14));
+        assert_eq!(script_end(r#"{= "}"#), None);
+    }
+}
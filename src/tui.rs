@@ -0,0 +1,358 @@
+//! The `tui` subcommand: a form for the fields of the diagnostic next to a
+//! preview re-rendered on every keystroke.
+
+use std::io;
+
+use clap::ValueEnum;
+use ratatui::{
+    crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    layout::{Constraint, Layout, Position, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span, Text},
+    widgets::{Block, Paragraph, Wrap},
+    DefaultTerminal, Frame,
+};
+use unicode_width::UnicodeWidthStr;
+
+use roost::{
+    ansi,
+    position::{PositionRange, PositionUnit},
+    prompt::range_factory,
+    render::{self, theme::BUILTIN, Emitter, RenderOptions},
+    Diagnostic, RoostError, DEFAULT_ERRNUM, DEFAULT_LINENO, DEFAULT_PATH,
+};
+
+use crate::config::Config;
+
+const HELP: &str =
+    "↑/↓ field · F2 style · F3 theme · Esc save and quit · Ctrl-C quit without saving";
+
+/// The fields of the form, in order.
+#[derive(Clone, Copy)]
+enum Field {
+    Summary,
+    Line,
+    Span,
+    Message,
+    Lineno,
+    Path,
+    Errnum,
+    Fixit,
+}
+
+impl Field {
+    const ALL: [Field; 8] = [
+        Field::Summary,
+        Field::Line,
+        Field::Span,
+        Field::Message,
+        Field::Lineno,
+        Field::Path,
+        Field::Errnum,
+        Field::Fixit,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            Field::Summary => "summary",
+            Field::Line => "line",
+            Field::Span => "span",
+            Field::Message => "message",
+            Field::Lineno => "line number",
+            Field::Path => "path",
+            Field::Errnum => "error number",
+            Field::Fixit => "fixit",
+        }
+    }
+}
+
+/// The values typed in the form, and the rendering options picked with the
+/// function keys.
+struct Editor {
+    values: [String; Field::ALL.len()],
+    focus: usize,
+    options: RenderOptions,
+    /// Index of the built-in theme in use, `None` for the one of the style
+    theme: Option<usize>,
+    /// What the positions of the span count, and the first one
+    unit: PositionUnit,
+    base: usize,
+    status: Option<String>,
+}
+
+impl Editor {
+    fn new(options: RenderOptions, config: &Config, unit: PositionUnit, base: usize) -> Self {
+        let theme = options
+            .theme
+            .and_then(|theme| BUILTIN.iter().position(|(_, builtin)| *builtin == theme));
+        let mut values: [String; Field::ALL.len()] = Default::default();
+        values[Field::Span as usize] = format!("{}..{}", base, base + 1);
+        values[Field::Lineno as usize] = DEFAULT_LINENO.to_string();
+        values[Field::Path as usize] = config
+            .default_path
+            .clone()
+            .unwrap_or(DEFAULT_PATH.to_owned());
+        values[Field::Errnum as usize] =
+            config.default_errnum.unwrap_or(DEFAULT_ERRNUM).to_string();
+
+        Editor {
+            values,
+            focus: 0,
+            options,
+            theme,
+            unit,
+            base,
+            status: None,
+        }
+    }
+
+    fn value(&self, field: Field) -> &str {
+        &self.values[field as usize]
+    }
+
+    /// The diagnostic described by the form, the span counting the positions
+    /// as the prompts do.
+    fn diagnostic(&self) -> Result<Diagnostic<'static>, RoostError> {
+        let number = |field: Field| {
+            self.value(field)
+                .parse::<usize>()
+                .map_err(|_| RoostError::ValueError {
+                    details: format!("invalid {}", field.name()),
+                })
+        };
+        let line = self.value(Field::Line);
+        // Checked as at the span prompt, against the length of the line
+        let count = self.unit.from_byte(line, line.len());
+        let PositionRange(span) = range_factory(self.base, count)(self.value(Field::Span))?;
+        let span = self
+            .unit
+            .to_bytes(line, span.start - self.base..span.end - self.base);
+
+        let builder = Diagnostic::builder()
+            .summary(self.value(Field::Summary))
+            .line(line)
+            .span(span)
+            .message(self.value(Field::Message))
+            .lineno(number(Field::Lineno)?)
+            .path(self.value(Field::Path))
+            .code(number(Field::Errnum)?);
+
+        match self.value(Field::Fixit) {
+            "" => builder,
+            fixit => builder.fixit(fixit),
+        }
+        .build()
+    }
+
+    fn next_style(&mut self) {
+        let styles = Emitter::value_variants();
+        let index = styles
+            .iter()
+            .position(|style| *style == self.options.style)
+            .unwrap_or(0);
+        self.options.style = styles[(index + 1) % styles.len()];
+    }
+
+    fn next_theme(&mut self) {
+        self.theme = match self.theme {
+            None => Some(0),
+            Some(index) if index + 1 < BUILTIN.len() => Some(index + 1),
+            Some(_) => None,
+        };
+        self.options.theme = self.theme.map(|index| BUILTIN[index].1);
+    }
+
+    fn draw(&self, frame: &mut Frame) {
+        let [main, help] =
+            Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(frame.area());
+        let [form, preview] =
+            Layout::horizontal([Constraint::Percentage(40), Constraint::Percentage(60)])
+                .areas(main);
+
+        self.draw_form(frame, form);
+        self.draw_preview(frame, preview);
+
+        let status = self.status.as_deref().unwrap_or(HELP);
+        frame.render_widget(
+            Paragraph::new(status).style(Style::new().add_modifier(Modifier::DIM)),
+            help,
+        );
+    }
+
+    fn draw_form(&self, frame: &mut Frame, area: Rect) {
+        let label_width = Field::ALL
+            .iter()
+            .map(|field| field.name().len())
+            .max()
+            .unwrap_or(0);
+        let lines: Vec<Line> = Field::ALL
+            .iter()
+            .enumerate()
+            .map(|(index, field)| {
+                let label = format!("{:>width$}: ", field.name(), width = label_width);
+                let label_style = match index == self.focus {
+                    true => Style::new().add_modifier(Modifier::BOLD | Modifier::REVERSED),
+                    false => Style::new().add_modifier(Modifier::BOLD),
+                };
+                Line::from(vec![
+                    Span::styled(label, label_style),
+                    Span::raw(self.values[index].as_str()),
+                ])
+            })
+            .collect();
+
+        let block = Block::bordered().title(" diagnostic ");
+        let inner = block.inner(area);
+        frame.render_widget(Paragraph::new(lines).block(block), area);
+
+        let typed = self.values[self.focus].width();
+        frame.set_cursor_position(Position::new(
+            inner.x + (label_width + 2 + typed).min(usize::from(inner.width)) as u16,
+            inner.y + self.focus as u16,
+        ));
+    }
+
+    fn draw_preview(&self, frame: &mut Frame, area: Rect) {
+        let theme = match self.theme {
+            Some(index) => BUILTIN[index].0,
+            None => "style's own",
+        };
+        let title = format!(
+            " {} · {} theme ",
+            self.options
+                .style
+                .to_possible_value()
+                .map_or(String::new(), |value| value.get_name().to_owned()),
+            theme
+        );
+        let block = Block::bordered().title(title);
+        let inner = block.inner(area);
+
+        let text = match self.diagnostic() {
+            Ok(err) => {
+                let options = RenderOptions {
+                    max_width: Some(inner.width.max(16)),
+                    ..self.options.clone()
+                };
+                preview_text(&render::render(&options, &err))
+            }
+            Err(err) => Text::styled(err.to_string(), Style::new().fg(Color::Red)),
+        };
+
+        frame.render_widget(
+            Paragraph::new(text).block(block).wrap(Wrap { trim: false }),
+            area,
+        );
+    }
+
+    /// Handles a key, telling whether to leave with the diagnostic
+    /// (`Some(true)`) or without it (`Some(false)`).
+    fn handle(&mut self, key: KeyEvent) -> Option<bool> {
+        self.status = None;
+
+        match key.code {
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                return Some(false)
+            }
+            KeyCode::Esc => match self.diagnostic() {
+                Ok(_) => return Some(true),
+                Err(err) => self.status = Some(format!("cannot save: {}", err)),
+            },
+            KeyCode::Up | KeyCode::BackTab => {
+                self.focus = (self.focus + Field::ALL.len() - 1) % Field::ALL.len()
+            }
+            KeyCode::Down | KeyCode::Tab | KeyCode::Enter => {
+                self.focus = (self.focus + 1) % Field::ALL.len()
+            }
+            KeyCode::F(2) => self.next_style(),
+            KeyCode::F(3) => self.next_theme(),
+            KeyCode::Backspace => {
+                self.values[self.focus].pop();
+            }
+            KeyCode::Char(c) => self.values[self.focus].push(c),
+            _ => {}
+        }
+
+        None
+    }
+}
+
+/// Turns the escape sequences of the rendered diagnostic into styled text.
+fn preview_text(rendered: &str) -> Text<'static> {
+    let color = |color: ansi::Color| match color {
+        ansi::Color::Basic(index) | ansi::Color::Fixed(index) => Color::Indexed(index),
+        ansi::Color::Bright(index) => Color::Indexed(index + 8),
+        ansi::Color::Rgb([r, g, b]) => Color::Rgb(r, g, b),
+    };
+
+    ansi::parse(rendered)
+        .iter()
+        .map(|cells| {
+            Line::from(
+                ansi::runs(cells)
+                    .into_iter()
+                    .map(|(attrs, text)| {
+                        let mut style = Style::new();
+                        if let Some(fg) = attrs.fg {
+                            style = style.fg(color(fg));
+                        }
+                        if attrs.bold {
+                            style = style.add_modifier(Modifier::BOLD);
+                        }
+                        if attrs.underline {
+                            style = style.add_modifier(Modifier::UNDERLINED);
+                        }
+                        Span::styled(text, style)
+                    })
+                    .collect::<Vec<_>>(),
+            )
+        })
+        .collect::<Vec<_>>()
+        .into()
+}
+
+fn edit(terminal: &mut DefaultTerminal, editor: &mut Editor) -> io::Result<bool> {
+    loop {
+        terminal.draw(|frame| editor.draw(frame))?;
+
+        if let Event::Key(key) = event::read()? {
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            if let Some(save) = editor.handle(key) {
+                return Ok(save);
+            }
+        }
+    }
+}
+
+/// Edits a diagnostic until it is saved, switching `options` to the style
+/// and theme picked on the way. The span counts `unit`s from `base`.
+pub fn run(
+    options: &mut RenderOptions,
+    config: &Config,
+    unit: PositionUnit,
+    base: usize,
+) -> Result<Diagnostic<'static>, RoostError> {
+    let mut editor = Editor::new(options.clone(), config, unit, base);
+
+    let mut terminal = ratatui::try_init().map_err(|err| RoostError::OutputError {
+        details: format!("could not use the terminal: {}", err),
+    })?;
+    let saved = edit(&mut terminal, &mut editor);
+    ratatui::restore();
+
+    match saved {
+        Ok(true) => {
+            options.style = editor.options.style;
+            options.theme = editor.options.theme;
+            editor.diagnostic()
+        }
+        Ok(false) => Err(RoostError::AbortError {
+            details: "the diagnostic was not saved".to_owned(),
+        }),
+        Err(err) => Err(RoostError::OutputError {
+            details: format!("could not use the terminal: {}", err),
+        }),
+    }
+}
@@ -0,0 +1,150 @@
+//! The `ui-annotations` subcommand: the `//~ ERROR` comments with which
+//! compiletest checks the diagnostics of a UI test.
+
+use std::{fs, path::PathBuf};
+
+use roost::{spec, Diagnostic, RoostError, Severity};
+
+#[derive(clap::Args, Debug)]
+pub struct UiAnnotationsArgs {
+    /// TOML or JSON spec file of the diagnostics
+    #[arg(long, value_name = "FILE")]
+    pub spec: PathBuf,
+    /// Source file of the test, written back with the annotations under
+    /// the lines they point at
+    #[arg(long, value_name = "FILE")]
+    pub source: Option<PathBuf>,
+}
+
+/// What an annotation expects, such as `ERROR` for the headline of an error
+/// and `NOTE` for a label.
+struct Annotation {
+    lineno: usize,
+    kind: &'static str,
+    message: String,
+}
+
+/// The annotations of `err`: its headline, then the labels of its line, its
+/// notes and helps, and the notes quoting the same file.
+fn annotations(err: &Diagnostic) -> Vec<Annotation> {
+    let first_line = |text: &str| text.lines().next().unwrap_or_default().to_owned();
+    let mut annotations = vec![Annotation {
+        lineno: err.lineno(),
+        kind: match err.severity {
+            Severity::Error => "ERROR",
+            Severity::Warning => "WARN",
+        },
+        message: first_line(&err.summary),
+    }];
+
+    let labels = std::iter::once(&*err.message)
+        .chain(err.labels.iter().map(|label| label.message.as_str()))
+        .filter(|message| !message.is_empty());
+    for label in labels {
+        annotations.push(Annotation {
+            lineno: err.lineno(),
+            kind: "NOTE",
+            message: first_line(label),
+        });
+    }
+    let footers = (err.notes.iter().map(|note| ("NOTE", note)))
+        .chain(err.helps.iter().map(|help| ("HELP", help)));
+    for (kind, text) in footers {
+        annotations.push(Annotation {
+            lineno: err.lineno(),
+            kind,
+            message: first_line(text),
+        });
+    }
+    for note in err.related.iter().filter(|note| note.path() == err.path()) {
+        annotations.push(Annotation {
+            lineno: note.lineno(),
+            kind: "NOTE",
+            message: first_line(&note.summary),
+        });
+    }
+
+    annotations
+}
+
+/// The annotations of `errs` as lines naming the line of the file each one
+/// goes at the end of, the ones after the first of a line with `//~|`.
+fn listed(errs: &[Diagnostic]) -> String {
+    let mut list = String::new();
+    for err in errs {
+        let mut previous = None;
+        for annotation in annotations(err) {
+            let marker = match previous == Some(annotation.lineno) {
+                true => "//~|",
+                false => "//~",
+            };
+            list.push_str(&format!(
+                "{}:{}: {} {} {}\n",
+                err.path(),
+                annotation.lineno,
+                marker,
+                annotation.kind,
+                annotation.message
+            ));
+            previous = Some(annotation.lineno);
+        }
+    }
+
+    list
+}
+
+/// `source` with the annotations of `errs` on lines of their own under the
+/// lines they point at, indented like them: `//~^` for the first one under
+/// a line and `//~|` for the next.
+fn inserted(source: &str, errs: &[Diagnostic]) -> Result<String, RoostError> {
+    let lines: Vec<&str> = source.lines().collect();
+    let mut by_line: Vec<Vec<Annotation>> = (0..lines.len()).map(|_| Vec::new()).collect();
+    for annotation in errs.iter().flat_map(annotations) {
+        let index = annotation
+            .lineno
+            .checked_sub(1)
+            .filter(|&index| index < lines.len());
+        let Some(index) = index else {
+            return Err(RoostError::ValueError {
+                details: format!(
+                    "line {} is past the end of the source, which has {} lines",
+                    annotation.lineno,
+                    lines.len()
+                ),
+            });
+        };
+        by_line[index].push(annotation);
+    }
+
+    let mut annotated = String::new();
+    for (line, annotations) in lines.iter().zip(by_line) {
+        annotated.push_str(line);
+        annotated.push('\n');
+        let indent = &line[..line.len() - line.trim_start().len()];
+        for (index, annotation) in annotations.iter().enumerate() {
+            annotated.push_str(&format!(
+                "{}{} {} {}\n",
+                indent,
+                if index == 0 { "//~^" } else { "//~|" },
+                annotation.kind,
+                annotation.message
+            ));
+        }
+    }
+
+    Ok(annotated)
+}
+
+/// The annotations of the diagnostics of the spec: inserted into a copy of
+/// the source when it is given, else listed.
+pub fn run(args: &UiAnnotationsArgs) -> Result<String, RoostError> {
+    let errs = spec::from_file(&args.spec, None)?;
+    let Some(path) = &args.source else {
+        return Ok(listed(&errs));
+    };
+
+    let source = fs::read_to_string(path).map_err(|err| RoostError::ValueError {
+        details: format!("could not read {}: {}", path.display(), err),
+    })?;
+    inserted(&source, &errs)
+}
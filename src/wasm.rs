@@ -0,0 +1,35 @@
+//! The JavaScript bindings of the WebAssembly build, for a web playground
+//! rendering the diagnostics with the same code as the command line.
+
+use clap::Parser;
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    export::{self, ExportOptions},
+    render::RenderOptions,
+    spec,
+};
+
+/// The options of `roost --format html` without any other flag.
+#[derive(Parser)]
+#[command(no_binary_name = true)]
+struct HtmlOptions {
+    #[command(flatten)]
+    export: ExportOptions,
+    #[command(flatten)]
+    render: RenderOptions,
+}
+
+/// The diagnostics of `spec_json`, a spec as read by `render --spec`, as
+/// the HTML `<pre>` block of `--format html`.
+#[wasm_bindgen]
+pub fn render_to_html(spec_json: &str) -> Result<String, JsError> {
+    let errs = spec::from_json(spec_json).map_err(|err| JsError::new(&err.to_string()))?;
+    let options = HtmlOptions::parse_from(["--format", "html"]);
+
+    let mut html = Vec::new();
+    export::write(&options.export, &options.render, &errs, &mut html)
+        .map_err(|err| JsError::new(&err.to_string()))?;
+
+    String::from_utf8(html).map_err(|err| JsError::new(&err.to_string()))
+}
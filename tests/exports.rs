@@ -0,0 +1,360 @@
+//! The output of each `--format`, compared to the snapshots of
+//! `tests/snapshots/` when it does not depend on the time.
+
+use roost::{
+    ansi::{self, ColorChoice},
+    export::{self, chat::ChatApp, ExportOptions, Format},
+    render::{Hyperlinks, RenderOptions},
+    Diagnostic, Label, Severity,
+};
+
+/// An error and a warning in two files, the warning with characters to
+/// escape in the markup formats.
+fn errs() -> Vec<Diagnostic<'static>> {
+    vec![
+        Diagnostic::builder()
+            .summary("mismatched types")
+            .line("    let x: u8 = \"a\";")
+            .span(16..19)
+            .message("expected `u8`, found `&str`")
+            .label(Label {
+                range: 11..13,
+                message: "expected due to this".to_owned(),
+                color: None,
+            })
+            .lineno(4)
+            .path("src/main.rs")
+            .code(308)
+            .note("`u8` is a number")
+            .build()
+            .unwrap(),
+        Diagnostic::builder()
+            .severity(Severity::Warning)
+            .summary("comparison is useless due to type limits")
+            .line("    if n < 0 && s == \"<&>\" {")
+            .span(7..12)
+            .message("`n` is unsigned")
+            .lineno(12)
+            .path("src/lib.rs")
+            .code(1001)
+            .build()
+            .unwrap(),
+    ]
+}
+
+fn options(format: Format) -> ExportOptions {
+    ExportOptions {
+        format,
+        color: ColorChoice::Never,
+        ansi_fence: false,
+        with_spec: false,
+        cast_command: "cargo build".to_owned(),
+        ci_log: false,
+        ci_command: "cargo build".to_owned(),
+        watermark: false,
+        chat_app: ChatApp::Discord,
+        package: None,
+        to_terminal: false,
+    }
+}
+
+/// The bytes written for `errs` with `options`, the diagnostics rendered
+/// without links within 80 columns.
+fn write(options: &ExportOptions, errs: &[Diagnostic]) -> Vec<u8> {
+    let render_options = RenderOptions {
+        hyperlinks: Hyperlinks::Never,
+        max_width: Some(80),
+        ..Default::default()
+    };
+    let mut output = Vec::new();
+    export::write(options, &render_options, errs, &mut output).unwrap();
+
+    output
+}
+
+fn export(options: &ExportOptions) -> String {
+    String::from_utf8(write(options, &errs())).unwrap()
+}
+
+#[test]
+fn png() {
+    let text = export(&options(Format::Text));
+    let png = write(&options(Format::Png), &errs());
+
+    let decoder = png::Decoder::new(std::io::Cursor::new(png));
+    let mut reader = decoder.read_info().unwrap();
+    let mut pixels = vec![0; reader.output_buffer_size().unwrap()];
+    let info = reader.next_frame(&mut pixels).unwrap();
+    let lines = ansi::parse(&text);
+    let columns = lines.iter().map(Vec::len).max().unwrap();
+
+    // Cells of 16 by 24 pixels, with 16 pixels of padding
+    assert_eq!(
+        (info.width, info.height),
+        (columns as u32 * 16 + 32, lines.len() as u32 * 24 + 32)
+    );
+    assert_eq!(pixels[..3], ansi::BACKGROUND);
+    assert!(pixels.chunks(3).any(|pixel| pixel != ansi::BACKGROUND));
+}
+
+#[test]
+fn markdown() {
+    insta::assert_snapshot!(export(&options(Format::Markdown)));
+}
+
+#[test]
+fn markdown_with_spec() {
+    let options = ExportOptions {
+        with_spec: true,
+        ..options(Format::Markdown)
+    };
+    insta::assert_snapshot!(export(&options));
+}
+
+#[test]
+fn markdown_ansi_fence() {
+    // The colors `--color auto` keeps with an `ansi` fence
+    let options = ExportOptions {
+        ansi_fence: true,
+        color: ColorChoice::Always,
+        ..options(Format::Markdown)
+    };
+    let markdown = export(&options);
+
+    assert!(markdown.starts_with("```ansi\n"), "{}", markdown);
+    assert!(markdown.contains("\x1b["), "{}", markdown);
+}
+
+#[test]
+fn short() {
+    insta::assert_snapshot!(export(&options(Format::Short)));
+}
+
+#[test]
+fn github() {
+    insta::assert_snapshot!(export(&options(Format::Github)));
+}
+
+#[test]
+fn gitlab_codequality() {
+    insta::assert_snapshot!(export(&options(Format::GitlabCodequality)));
+}
+
+#[test]
+fn junit() {
+    insta::assert_snapshot!(export(&options(Format::Junit)));
+}
+
+#[test]
+fn rdjson() {
+    insta::assert_snapshot!(export(&options(Format::Rdjson)));
+}
+
+/// The header of an asciicast recording, and the time and the text of
+/// each of its events.
+fn cast_events(cast: &str) -> (serde_json::Value, Vec<(f64, String)>) {
+    let mut lines = cast
+        .lines()
+        .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap());
+    let header = lines.next().unwrap();
+    let events = lines
+        .map(|event| {
+            assert_eq!(event[1], "o");
+            (
+                event[0].as_f64().unwrap(),
+                event[2].as_str().unwrap().to_owned(),
+            )
+        })
+        .collect();
+
+    (header, events)
+}
+
+#[test]
+fn asciicast() {
+    // The header has the time of the recording, so only the shape of the
+    // cast is checked
+    let (header, events) = cast_events(&export(&options(Format::Asciicast)));
+    let output: String = events.iter().map(|(_, text)| text.as_str()).collect();
+
+    assert_eq!(header["version"], 2);
+    assert_eq!(header["width"], 80);
+    assert!(header["timestamp"].is_u64());
+    assert!(events.windows(2).all(|pair| pair[0].0 <= pair[1].0));
+    assert!(ansi::strip(&output).starts_with("$ cargo build\r\n"));
+    assert!(output.contains("12 |     if n < 0 && s == \"<&>\" {\r\n"));
+    // The same text is always typed at the same pace
+    let (_, again) = cast_events(&export(&options(Format::Asciicast)));
+    assert_eq!(events, again);
+}
+
+#[test]
+fn chat() {
+    insta::assert_snapshot!(export(&options(Format::Chat)));
+}
+
+#[test]
+fn chat_slack() {
+    let slack = ExportOptions {
+        chat_app: ChatApp::Slack,
+        color: ColorChoice::Always,
+        ..options(Format::Chat)
+    };
+
+    // Slack shows the block as it is, without the colors
+    assert_eq!(
+        export(&slack),
+        export(&options(Format::Chat)).replacen("```ansi", "```", 1)
+    );
+}
+
+#[test]
+fn quickfix() {
+    insta::assert_snapshot!(export(&options(Format::Quickfix)));
+}
+
+#[test]
+fn teamcity() {
+    insta::assert_snapshot!(export(&options(Format::Teamcity)));
+}
+
+#[test]
+fn cargo_json() {
+    insta::assert_snapshot!(export(&options(Format::CargoJson)));
+}
+
+// Highlighting colors the quoted code too
+#[cfg(not(feature = "syntax-highlighting"))]
+#[test]
+fn html() {
+    let options = ExportOptions {
+        color: ColorChoice::Always,
+        ..options(Format::Html)
+    };
+    insta::assert_snapshot!(export(&options));
+}
+
+// Highlighting colors the quoted code too
+#[cfg(not(feature = "syntax-highlighting"))]
+#[test]
+fn svg() {
+    let options = ExportOptions {
+        color: ColorChoice::Always,
+        ..options(Format::Svg)
+    };
+    insta::assert_snapshot!(export(&options));
+}
+
+/// The lines of a CI log without their times, checking that the times go
+/// forward.
+fn log_lines(log: &str) -> Vec<&str> {
+    let (times, lines): (Vec<&str>, Vec<&str>) = log
+        .lines()
+        .map(|line| line.split_once(' ').unwrap())
+        .unzip();
+    assert!(times
+        .iter()
+        .all(|time| time.len() == 24 && time.ends_with('Z')));
+    assert!(times.windows(2).all(|pair| pair[0] <= pair[1]));
+
+    lines
+}
+
+#[test]
+fn ci_log() {
+    // The steps take random times, so only the lines are compared
+    let ci_log = ExportOptions {
+        ci_log: true,
+        ..options(Format::Text)
+    };
+    let log = export(&ci_log);
+    let lines = log_lines(&log);
+    let text = export(&options(Format::Text));
+    let build = lines
+        .iter()
+        .position(|line| *line == "##[group]Run cargo build")
+        .unwrap();
+
+    assert_eq!(lines[0], "##[group]Set up job");
+    assert_eq!(
+        lines[build + 1..build + 4].join("\n"),
+        "cargo build\nshell: /usr/bin/bash -e {0}\n##[endgroup]"
+    );
+    assert_eq!(lines[build + 4..lines.len() - 2].join("\n") + "\n", text);
+    assert_eq!(
+        lines[lines.len() - 2],
+        "##[error]Process completed with exit code 101."
+    );
+}
+
+#[test]
+fn ci_log_without_errors() {
+    let ci_log = ExportOptions {
+        ci_log: true,
+        ..options(Format::Text)
+    };
+    let warning = &errs()[1..];
+    let log = String::from_utf8(write(&ci_log, warning)).unwrap();
+    let lines = log_lines(&log);
+
+    assert!(lines
+        .last()
+        .unwrap()
+        .starts_with("Run cargo build completed in "));
+    assert!(!lines.iter().any(|line| line.starts_with("##[error]")));
+}
+
+#[test]
+fn ui_stderr() {
+    insta::assert_snapshot!(export(&options(Format::UiStderr)));
+}
+
+#[test]
+fn sarif() {
+    insta::assert_snapshot!(export(&options(Format::Sarif)));
+}
+
+#[test]
+fn prose() {
+    insta::assert_snapshot!(export(&options(Format::Prose)));
+}
+
+/// A plugin whose output is its input: `alloc` gives the offset 1024 of its
+/// memory, and `render` returns the pointer and the length it was given.
+#[cfg(feature = "plugins")]
+const ECHO_PLUGIN: &[u8] = &[
+    0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, // magic and version
+    0x01, 0x0c, 0x02, 0x60, 0x01, 0x7f, 0x01, 0x7f, // (i32) -> i32
+    0x60, 0x02, 0x7f, 0x7f, 0x01, 0x7e, // (i32, i32) -> i64
+    0x03, 0x03, 0x02, 0x00, 0x01, // two functions of these types
+    0x05, 0x03, 0x01, 0x00, 0x01, // one page of memory
+    0x07, 0x1b, 0x03, // exports
+    0x06, b'm', b'e', b'm', b'o', b'r', b'y', 0x02, 0x00, //
+    0x05, b'a', b'l', b'l', b'o', b'c', 0x00, 0x00, //
+    0x06, b'r', b'e', b'n', b'd', b'e', b'r', 0x00, 0x01, //
+    0x0a, 0x14, 0x02, // code
+    0x05, 0x00, 0x41, 0x80, 0x08, 0x0b, // i32.const 1024
+    0x0c, 0x00, 0x20, 0x00, 0xad, 0x42, 0x20, 0x86, // ptr << 32
+    0x20, 0x01, 0xad, 0x84, 0x0b, // | len
+];
+
+#[cfg(feature = "plugins")]
+#[test]
+fn plugin() {
+    let config = std::env::temp_dir().join(format!("roost-exports-{}", std::process::id()));
+    let dir = config.join("roost").join("plugins");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("echo.wasm"), ECHO_PLUGIN).unwrap();
+    std::env::set_var("XDG_CONFIG_HOME", &config);
+
+    let output = export(&options(Format::Plugin("echo".to_owned())));
+    std::fs::remove_dir_all(&config).unwrap();
+    let input: serde_json::Value = serde_json::from_str(&output).unwrap();
+
+    assert_eq!(input["diagnostics"][0]["summary"], "mismatched types");
+    assert_eq!(input["diagnostics"][1]["severity"], "warning");
+    assert!(input["rendered"]
+        .as_str()
+        .unwrap()
+        .starts_with("error[E0308]: mismatched types\n"));
+}
@@ -0,0 +1,117 @@
+//! The text of a diagnostic in each style and engine, compared to the
+//! snapshots of `tests/snapshots/`. `INSTA_UPDATE=always cargo test`
+//! rewrites them after a change meant to show.
+
+use roost::{
+    ansi,
+    render::{self, Emitter, Engine, Hyperlinks, RenderOptions},
+    Diagnostic, Label, Severity,
+};
+
+fn mismatch() -> Diagnostic<'static> {
+    Diagnostic::builder()
+        .summary("mismatched types")
+        .line("    let x: u8 = \"a\";")
+        .span(16..19)
+        .message("expected `u8`, found `&str`")
+        .label(Label {
+            range: 11..13,
+            message: "expected due to this".to_owned(),
+            color: None,
+        })
+        .lineno(4)
+        .path("src/main.rs")
+        .code(308)
+        .context_before(vec!["fn main() {".to_owned(), "    // a byte".to_owned()])
+        .context_after(vec!["}".to_owned()])
+        .fixit("'a'")
+        .note("`u8` is a number")
+        .help("use a byte literal")
+        .build()
+        .unwrap()
+}
+
+/// The diagnostic as printed by the style and engine, without colors and
+/// links, within 80 columns.
+fn render(err: &Diagnostic, style: Emitter, engine: Engine) -> String {
+    let options = RenderOptions {
+        style,
+        engine,
+        hyperlinks: Hyperlinks::Never,
+        max_width: Some(80),
+        ..Default::default()
+    };
+
+    ansi::strip(&render::render(&options, err))
+}
+
+#[test]
+fn rustc() {
+    insta::assert_snapshot!(render(&mismatch(), Emitter::Rustc, Engine::Roost));
+}
+
+#[test]
+fn gcc() {
+    insta::assert_snapshot!(render(&mismatch(), Emitter::Gcc, Engine::Roost));
+}
+
+#[test]
+fn clang() {
+    insta::assert_snapshot!(render(&mismatch(), Emitter::Clang, Engine::Roost));
+}
+
+#[test]
+fn python() {
+    insta::assert_snapshot!(render(&mismatch(), Emitter::Python, Engine::Roost));
+}
+
+#[test]
+fn tsc() {
+    insta::assert_snapshot!(render(&mismatch(), Emitter::Tsc, Engine::Roost));
+}
+
+#[test]
+fn annotate_snippets() {
+    insta::assert_snapshot!(render(
+        &mismatch(),
+        Emitter::Rustc,
+        Engine::AnnotateSnippets
+    ));
+}
+
+#[test]
+fn ariadne() {
+    insta::assert_snapshot!(render(&mismatch(), Emitter::Rustc, Engine::Ariadne));
+}
+
+#[test]
+fn warning() {
+    let err = Diagnostic::builder()
+        .severity(Severity::Warning)
+        .summary("unused variable: `日本`")
+        .line("let 日本 = 1;")
+        .span(4..10)
+        .message("help: prefix it with an underscore")
+        .build()
+        .unwrap();
+
+    insta::assert_snapshot!(render(&err, Emitter::Rustc, Engine::Roost));
+}
+
+#[test]
+fn plain_within_width() {
+    insta::assert_snapshot!(render::render_plain(&mismatch(), 40));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_round_trip() {
+    let err = mismatch();
+    let json = serde_json::to_string(&err).unwrap();
+    let read: Diagnostic = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(
+        render::render_plain(&read, 80),
+        render::render_plain(&err, 80)
+    );
+}